@@ -1,5 +1,12 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     built::write_built_file().expect("Failed to acquire build-time information");
 
+    // Use a vendored `protoc`, so that building the CLI does not require one to
+    // be installed.
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure()
+        .build_server(false)
+        .compile(&["proto/dpm_agent.proto"], &["proto"])?;
+
     Ok(())
 }
@@ -0,0 +1,75 @@
+//! Client for `dpm-agent`, the service that compiles and executes queries on
+//! datasets' sources.
+
+use anyhow::{Context, Result};
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use url::Url;
+
+use crate::{built_info, env};
+
+#[allow(clippy::enum_variant_names)]
+pub mod proto {
+    tonic::include_proto!("dpm_agent");
+}
+
+use proto::dpm_agent_client::DpmAgentClient;
+use proto::{client_version, ClientVersion, Query, QueryResult};
+
+/// Returns the version info that identifies this CLI as a query's client.
+pub fn client_version(dataset_version: &str) -> ClientVersion {
+    ClientVersion {
+        client: client_version::Client::Dpm.into(),
+        dataset_version: dataset_version.into(),
+        code_version: built_info::PKG_VERSION.into(),
+    }
+}
+
+pub struct Client {
+    client: DpmAgentClient<Channel>,
+    dpm_auth_token: MetadataValue<tonic::metadata::Ascii>,
+}
+
+impl Client {
+    /// Returns a client for the `dpm-agent` at `url`, e.g.
+    /// "http://localhost:50051" or "https://agent.dpm.sh". TLS is used if and
+    /// only if the scheme is `https`. The connection is established on first
+    /// use.
+    pub fn new(url: &Url, dpm_auth_token: &str) -> Result<Self> {
+        let mut endpoint = Endpoint::from_shared(url.to_string())
+            .with_context(|| format!("invalid dpm-agent address: {url}"))?;
+        if url.scheme() == "https" {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+        }
+        let dpm_auth_token = MetadataValue::try_from(dpm_auth_token)
+            .context("authentication token is not a valid header value")?;
+
+        Ok(Self {
+            client: DpmAgentClient::new(endpoint.connect_lazy()),
+            dpm_auth_token,
+        })
+    }
+
+    /// Returns a client for the `dpm-agent` named by DPM_AGENT_URL,
+    /// authenticated with `dpm_auth_token`.
+    pub fn from_env(dpm_auth_token: &str) -> Result<Self> {
+        Self::new(&env::dpm_agent_url()?, dpm_auth_token)
+    }
+
+    /// Sends the query to `dpm-agent`, and returns its result. If the query
+    /// is a dry run, the result holds only the compiled query string.
+    pub async fn execute_query(&self, query: Query) -> Result<QueryResult> {
+        let mut request = tonic::Request::new(query);
+        request
+            .metadata_mut()
+            .insert("dpm-auth-token", self.dpm_auth_token.clone());
+        let response = self
+            .client
+            .clone()
+            .execute_query(request)
+            .await
+            .map_err(|status| anyhow::anyhow!("{}", status.message()))
+            .context("dpm-agent failed to execute query")?;
+        Ok(response.into_inner())
+    }
+}
@@ -7,14 +7,54 @@ use semver::Version;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::command::snowflake;
+use crate::command::{postgres, snowflake};
 use crate::descriptor::{Name, Table, TableSource};
 use crate::env;
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SnowflakeAuthenticationMethod<'a> {
-    Password { password: &'a str },
+    Password {
+        password: &'a str,
+    },
+    KeyPair {
+        #[serde(rename = "private_key")]
+        private_key_b64: String,
+        passphrase: Option<&'a str>,
+    },
+    #[serde(rename = "oauth")]
+    OAuth {
+        client_id: &'a str,
+        client_secret: &'a str,
+        refresh_token: &'a str,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostgresAuthenticationMethod<'a> {
+    Password {
+        password: &'a str,
+    },
+    ClientCertificate {
+        #[serde(rename = "client_certificate")]
+        client_certificate_b64: String,
+        #[serde(rename = "client_key")]
+        client_key_b64: String,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DatabricksAuthenticationMethod<'a> {
+    PersonalAccessToken {
+        token: &'a str,
+    },
+    #[serde(rename = "oauth_m2m")]
+    OAuthM2M {
+        client_id: &'a str,
+        client_secret: &'a str,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -36,6 +76,20 @@ pub enum CreateSourceParameters<'a> {
         authentication_method: SnowflakeAuthenticationMethod<'a>,
         staging_database: &'a str,
     },
+    Postgres {
+        host: &'a str,
+        port: u16,
+        database: &'a str,
+        user: &'a str,
+        ssl_mode: postgres::SslMode,
+        authentication_method: PostgresAuthenticationMethod<'a>,
+    },
+    Databricks {
+        host: &'a str,
+        http_path: &'a str,
+        catalog: &'a str,
+        authentication_method: DatabricksAuthenticationMethod<'a>,
+    },
 }
 
 #[derive(Deserialize, Serialize)]
@@ -53,6 +107,17 @@ pub enum GetSourceParameters {
         database: String,
         user: String,
     },
+    Postgres {
+        host: String,
+        port: u16,
+        database: String,
+        user: String,
+    },
+    Databricks {
+        host: String,
+        http_path: String,
+        catalog: String,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -245,6 +310,8 @@ impl Source {
         match self.source_parameters {
             GetSourceParameters::BigQuery { .. } => "bigquery".into(),
             GetSourceParameters::Snowflake { .. } => "snowflake".into(),
+            GetSourceParameters::Postgres { .. } => "postgres".into(),
+            GetSourceParameters::Databricks { .. } => "databricks".into(),
         }
     }
 }
@@ -1,7 +1,10 @@
 mod csharp;
 mod generator;
+mod go;
+mod java;
 mod nodejs;
 mod python;
+mod rust;
 
 use dialoguer::Confirm;
 use std::collections::HashSet;
@@ -10,11 +13,16 @@ use std::path::{Path, PathBuf};
 use std::process;
 
 use clap::Subcommand;
+use convert_case::{Case, Casing};
+use regress::Regex;
 
 pub use csharp::Csharp;
-pub use generator::{Generator, ItemRef};
+pub use generator::{Changelog, Generator, ItemRef};
+pub use go::Go;
+pub use java::Java;
 pub use nodejs::NodeJs;
 pub use python::Python;
+pub use rust::Rust;
 
 use crate::api::GetDatasetVersionResponse;
 
@@ -32,9 +40,34 @@ pub enum Target {
 
     /// Build a C# data package
     Csharp,
+
+    /// Build a Rust data package
+    Rust,
+
+    /// Build a Go data package
+    Go,
+
+    /// Build a Java data package
+    Java {
+        /// Emit Kotlin table definitions, built with Gradle, instead of Java
+        #[arg(long)]
+        kotlin: bool,
+    },
 }
 
 impl Target {
+    /// The name of the target, as given on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::NodeJs { .. } => "nodejs",
+            Target::Python => "python",
+            Target::Csharp => "csharp",
+            Target::Rust => "rust",
+            Target::Go => "go",
+            Target::Java { .. } => "java",
+        }
+    }
+
     pub fn generator_for_package<'a>(
         &self,
         dp: &'a GetDatasetVersionResponse,
@@ -43,12 +76,34 @@ impl Target {
             Target::NodeJs { scope } => Box::new(NodeJs::new(dp, scope.clone())),
             Target::Python {} => Box::new(Python::new(dp)),
             Target::Csharp {} => Box::new(Csharp::new(dp)),
+            Target::Rust {} => Box::new(Rust::new(dp)),
+            Target::Go {} => Box::new(Go::new(dp)),
+            Target::Java { kotlin } => Box::new(Java::new(dp, *kotlin)),
         };
         generator
     }
 }
 
-fn write<C: AsRef<[u8]>>(target: &Path, content: C, msg_snippet: String) {
+/// Returns the identifiers that generators derive from a table's name: its
+/// class, module, accessor and package-directory names. Two tables whose
+/// identifiers coincide would overwrite each other in a package.
+pub fn table_identifiers(name: &str) -> [String; 4] {
+    let re = Regex::new(r"[a-zA-Z0-9_\-\ ]+").unwrap();
+    let name = re
+        .find_iter(name)
+        .map(|m| &name[m.range()])
+        .collect::<Vec<&str>>()
+        .join("");
+    [
+        name.to_case(Case::Pascal),
+        name.to_case(Case::Snake),
+        name.to_case(Case::Camel),
+        name.to_case(Case::Kebab),
+    ]
+}
+
+/// Writes `content` to `target`, returning `target`.
+fn write<C: AsRef<[u8]>>(target: &Path, content: C, msg_snippet: String) -> PathBuf {
     let parent = target.parent().unwrap();
     let create_dir_res = fs::create_dir_all(parent);
     if create_dir_res.is_err() {
@@ -59,12 +114,13 @@ fn write<C: AsRef<[u8]>>(target: &Path, content: C, msg_snippet: String) {
         );
     }
     match fs::write(target, content) {
-        Ok(_) => println!("Wrote {msg_snippet} to {:?}", target),
+        Ok(_) => eprintln!("Wrote {msg_snippet} to {:?}", target),
         Err(e) => panic!(
             "Failed to write {msg_snippet} to {:?}, with error {e}",
             target
         ),
     }
+    target.to_owned()
 }
 
 fn check_package_existence(path: &PathBuf, assume_yes: bool) {
@@ -78,9 +134,9 @@ fn check_package_existence(path: &PathBuf, assume_yes: bool) {
                 .interact()
                 .unwrap()
         {
-            println!("Overwriting");
+            eprintln!("Overwriting");
         } else {
-            println!("Package generation cancelled");
+            eprintln!("Package generation cancelled");
             process::exit(1);
         }
     }
@@ -89,15 +145,20 @@ fn check_package_existence(path: &PathBuf, assume_yes: bool) {
 /// Outputs all static assets to the output directory. These assets are
 /// typically code that defines basic types, such as `Field`, `Table`, which are
 /// used to define the specific resources present in the dataset.json.
-fn output_static_assets(generator: &dyn Generator, output: &Path) {
-    for static_asset in generator.static_assets() {
-        let target = output.join(static_asset.path.as_path());
-        write(
-            &target,
-            &static_asset.content.data,
-            format!("asset {:?}", static_asset.path),
-        );
-    }
+/// Returns the paths of the assets.
+fn output_static_assets(generator: &dyn Generator, output: &Path) -> Vec<PathBuf> {
+    generator
+        .static_assets()
+        .iter()
+        .map(|static_asset| {
+            let target = output.join(static_asset.path.as_path());
+            write(
+                &target,
+                &static_asset.content.data,
+                format!("asset {:?}", static_asset.path),
+            )
+        })
+        .collect()
 }
 
 /// Outputs all generated table definitions, one per resource, to the output directory.
@@ -135,47 +196,90 @@ fn output_table_definitions(generator: &dyn Generator, output: &Path) -> Vec<Ite
 }
 
 /// Outputs the manifest for the generated data package code.
-fn output_manifest(generator: &dyn Generator, output: &Path) {
-    let manifest = generator.manifest();
+fn output_manifest(generator: &dyn Generator, changelog: &Changelog, output: &Path) -> PathBuf {
+    let manifest = generator.manifest(changelog);
     let target = output.join(manifest.file_name);
-    write(&target, manifest.description, "manifest".to_string());
+    write(&target, manifest.description, "manifest".to_string())
+}
+
+/// Outputs the dataset's changelog.
+fn output_changelog(generator: &dyn Generator, changelog: &Changelog, output: &Path) -> PathBuf {
+    let asset = generator.changelog(changelog);
+    let target = output.join(asset.path.as_path());
+    write(&target, asset.content, "changelog".to_string())
 }
 
 /// Outputs a file containing the code version of the data package.
-fn output_version(generator: &dyn Generator, output: &Path) {
+fn output_version(generator: &dyn Generator, output: &Path) -> PathBuf {
     let version = generator.version();
     let target = output.join(version.path.as_path());
-    write(&target, version.content, "version".to_string());
+    write(&target, version.content, "version".to_string())
 }
 
 /// Outputs the entry point for the generated data package code. E.g., for
 /// Node.js this is the `index.ts` file containing the table exports.
-fn output_entry_point(generator: &dyn Generator, table_definitions: Vec<ItemRef>, output: &Path) {
+fn output_entry_point(
+    generator: &dyn Generator,
+    table_definitions: Vec<ItemRef>,
+    output: &Path,
+) -> Option<PathBuf> {
     let entry_code = generator.entry_code(table_definitions);
     if entry_code.content.is_empty() {
-        println!("Skipping empty entry point");
-        return;
+        eprintln!("Skipping empty entry point");
+        return None;
     }
 
     let target = output.join(entry_code.path.as_path());
-    write(&target, entry_code.content, "entry code".to_string());
+    Some(write(&target, entry_code.content, "entry code".to_string()))
+}
+
+/// A generated data package.
+pub struct Package {
+    /// Directory the package was generated in.
+    pub root: PathBuf,
+    /// Files written to the directory, before the package was built.
+    pub files: Vec<PathBuf>,
 }
 
 pub fn generate_package(
     dp: &GetDatasetVersionResponse,
+    changelog: &Changelog,
     target: &Target,
     output: &Path,
     assume_yes: bool,
-) {
-    println!("Going to generate a data package in {:?}", target);
+) -> Package {
+    eprintln!("Going to generate a data package in {:?}", target);
     let generator = target.generator_for_package(dp);
 
     let out_root_dir = output.join(generator.root_dir());
     check_package_existence(&out_root_dir, assume_yes);
-    output_static_assets(generator.as_ref(), &out_root_dir);
+    let mut files = output_static_assets(generator.as_ref(), &out_root_dir);
     let table_definitions = output_table_definitions(generator.as_ref(), &out_root_dir);
-    output_version(generator.as_ref(), &out_root_dir);
-    output_entry_point(generator.as_ref(), table_definitions, &out_root_dir);
-    output_manifest(generator.as_ref(), &out_root_dir);
+    files.extend(
+        table_definitions
+            .iter()
+            .map(|item_ref| out_root_dir.join(item_ref.path.as_path())),
+    );
+    files.push(output_version(generator.as_ref(), &out_root_dir));
+    files.extend(output_entry_point(
+        generator.as_ref(),
+        table_definitions,
+        &out_root_dir,
+    ));
+    files.push(output_changelog(
+        generator.as_ref(),
+        changelog,
+        &out_root_dir,
+    ));
+    files.push(output_manifest(
+        generator.as_ref(),
+        changelog,
+        &out_root_dir,
+    ));
     generator.build_package(&out_root_dir);
+
+    Package {
+        root: out_root_dir,
+        files,
+    }
 }
@@ -1,6 +1,9 @@
 //! Csharp code generator.
 
-use super::generator::{exec_cmd, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset};
+use super::generator::{
+    exec_cmd, Changelog, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset,
+    CHANGELOG_FILE_NAME,
+};
 use crate::api::GetDatasetVersionResponse;
 use crate::descriptor::{FieldType, Table, TableSchema, TableSchemaField};
 use convert_case::{Case, Casing};
@@ -39,12 +42,16 @@ struct FieldData {
     field_type_decl: String,
     field_init: String,
     field_ref: String,
+    row_param: String,
+    param_doc: String,
 }
 
 struct FieldSnippets {
     fields_types: String,
     fields_inits: String,
     fields_list: String,
+    row_fields: String,
+    params_doc: String,
 }
 
 /// Clean the name to retain only alphanumeric, underscore, hyphen, and space characters.
@@ -56,30 +63,66 @@ fn clean_name(name: &str) -> String {
         .join("")
 }
 
+/// Returns the description, trimmed and escaped for use in an XML doc comment,
+/// or `None` if it is empty.
+fn xml_doc_text(description: Option<&str>) -> Option<String> {
+    match description.map(str::trim) {
+        Some(d) if !d.is_empty() => Some(
+            d.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        ),
+        _ => None,
+    }
+}
+
+/// Returns an XML doc comment summarizing a declaration with the description,
+/// followed by a newline and `indent` so that it can prefix the declaration.
+/// Returns an empty string if there is no description.
+fn doc_summary(description: Option<&str>, indent: &str) -> String {
+    match xml_doc_text(description) {
+        Some(d) => {
+            let lines = d
+                .lines()
+                .map(|line| format!("{indent}/// {line}").trim_end().to_string())
+                .collect::<Vec<String>>()
+                .join("\n");
+            format!("/// <summary>\n{lines}\n{indent}/// </summary>\n{indent}")
+        }
+        None => String::new(),
+    }
+}
+
 static FIELD_INIT_TEMPLATE_NAME: &str = "field_init";
 static FIELD_INIT_TEMPLATE: &str = "{field_ref}: new {field_type}(\"{field_name}\")";
 
 static TABLE_CLASS_TEMPLATE_NAME: &str = "table";
 static TABLE_CLASS_TEMPLATE: &str = "
 using Dpm;
+using Newtonsoft.Json;
 
 namespace {namespace} \\{
-  public class {class_name} \\{
+  {class_doc}public class {class_name} \\{
     // Fields.
-    public record FieldsRecord(
+    {params_doc}public record FieldsRecord(
         {fields_types}
     );
     public static FieldsRecord Fields = new FieldsRecord(
         {fields_inits}
     );
 
-    private Table table_;
+    // A row of `{resource_name}`, as returned by executing a query on the table.
+    {params_doc}public record Row(
+        {row_fields}
+    );
+
+    private Table<Row> table_;
 
     // Singleton.
     private static readonly Lazy<{class_name}> lazy = new Lazy<{class_name}>(() => new {class_name}());
 
     private {class_name}() \\{
-      this.table_ = new Table(
+      this.table_ = new Table<Row>(
         packageId: \"{dataset_id}\",
         datasetName: \"{dataset_name}\",
         datasetVersion: \"{dataset_version}\",
@@ -92,11 +135,11 @@ namespace {namespace} \\{
 
     private static {class_name} Instance \\{ get \\{ return lazy.Value; } }
 
-    public static Table Table() \\{
+    public static Table<Row> Table() \\{
       return {class_name}.Instance.table_;
     }
 
-    public static Table Select(params FieldExpr[] selection) \\{
+    public static Table<Row> Select(params FieldExpr[] selection) \\{
       return {class_name}.Table().Select(selection);
     }
   };
@@ -169,9 +212,7 @@ impl<'a> Csharp<'a> {
             FieldType::Date => String::from("DateField"),
             FieldType::DateTime => String::from("DateTimeField"),
             FieldType::Time => String::from("TimeField"),
-            FieldType::Array => {
-                unreachable!("Unsupported field type {:?}, please report a bug!", field)
-            }
+            FieldType::Array => String::from("ArrayField<object>"),
         };
         let field_ref = self.variable_name(&field_name);
 
@@ -189,15 +230,37 @@ impl<'a> Csharp<'a> {
         };
 
         let field_type_decl = format!("{field_type} {field_ref}");
+        let row_type = match field.type_ {
+            FieldType::Number => "double",
+            FieldType::Boolean => "bool",
+            FieldType::String => "string",
+            FieldType::Date | FieldType::DateTime => "DateTime",
+            FieldType::Time => "TimeSpan",
+            FieldType::Array => "object[]",
+        };
+        // Fields that are not required may be null in query results.
+        let nullable = if field.is_required() { "" } else { "?" };
+        let row_param =
+            format!("[property: JsonProperty(\"{field_name}\")] {row_type}{nullable} {field_ref}");
         let field_init = match self.tt.render(FIELD_INIT_TEMPLATE_NAME, &context) {
             Ok(result) => result,
             Err(e) => panic!("Failed to render field defs with error {:?}", e),
         };
 
+        let param_doc = match xml_doc_text(field.description.as_deref()) {
+            Some(d) => format!(
+                "/// <param name=\"{field_ref}\">{}</param>\n    ",
+                d.lines().collect::<Vec<&str>>().join("\n    /// ")
+            ),
+            None => String::new(),
+        };
+
         FieldData {
             field_type_decl,
             field_init,
             field_ref,
+            row_param,
+            param_doc,
         }
     }
 
@@ -224,10 +287,21 @@ impl<'a> Csharp<'a> {
             .map(|fd| format!("Fields.{0}", fd.field_ref))
             .collect::<Vec<String>>()
             .join(",\n\t");
+        let row_fields = fields_data
+            .iter()
+            .map(|fd| fd.row_param.as_str())
+            .collect::<Vec<&str>>()
+            .join(",\n\t");
+        let params_doc = fields_data
+            .iter()
+            .map(|fd| fd.param_doc.as_str())
+            .collect::<String>();
         FieldSnippets {
             fields_types,
             fields_inits,
             fields_list,
+            row_fields,
+            params_doc,
         }
     }
 }
@@ -250,11 +324,14 @@ impl Generator for Csharp<'_> {
             fields_inits,
             fields_list,
             fields_types,
+            row_fields,
+            params_doc,
         } = self.gen_field_defs(fields);
 
         #[derive(Serialize)]
         struct Context {
             namespace: String,
+            class_doc: String,
             dataset_id: String,
             dataset_name: String,
             dataset_version: String,
@@ -263,9 +340,12 @@ impl Generator for Csharp<'_> {
             fields_types: String,
             fields_inits: String,
             fields_list: String,
+            row_fields: String,
+            params_doc: String,
         }
         let context = Context {
             namespace,
+            class_doc: doc_summary(r.description.as_deref(), "  "),
             dataset_id,
             dataset_name,
             dataset_version: dataset.version.version.to_string(),
@@ -274,6 +354,8 @@ impl Generator for Csharp<'_> {
             fields_types,
             fields_inits,
             fields_list,
+            row_fields,
+            params_doc,
         };
 
         let code = match self.tt.render(TABLE_CLASS_TEMPLATE_NAME, &context) {
@@ -359,13 +441,17 @@ impl Generator for Csharp<'_> {
         clean_name(name).to_case(Case::Pascal)
     }
 
-    fn manifest(&self) -> Manifest {
+    fn manifest(&self, changelog: &Changelog) -> Manifest {
         let dataset = self.dataset();
         let pkg_name: String = self.dataset_name(&dataset.name);
         let version = package_version(&dataset.version.version);
 
         let src_dir = self.source_dir();
         let proj_file_name = format!("{pkg_name}.csproj");
+        let release_notes = changelog
+            .release_notes
+            .replace('&', "&amp;")
+            .replace('<', "&lt;");
         let path = Path::new(&src_dir).join(proj_file_name);
         let contents = format!("
 <Project Sdk=\"Microsoft.NET.Sdk\">
@@ -375,8 +461,13 @@ impl Generator for Csharp<'_> {
     <ImplicitUsings>enable</ImplicitUsings>
     <Nullable>enable</Nullable>
     <Version>{version}</Version>
+    <PackageReleaseNotes>{release_notes}</PackageReleaseNotes>
   </PropertyGroup>
 
+  <ItemGroup>
+    <None Include=\"..\\{CHANGELOG_FILE_NAME}\" Pack=\"true\" PackagePath=\"\\\" />
+  </ItemGroup>
+
   <ItemGroup>
     <PackageReference Include=\"Google.Protobuf\" Version=\"3.24.1\" />
     <PackageReference Include=\"Grpc.Net.Client\" Version=\"2.56.0-pre2\" />
@@ -408,7 +499,7 @@ impl Generator for Csharp<'_> {
     /// Builds the generated package. E.g., for the `C#` target, builds the C# package using
     /// the recommended C# build tools: `dotnet build`.
     fn build_package(&self, path: &Path) {
-        println!("Building C# package");
+        eprintln!("Building C# package");
         let dataset = self.dataset();
         let pkg_name: String = self.dataset_name(&dataset.name);
 
@@ -8,6 +8,9 @@ use std::process::Command;
 use crate::api::GetDatasetVersionResponse;
 use crate::descriptor::Table;
 
+/// The name of the changelog file in a package's root directory.
+pub const CHANGELOG_FILE_NAME: &str = "CHANGELOG.md";
+
 /// ItemRef stores the name of a generated item, such as a Class or variable,
 /// and the filename that contains its definition.
 #[derive(Serialize)]
@@ -44,6 +47,14 @@ pub struct DynamicAsset {
     pub content: String,
 }
 
+/// The changelog of a dataset, shipped in its packages.
+pub struct Changelog {
+    /// The changes in every version, newest first, as a Markdown document.
+    pub markdown: String,
+    /// The changes in the version being built, for package metadata.
+    pub release_notes: String,
+}
+
 /// Executes a command with the given path as the working directory.
 pub fn exec_cmd(name: &str, path: &Path, cmd: &str, args: &[&str]) {
     let mut cmd = Command::new(cmd);
@@ -89,8 +100,18 @@ pub trait Generator {
     /// Returns a package name in the language, given a name.
     fn dataset_name(&self, name: &str) -> String;
 
-    /// Returns a manifest used by the language.
-    fn manifest(&self) -> Manifest;
+    /// Returns a manifest used by the language. Where the language's manifest
+    /// has a place for them, it records the changelog's release notes.
+    fn manifest(&self, changelog: &Changelog) -> Manifest;
+
+    /// Returns a dynamic asset that contains the dataset's changelog.
+    fn changelog(&self, changelog: &Changelog) -> DynamicAsset {
+        DynamicAsset {
+            path: Box::new(PathBuf::from(CHANGELOG_FILE_NAME)),
+            name: String::from("CHANGELOG"),
+            content: changelog.markdown.clone(),
+        }
+    }
 
     /// Returns entry code definition for the target. E.g. for `Node.js`
     /// returns the contents of an `index.ts` file.
@@ -0,0 +1,530 @@
+//! Go code generator.
+
+use super::generator::{
+    exec_cmd, Changelog, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset,
+};
+use crate::api::GetDatasetVersionResponse;
+use crate::descriptor::{FieldType, Table, TableSchema, TableSchemaField};
+use convert_case::{Case, Casing};
+use regress::Regex;
+use rust_embed::RustEmbed;
+use semver::Version;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tinytemplate::TinyTemplate;
+
+pub struct Go<'a> {
+    pub dataset: &'a GetDatasetVersionResponse,
+    tt: TinyTemplate<'a>,
+}
+
+const GO_VERSION: &str = "0.1.0";
+
+#[derive(RustEmbed)]
+#[folder = "static/go/"]
+#[exclude = "go.*"] // The module file will be written as a manifest.
+#[exclude = "dpm/version.go"] // Written as the version.
+#[exclude = "dpm/*.pb.go"] // Generated by `go generate` when building the package.
+#[exclude = "proto/*"] // RustEmbed fails on symbolic links; handled by embedding original proto below.
+#[exclude = ".gitignore"]
+struct Asset;
+
+// Embed the proto files available at the top-level proto directory.
+#[derive(RustEmbed)]
+#[folder = "proto"]
+#[prefix = "proto/"]
+struct ProtoAsset;
+
+// Helpers.
+struct FieldData {
+    field_type_decl: String,
+    field_init: String,
+    field_ref: String,
+    row_field_decl: String,
+}
+
+struct FieldSnippets {
+    fields_types: String,
+    fields_inits: String,
+    fields_list: String,
+    row_fields: String,
+}
+
+/// Clean the name to retain only alphanumeric, underscore, hyphen, and space characters.
+fn clean_name(name: &str) -> String {
+    let re = Regex::new(r"[a-zA-Z0-9_\-\ ]+").unwrap();
+    re.find_iter(name)
+        .map(|m| &name[m.range()])
+        .collect::<Vec<&str>>()
+        .join("")
+}
+
+/// Returns `name` as an exported Go identifier. Names that do not start with a
+/// letter are prefixed with an `X`, as `protoc-gen-go` does.
+fn exported_identifier(name: &str) -> String {
+    let name = clean_name(name).to_case(Case::Pascal);
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("X{name}")
+    }
+}
+
+/// Returns comment lines holding the description, each followed by a newline
+/// and `indent` so that they can prefix a declaration. Returns an empty string
+/// if there is no description.
+fn doc_comment(description: Option<&str>, indent: &str) -> String {
+    match description.map(str::trim) {
+        Some(d) if !d.is_empty() => d
+            .lines()
+            .map(|line| format!("// {line}").trim_end().to_string() + "\n" + indent)
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+static FIELD_INIT_TEMPLATE_NAME: &str = "field_init";
+static FIELD_INIT_TEMPLATE: &str = "{field_ref}: dpm.{field_constructor}(\"{field_name}\"),";
+
+static TABLE_TYPE_TEMPLATE_NAME: &str = "table";
+static TABLE_TYPE_TEMPLATE: &str = "package tables
+
+import \"{module}/dpm\"
+
+// {class_name}Fields are the fields of the `{resource_name}` table.
+type {class_name}Fields struct \\{
+	{fields_types}
+}
+
+// {class_name}Row is a row of the `{resource_name}` table, as returned by
+// `dpm.ExecuteAs[{class_name}Row]`.
+type {class_name}Row struct \\{
+	{row_fields}
+}
+
+// {class_name}Table is the `{resource_name}` table.
+{table_doc}type {class_name}Table struct \\{
+	Fields {class_name}Fields
+	table  *dpm.Table
+}
+
+// {class_name} is the entry point to querying the `{resource_name}` table.
+var {class_name} = new{class_name}()
+
+func new{class_name}() *{class_name}Table \\{
+	fields := {class_name}Fields\\{
+		{fields_inits}
+	}
+	return &{class_name}Table\\{
+		Fields: fields,
+		table: dpm.NewTable(
+			\"{dataset_id}\",
+			\"{dataset_name}\",
+			\"{dataset_version}\",
+			\"{resource_name}\",
+			{fields_list}
+		),
+	}
+}
+
+// Table returns the table, with all fields selected.
+func (t *{class_name}Table) Table() *dpm.Table \\{
+	return t.table
+}
+
+// Select returns the table with the given fields selected.
+func (t *{class_name}Table) Select(selection ...dpm.Selectable) *dpm.Table \\{
+	return t.table.Select(selection...)
+}
+";
+
+static VERSION_TEMPLATE_NAME: &str = "version";
+static VERSION_TEMPLATE: &str = "package dpm
+
+// CodeVersion is the version of the generated code.
+const CodeVersion = \"{code_version}\"
+";
+
+/// Returns a version string for a Go module:
+///   dataset-version "-" code-version (".draft." draft-number)?
+/// Go modules are versioned by their VCS tags, so this is used in the package's
+/// directory name, and as the suggested tag.
+/// See: https://go.dev/doc/modules/version-numbers
+fn package_version(v: &Version) -> String {
+    if v.pre.is_empty() {
+        format!("{}-{}", v, GO_VERSION)
+    } else {
+        format!(
+            "{}.{}.{}-{}.{}",
+            v.major,
+            v.minor,
+            v.patch,
+            GO_VERSION,
+            // Assume this has form "draft.<number>", and so can be
+            // joined with the rest of the string via a ".".
+            v.pre.as_str()
+        )
+    }
+}
+
+impl<'a> Go<'a> {
+    pub fn new(dp: &'a GetDatasetVersionResponse) -> Self {
+        let mut tt = TinyTemplate::new();
+        if tt
+            .add_template(FIELD_INIT_TEMPLATE_NAME, FIELD_INIT_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", FIELD_INIT_TEMPLATE_NAME);
+        }
+        if tt
+            .add_template(TABLE_TYPE_TEMPLATE_NAME, TABLE_TYPE_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", TABLE_TYPE_TEMPLATE_NAME);
+        }
+        if tt
+            .add_template(VERSION_TEMPLATE_NAME, VERSION_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", VERSION_TEMPLATE_NAME);
+        }
+        // Do not perform HTML escaping.
+        tt.set_default_formatter(&tinytemplate::format_unescaped);
+
+        Self { dataset: dp, tt }
+    }
+
+    /// Returns a field's declaration, initialization, and reference.
+    fn gen_field(&self, field: &TableSchemaField) -> FieldData {
+        let field_name = field.name.to_owned();
+        let (field_type, field_constructor) = match field.type_ {
+            FieldType::Number => ("*dpm.Field[float64]", "NewField[float64]"),
+            FieldType::Boolean => ("*dpm.Field[bool]", "NewField[bool]"),
+            FieldType::String => ("*dpm.StringField", "NewStringField"),
+            FieldType::Date => ("*dpm.DateField", "NewDateField"),
+            FieldType::DateTime => ("*dpm.DateTimeField", "NewDateTimeField"),
+            FieldType::Time => ("*dpm.TimeField", "NewTimeField"),
+            FieldType::Array => ("*dpm.ArrayField", "NewArrayField"),
+        };
+        let field_ref = self.variable_name(&field_name);
+
+        #[derive(Serialize)]
+        struct Context<'a> {
+            field_ref: &'a str,
+            field_constructor: &'a str,
+            field_name: &'a str,
+        }
+
+        let context = Context {
+            field_ref: &field_ref,
+            field_constructor,
+            field_name: &field_name,
+        };
+
+        let doc = doc_comment(field.description.as_deref(), "\t");
+        let field_type_decl = format!("{doc}{field_ref} {field_type}");
+        let row_type = match field.type_ {
+            FieldType::Number => "float64",
+            FieldType::Boolean => "bool",
+            FieldType::String | FieldType::Date | FieldType::DateTime | FieldType::Time => "string",
+            FieldType::Array => "[]any",
+        };
+        // Fields that are not required may be null in query results. A nil
+        // slice already represents a null array.
+        let row_type = if field.is_required() || field.type_ == FieldType::Array {
+            row_type.to_string()
+        } else {
+            format!("*{row_type}")
+        };
+        let row_field_decl = format!("{doc}{field_ref} {row_type} `json:\"{field_name}\"`");
+        let field_init = match self.tt.render(FIELD_INIT_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render field defs with error {:?}", e),
+        };
+
+        FieldData {
+            field_type_decl,
+            field_init,
+            field_ref,
+            row_field_decl,
+        }
+    }
+
+    /// Returns code snippets used for declaring field types, initializations,
+    /// and references.
+    fn gen_field_defs(&self, fields: &[TableSchemaField]) -> FieldSnippets {
+        let fields_data = fields
+            .iter()
+            .map(|f| self.gen_field(f))
+            .collect::<Vec<FieldData>>();
+
+        let fields_types = fields_data
+            .iter()
+            .map(|fd| fd.field_type_decl.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n\t");
+        let fields_inits = fields_data
+            .iter()
+            .map(|fd| fd.field_init.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n\t\t");
+        let fields_list = fields_data
+            .iter()
+            .map(|fd| format!("fields.{},", fd.field_ref))
+            .collect::<Vec<String>>()
+            .join("\n\t\t\t");
+        let row_fields = fields_data
+            .iter()
+            .map(|fd| fd.row_field_decl.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n\t");
+        FieldSnippets {
+            fields_types,
+            fields_inits,
+            fields_list,
+            row_fields,
+        }
+    }
+}
+
+impl Generator for Go<'_> {
+    fn dataset(&self) -> &GetDatasetVersionResponse {
+        self.dataset
+    }
+
+    fn resource_table(&self, r: &Table) -> DynamicAsset {
+        let dataset = self.dataset();
+        let dataset_id = dataset.uuid.to_string();
+        let dataset_name = self.dataset_name(&dataset.name);
+
+        let resource_name = &r.name;
+        let class_name = exported_identifier(resource_name);
+        let TableSchema { fields, .. } = &r.schema;
+        let FieldSnippets {
+            fields_types,
+            fields_inits,
+            fields_list,
+            row_fields,
+        } = self.gen_field_defs(fields);
+
+        #[derive(Serialize)]
+        struct Context {
+            module: String,
+            table_doc: String,
+            dataset_id: String,
+            dataset_name: String,
+            dataset_version: String,
+            class_name: String,
+            resource_name: String,
+            fields_types: String,
+            fields_inits: String,
+            fields_list: String,
+            row_fields: String,
+        }
+        let context = Context {
+            module: dataset_name.clone(),
+            // A separate paragraph of the table type's doc comment.
+            table_doc: match doc_comment(r.description.as_deref(), "") {
+                doc if doc.is_empty() => doc,
+                doc => format!("//\n{doc}"),
+            },
+            dataset_id,
+            dataset_name,
+            dataset_version: dataset.version.version.to_string(),
+            class_name: class_name.clone(),
+            resource_name: resource_name.to_string(),
+            fields_types,
+            fields_inits,
+            fields_list,
+            row_fields,
+        };
+
+        let code = match self.tt.render(TABLE_TYPE_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render table type with error {:?}", e),
+        };
+
+        let path = Path::new("tables").join(self.file_name(&class_name));
+        DynamicAsset {
+            path: Box::new(path),
+            name: class_name,
+            content: code,
+        }
+    }
+
+    fn version(&self) -> DynamicAsset {
+        let src_dir = self.source_dir();
+        let src_dir = Path::new(&src_dir);
+        #[derive(Serialize)]
+        struct Context {
+            code_version: String,
+        }
+        let context = Context {
+            code_version: String::from(GO_VERSION),
+        };
+        let code = match self.tt.render(VERSION_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render version file with error {:?}", e),
+        };
+
+        DynamicAsset {
+            path: Box::new(src_dir.join("version.go")),
+            name: "CodeVersion".into(),
+            content: code,
+        }
+    }
+
+    fn static_assets(&self) -> Vec<StaticAsset> {
+        Asset::iter()
+            .chain(ProtoAsset::iter())
+            .map(|p| {
+                // Static paths are relative to the module root, e.g., `dpm/field.go`.
+                let path = Box::new(PathBuf::from(p.to_string()));
+                let content = Asset::get(&p).unwrap_or_else(|| ProtoAsset::get(&p).unwrap());
+                StaticAsset { path, content }
+            })
+            .collect()
+    }
+
+    fn entry_file_name(&self) -> String {
+        String::from("")
+    }
+
+    fn root_dir(&self) -> PathBuf {
+        let dataset = self.dataset();
+        let package_directory = format!(
+            "{}@{}",
+            self.dataset_name(&dataset.name),
+            package_version(&dataset.version.version)
+        );
+        Path::new("go").join(package_directory)
+    }
+
+    /// The directory of the `dpm` package, which holds the static code.
+    fn source_dir(&self) -> String {
+        String::from("dpm")
+    }
+
+    fn variable_name(&self, name: &str) -> String {
+        exported_identifier(name)
+    }
+
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.go", clean_name(name).to_case(Case::Snake))
+    }
+
+    /// Returns the module path, e.g. `my-dataset`.
+    fn dataset_name(&self, name: &str) -> String {
+        clean_name(name).to_case(Case::Kebab)
+    }
+
+    fn manifest(&self, _changelog: &Changelog) -> Manifest {
+        let dataset = self.dataset();
+        let module = self.dataset_name(&dataset.name);
+        let contents = format!(
+            "module {module}
+
+go 1.21
+
+require (
+	google.golang.org/grpc v1.58.3
+	google.golang.org/protobuf v1.31.0
+)
+"
+        );
+
+        Manifest {
+            file_name: String::from("go.mod"),
+            description: contents,
+        }
+    }
+
+    fn entry_code(&self, _imports: Vec<ItemRef>) -> DynamicAsset {
+        DynamicAsset {
+            path: Box::new(Path::new(&self.source_dir()).join(self.entry_file_name())),
+            name: "".into(),
+            content: "".into(),
+        }
+    }
+
+    /// Builds the generated package. E.g., for the `Go` target, generates the gRPC client with
+    /// `go generate`, then builds and checks the module with `go build`, and `go vet`. Requires
+    /// `protoc` to be installed, and the Go bin directory to be on the PATH.
+    fn build_package(&self, path: &Path) {
+        eprintln!("Building Go module");
+        exec_cmd(
+            "install protoc-gen-go",
+            path,
+            "go",
+            &[
+                "install",
+                "google.golang.org/protobuf/cmd/protoc-gen-go@v1.31.0",
+            ],
+        );
+
+        exec_cmd(
+            "install protoc-gen-go-grpc",
+            path,
+            "go",
+            &[
+                "install",
+                "google.golang.org/grpc/cmd/protoc-gen-go-grpc@v1.3.0",
+            ],
+        );
+
+        exec_cmd("generate gRPC client", path, "go", &["generate", "./..."]);
+
+        exec_cmd("resolve Go dependencies", path, "go", &["mod", "tidy"]);
+
+        exec_cmd("format Go module", path, "gofmt", &["-w", "."]);
+
+        exec_cmd("build Go module", path, "go", &["build", "./..."]);
+
+        exec_cmd("vet Go module", path, "go", &["vet", "./..."]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{api::DatasetVersion, descriptor::Dataset};
+
+    #[test]
+    fn clean_name_works() {
+        assert_eq!(clean_name("oneword"), "oneword");
+        assert_eq!(clean_name("two W0rds"), "two W0rds");
+        assert_eq!(clean_name("words, with fie;nds"), "words with fiends");
+        assert_eq!(clean_name("underscores_ are_ok"), "underscores_ are_ok");
+        assert_eq!(clean_name("dots.are.not"), "dotsarenot");
+        assert_eq!(clean_name("dine-and-dash"), "dine-and-dash");
+    }
+
+    #[test]
+    fn exported_identifier_works() {
+        assert_eq!(exported_identifier("price"), "Price");
+        assert_eq!(exported_identifier("SALE_DATE"), "SaleDate");
+        assert_eq!(exported_identifier("2023 sales"), "X2023Sales");
+    }
+
+    #[test]
+    fn root_dir_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let res = GetDatasetVersionResponse {
+            name: dataset.name.to_string(),
+            uuid: Uuid::from_bytes(dataset.id.as_bytes().to_owned()),
+            description: dataset.description.unwrap_or("".into()),
+            version: DatasetVersion {
+                version: dataset.version,
+                accelerated: false,
+                dataset: dataset.tables,
+                patch_state: None,
+                patch_state_data: None,
+            },
+        };
+        let generator = Box::new(Go::new(&res));
+        let expected_dir = format!("test-snowflake@0.1.0-{}", GO_VERSION);
+        assert_eq!(generator.root_dir(), Path::new("go").join(expected_dir));
+    }
+}
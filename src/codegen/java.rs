@@ -0,0 +1,968 @@
+//! Java code generator. Optionally emits the table definitions in Kotlin.
+
+use super::generator::{
+    exec_cmd, Changelog, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset,
+};
+use crate::api::GetDatasetVersionResponse;
+use crate::descriptor::{FieldType, Table, TableSchema, TableSchemaField};
+use convert_case::{Case, Casing};
+use regress::Regex;
+use rust_embed::RustEmbed;
+use semver::Version;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tinytemplate::TinyTemplate;
+
+pub struct Java<'a> {
+    pub dataset: &'a GetDatasetVersionResponse,
+    /// Whether to emit Kotlin table definitions, built with Gradle, rather than
+    /// Java ones, built with Maven. The static library is Java in either case.
+    kotlin: bool,
+    tt: TinyTemplate<'a>,
+}
+
+const JAVA_VERSION: &str = "0.1.0";
+
+/// The package holding the static code, under which each dataset gets its own
+/// package of tables.
+const BASE_PACKAGE: &str = "tech.patch.dpm";
+
+#[derive(RustEmbed)]
+#[folder = "static/java/"]
+#[exclude = "pom.xml"] // The project file will be written as a manifest.
+#[exclude = "src/main/java/tech/patch/dpm/Version.java"] // Written as the version.
+#[exclude = "src/main/proto/*"] // RustEmbed fails on symbolic links; handled by embedding original proto below.
+#[exclude = "target/*"]
+#[exclude = "build/*"]
+#[exclude = ".gitignore"]
+struct Asset;
+
+// Embed the proto files available at the top-level proto directory.
+#[derive(RustEmbed)]
+#[folder = "proto"]
+#[prefix = "src/main/proto/"]
+struct ProtoAsset;
+
+// Helpers.
+struct FieldData {
+    field_decl: String,
+    field_ref: String,
+    row_param: String,
+    doc: Option<String>,
+}
+
+struct FieldSnippets {
+    fields_decls: String,
+    fields_list: String,
+    row_params: String,
+    row_doc: String,
+}
+
+/// Clean the name to retain only alphanumeric, underscore, hyphen, and space characters.
+fn clean_name(name: &str) -> String {
+    let re = Regex::new(r"[a-zA-Z0-9_\-\ ]+").unwrap();
+    re.find_iter(name)
+        .map(|m| &name[m.range()])
+        .collect::<Vec<&str>>()
+        .join("")
+}
+
+/// Java keywords and literals, which cannot be used as identifiers.
+static JAVA_KEYWORDS: &[&str] = &[
+    "_",
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// Kotlin hard keywords, which can only be used as identifiers when quoted in
+/// backticks.
+static KOTLIN_KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+/// Returns `name` as a valid Java identifier, escaping keywords.
+fn java_identifier(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if JAVA_KEYWORDS.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns `name` as a valid Kotlin identifier, escaping keywords.
+fn kotlin_identifier(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if KOTLIN_KEYWORDS.contains(&name) {
+        format!("`{name}`")
+    } else {
+        name.to_string()
+    }
+}
+
+static JAVA_FIELD_DECL_TEMPLATE_NAME: &str = "java_field_decl";
+static JAVA_FIELD_DECL_TEMPLATE: &str =
+    "public static final {field_type} {field_ref} = new {field_constructor}(\"{field_name}\");";
+
+static KOTLIN_FIELD_DECL_TEMPLATE_NAME: &str = "kotlin_field_decl";
+static KOTLIN_FIELD_DECL_TEMPLATE: &str = "val {field_ref} = {field_type}(\"{field_name}\")";
+
+static JAVA_TABLE_CLASS_TEMPLATE_NAME: &str = "java_table";
+static JAVA_TABLE_CLASS_TEMPLATE: &str = "package {package};
+
+import com.fasterxml.jackson.annotation.JsonProperty;
+import java.util.List;
+import tech.patch.dpm.*;
+
+/**
+ * The `{resource_name}` table.{class_doc}
+ */
+public final class {class_name} \\{
+  /** The fields of the `{resource_name}` table. */
+  public static final class Fields \\{
+    {fields_decls}
+
+    private Fields() \\{}
+  }
+
+  /**
+   * A row of the `{resource_name}` table, as returned by
+   * `execute({class_name}.Row.class)`.{row_doc}
+   */
+  public record Row(
+      {row_params}) \\{}
+
+  private static final Table TABLE = new Table(
+      \"{dataset_id}\",
+      \"{dataset_name}\",
+      \"{dataset_version}\",
+      \"{resource_name}\",
+      List.of(
+          {fields_list}
+      ));
+
+  private {class_name}() \\{}
+
+  /** Returns the table, with all fields selected. */
+  public static Table table() \\{
+    return TABLE;
+  }
+
+  /** Returns the table with the given fields selected. */
+  public static Table select(FieldExpr... selection) \\{
+    return TABLE.select(selection);
+  }
+}
+";
+
+static KOTLIN_TABLE_OBJECT_TEMPLATE_NAME: &str = "kotlin_table";
+static KOTLIN_TABLE_OBJECT_TEMPLATE: &str = "package {package}
+
+import com.fasterxml.jackson.annotation.JsonProperty
+import tech.patch.dpm.*
+
+/**
+ * The `{resource_name}` table.{class_doc}
+ */
+object {class_name} \\{
+    /** The fields of the `{resource_name}` table. */
+    object Fields \\{
+        {fields_decls}
+    }
+
+    /**
+     * A row of the `{resource_name}` table, as returned by
+     * `execute({class_name}.Row::class.java)`.{row_doc}
+     */
+    data class Row(
+        {row_params}
+    )
+
+    private val TABLE = Table(
+        \"{dataset_id}\",
+        \"{dataset_name}\",
+        \"{dataset_version}\",
+        \"{resource_name}\",
+        listOf<FieldExpr>(
+            {fields_list}
+        ),
+    )
+
+    /** Returns the table, with all fields selected. */
+    fun table(): Table = TABLE
+
+    /** Returns the table with the given fields selected. */
+    fun select(vararg selection: FieldExpr): Table = TABLE.select(*selection)
+}
+";
+
+static VERSION_TEMPLATE_NAME: &str = "version";
+static VERSION_TEMPLATE: &str = "package tech.patch.dpm;
+
+public final class Version \\{
+  public static final String CODE_VERSION = \"{code_version}\";
+
+  private Version() \\{}
+}
+";
+
+/// Returns a version string for a Maven artifact:
+///   dataset-version "-" code-version (".draft." draft-number)?
+/// See: https://maven.apache.org/pom.html#version-order-specification
+fn package_version(v: &Version) -> String {
+    if v.pre.is_empty() {
+        format!("{}-{}", v, JAVA_VERSION)
+    } else {
+        format!(
+            "{}.{}.{}-{}.{}",
+            v.major,
+            v.minor,
+            v.patch,
+            JAVA_VERSION,
+            // Assume this has form "draft.<number>", and so can be
+            // joined with the rest of the string via a ".".
+            v.pre.as_str()
+        )
+    }
+}
+
+impl<'a> Java<'a> {
+    pub fn new(dp: &'a GetDatasetVersionResponse, kotlin: bool) -> Self {
+        let mut tt = TinyTemplate::new();
+        if tt
+            .add_template(JAVA_FIELD_DECL_TEMPLATE_NAME, JAVA_FIELD_DECL_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", JAVA_FIELD_DECL_TEMPLATE_NAME);
+        }
+        if tt
+            .add_template(KOTLIN_FIELD_DECL_TEMPLATE_NAME, KOTLIN_FIELD_DECL_TEMPLATE)
+            .is_err()
+        {
+            panic!(
+                "Failed to add {:?} template",
+                KOTLIN_FIELD_DECL_TEMPLATE_NAME
+            );
+        }
+        if tt
+            .add_template(JAVA_TABLE_CLASS_TEMPLATE_NAME, JAVA_TABLE_CLASS_TEMPLATE)
+            .is_err()
+        {
+            panic!(
+                "Failed to add {:?} template",
+                JAVA_TABLE_CLASS_TEMPLATE_NAME
+            );
+        }
+        if tt
+            .add_template(
+                KOTLIN_TABLE_OBJECT_TEMPLATE_NAME,
+                KOTLIN_TABLE_OBJECT_TEMPLATE,
+            )
+            .is_err()
+        {
+            panic!(
+                "Failed to add {:?} template",
+                KOTLIN_TABLE_OBJECT_TEMPLATE_NAME
+            );
+        }
+        if tt
+            .add_template(VERSION_TEMPLATE_NAME, VERSION_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", VERSION_TEMPLATE_NAME);
+        }
+        // Do not perform HTML escaping.
+        tt.set_default_formatter(&tinytemplate::format_unescaped);
+
+        Self {
+            dataset: dp,
+            kotlin,
+            tt,
+        }
+    }
+
+    /// The source language's name, used for its source root, e.g.
+    /// `src/main/kotlin`.
+    fn language(&self) -> &'static str {
+        if self.kotlin {
+            "kotlin"
+        } else {
+            "java"
+        }
+    }
+
+    fn identifier(&self, name: &str) -> String {
+        if self.kotlin {
+            kotlin_identifier(name)
+        } else {
+            java_identifier(name)
+        }
+    }
+
+    /// Returns the description, trimmed and escaped for use in a doc comment,
+    /// or `None` if it is empty. Javadoc is HTML, whereas KDoc is Markdown.
+    fn doc_text(&self, description: Option<&str>) -> Option<String> {
+        let description = match description.map(str::trim) {
+            Some(d) if !d.is_empty() => d,
+            _ => return None,
+        };
+        if self.kotlin {
+            // Kotlin block comments nest, so `/*` must be broken up too.
+            Some(description.replace("*/", "* /").replace("/*", "/ *"))
+        } else {
+            Some(
+                description
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+                    .replace("*/", "*&#47;")
+                    // javac reads `\u` as a Unicode escape, even in comments.
+                    .replace('\\', "&#92;"),
+            )
+        }
+    }
+
+    /// Returns the lines of a doc comment holding `text`, each preceded by a
+    /// newline and `indent`.
+    fn doc_lines(&self, text: &str, indent: &str) -> String {
+        text.lines()
+            .map(|line| format!("\n{indent} * {line}").trim_end().to_string())
+            .collect()
+    }
+
+    /// The indentation of the declarations in a table's `Fields`.
+    fn field_indent(&self) -> &'static str {
+        if self.kotlin {
+            "        "
+        } else {
+            "    "
+        }
+    }
+
+    /// Returns the package holding the dataset's tables, e.g.
+    /// `tech.patch.dpm.datasets.my_dataset`. Package names follow Java's rules
+    /// in both flavours, so that they can be used from either language.
+    fn package(&self) -> String {
+        let dataset_package = clean_name(&self.dataset().name).to_case(Case::Snake);
+        format!(
+            "{BASE_PACKAGE}.datasets.{}",
+            java_identifier(&dataset_package)
+        )
+    }
+
+    /// Returns a field's declaration and reference.
+    fn gen_field(&self, field: &TableSchemaField) -> FieldData {
+        let field_name = field.name.to_owned();
+        let (field_type, java_constructor) = match field.type_ {
+            FieldType::Number => ("Field<Double>", "Field<>"),
+            FieldType::Boolean => ("Field<Boolean>", "Field<>"),
+            FieldType::String => ("StringField", "StringField"),
+            FieldType::Date => ("DateField", "DateField"),
+            FieldType::DateTime => ("DateTimeField", "DateTimeField"),
+            FieldType::Time => ("TimeField", "TimeField"),
+            FieldType::Array => ("ArrayField", "ArrayField"),
+        };
+        let field_ref = self.variable_name(&field_name);
+
+        #[derive(Serialize)]
+        struct Context<'a> {
+            field_ref: &'a str,
+            field_type: &'a str,
+            field_constructor: &'a str,
+            field_name: &'a str,
+        }
+
+        let context = Context {
+            field_ref: &field_ref,
+            field_type,
+            field_constructor: java_constructor,
+            field_name: &field_name,
+        };
+
+        let template_name = if self.kotlin {
+            KOTLIN_FIELD_DECL_TEMPLATE_NAME
+        } else {
+            JAVA_FIELD_DECL_TEMPLATE_NAME
+        };
+        let field_decl = match self.tt.render(template_name, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render field defs with error {:?}", e),
+        };
+        let doc = self.doc_text(field.description.as_deref());
+        let field_decl = match &doc {
+            Some(d) if d.lines().count() == 1 => {
+                format!("/** {d} */\n{}{field_decl}", self.field_indent())
+            }
+            Some(d) => {
+                let indent = self.field_indent();
+                format!(
+                    "/**{}\n{indent} */\n{indent}{field_decl}",
+                    self.doc_lines(d, indent)
+                )
+            }
+            None => field_decl,
+        };
+
+        // Fields that are not required may be null in query results.
+        let required = field.is_required();
+        let row_param = if self.kotlin {
+            let row_type = match field.type_ {
+                FieldType::Number => "Double",
+                FieldType::Boolean => "Boolean",
+                FieldType::String | FieldType::Date | FieldType::DateTime | FieldType::Time => {
+                    "String"
+                }
+                FieldType::Array => "List<Any?>",
+            };
+            let nullable = if required { "" } else { "?" };
+            format!("@JsonProperty(\"{field_name}\") val {field_ref}: {row_type}{nullable}")
+        } else {
+            let row_type = match (&field.type_, required) {
+                (FieldType::Number, true) => "double",
+                (FieldType::Number, false) => "Double",
+                (FieldType::Boolean, true) => "boolean",
+                (FieldType::Boolean, false) => "Boolean",
+                (
+                    FieldType::String | FieldType::Date | FieldType::DateTime | FieldType::Time,
+                    _,
+                ) => "String",
+                (FieldType::Array, _) => "List<Object>",
+            };
+            format!("@JsonProperty(\"{field_name}\") {row_type} {field_ref}")
+        };
+
+        FieldData {
+            field_decl,
+            field_ref,
+            row_param,
+            doc,
+        }
+    }
+
+    /// Returns code snippets used for declaring and referencing fields.
+    fn gen_field_defs(&self, fields: &[TableSchemaField]) -> FieldSnippets {
+        let fields_data = fields
+            .iter()
+            .map(|f| self.gen_field(f))
+            .collect::<Vec<FieldData>>();
+
+        let decls_sep = format!("\n{}", self.field_indent());
+        let (list_sep, params_sep, row_doc_indent, row_doc_tag) = if self.kotlin {
+            (",\n            ", ",\n        ", "    ", "@property")
+        } else {
+            (",\n          ", ",\n      ", "  ", "@param")
+        };
+        let fields_decls = fields_data
+            .iter()
+            .map(|fd| fd.field_decl.as_str())
+            .collect::<Vec<&str>>()
+            .join(&decls_sep);
+        let fields_list = fields_data
+            .iter()
+            .map(|fd| format!("Fields.{}", fd.field_ref))
+            .collect::<Vec<String>>()
+            .join(list_sep);
+        let row_params = fields_data
+            .iter()
+            .map(|fd| fd.row_param.as_str())
+            .collect::<Vec<&str>>()
+            .join(params_sep);
+        let row_tags = fields_data
+            .iter()
+            .filter_map(|fd| {
+                fd.doc
+                    .as_ref()
+                    .map(|d| format!("{row_doc_tag} {} {d}", fd.field_ref.trim_matches('`')))
+            })
+            .collect::<Vec<String>>();
+        let row_doc = if row_tags.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\n{row_doc_indent} *{}",
+                self.doc_lines(&row_tags.join("\n"), row_doc_indent)
+            )
+        };
+        FieldSnippets {
+            fields_decls,
+            fields_list,
+            row_params,
+            row_doc,
+        }
+    }
+}
+
+impl Generator for Java<'_> {
+    fn dataset(&self) -> &GetDatasetVersionResponse {
+        self.dataset
+    }
+
+    fn resource_table(&self, r: &Table) -> DynamicAsset {
+        let dataset = self.dataset();
+        let dataset_id = dataset.uuid.to_string();
+        let dataset_name = self.dataset_name(&dataset.name);
+        let package = self.package();
+
+        let resource_name = &r.name;
+        let class_name = self.identifier(&clean_name(resource_name).to_case(Case::Pascal));
+        let TableSchema { fields, .. } = &r.schema;
+        let FieldSnippets {
+            fields_decls,
+            fields_list,
+            row_params,
+            row_doc,
+        } = self.gen_field_defs(fields);
+
+        #[derive(Serialize)]
+        struct Context {
+            package: String,
+            class_doc: String,
+            dataset_id: String,
+            dataset_name: String,
+            dataset_version: String,
+            class_name: String,
+            resource_name: String,
+            fields_decls: String,
+            fields_list: String,
+            row_params: String,
+            row_doc: String,
+        }
+        let context = Context {
+            package: package.clone(),
+            class_doc: self
+                .doc_text(r.description.as_deref())
+                .map(|d| format!("\n *{}", self.doc_lines(&d, "")))
+                .unwrap_or_default(),
+            dataset_id,
+            dataset_name,
+            dataset_version: dataset.version.version.to_string(),
+            class_name: class_name.clone(),
+            resource_name: resource_name.to_string(),
+            fields_decls,
+            fields_list,
+            row_params,
+            row_doc,
+        };
+
+        let template_name = if self.kotlin {
+            KOTLIN_TABLE_OBJECT_TEMPLATE_NAME
+        } else {
+            JAVA_TABLE_CLASS_TEMPLATE_NAME
+        };
+        let code = match self.tt.render(template_name, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render table class with error {:?}", e),
+        };
+
+        let mut path = Path::new("src/main").join(self.language());
+        for segment in package.split('.') {
+            path.push(segment);
+        }
+        path.push(self.file_name(&class_name));
+        DynamicAsset {
+            path: Box::new(path),
+            name: class_name,
+            content: code,
+        }
+    }
+
+    fn version(&self) -> DynamicAsset {
+        let src_dir = self.source_dir();
+        let src_dir = Path::new(&src_dir);
+        #[derive(Serialize)]
+        struct Context {
+            code_version: String,
+        }
+        let context = Context {
+            code_version: String::from(JAVA_VERSION),
+        };
+        let code = match self.tt.render(VERSION_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render version file with error {:?}", e),
+        };
+
+        DynamicAsset {
+            path: Box::new(src_dir.join("Version.java")),
+            name: "Version".into(),
+            content: code,
+        }
+    }
+
+    fn static_assets(&self) -> Vec<StaticAsset> {
+        Asset::iter()
+            .chain(ProtoAsset::iter())
+            .map(|p| {
+                // Static paths are relative to the project root, e.g.
+                // `src/main/java/tech/patch/dpm/Table.java`.
+                let path = Box::new(PathBuf::from(p.to_string()));
+                let content = Asset::get(&p).unwrap_or_else(|| ProtoAsset::get(&p).unwrap());
+                StaticAsset { path, content }
+            })
+            .collect()
+    }
+
+    /// Only Gradle needs an entry file, naming the root project; Maven reads
+    /// everything from the pom.
+    fn entry_file_name(&self) -> String {
+        if self.kotlin {
+            String::from("settings.gradle.kts")
+        } else {
+            String::from("")
+        }
+    }
+
+    fn root_dir(&self) -> PathBuf {
+        let dataset = self.dataset();
+        let package_directory = format!(
+            "{}@{}",
+            self.dataset_name(&dataset.name),
+            package_version(&dataset.version.version)
+        );
+        Path::new(self.language()).join(package_directory)
+    }
+
+    /// The directory of the `tech.patch.dpm` package, which holds the static
+    /// code. This is Java for both flavours.
+    fn source_dir(&self) -> String {
+        format!("src/main/java/{}", BASE_PACKAGE.replace('.', "/"))
+    }
+
+    fn variable_name(&self, name: &str) -> String {
+        self.identifier(&clean_name(name).to_case(Case::Camel))
+    }
+
+    fn file_name(&self, name: &str) -> String {
+        let extension = if self.kotlin { "kt" } else { "java" };
+        format!("{name}.{extension}")
+    }
+
+    /// Returns the artifact id, e.g. `my-dataset`.
+    fn dataset_name(&self, name: &str) -> String {
+        clean_name(name).to_case(Case::Kebab)
+    }
+
+    fn manifest(&self, _changelog: &Changelog) -> Manifest {
+        let dataset = self.dataset();
+        let artifact_id = self.dataset_name(&dataset.name);
+        let version = package_version(&dataset.version.version);
+        let description = dataset
+            .description
+            .replace('&', "&amp;")
+            .replace('<', "&lt;");
+
+        if self.kotlin {
+            let contents = format!(
+                "import com.google.protobuf.gradle.id
+
+plugins {{
+    kotlin(\"jvm\") version \"1.9.10\"
+    id(\"com.google.protobuf\") version \"0.9.4\"
+    `java-library`
+}}
+
+group = \"{BASE_PACKAGE}\"
+version = \"{version}\"
+description = \"{}\"
+
+repositories {{
+    mavenCentral()
+}}
+
+dependencies {{
+    api(\"io.grpc:grpc-protobuf:1.58.0\")
+    api(\"io.grpc:grpc-stub:1.58.0\")
+    api(\"com.google.protobuf:protobuf-java:3.24.3\")
+    implementation(\"com.fasterxml.jackson.core:jackson-databind:2.15.2\")
+    implementation(\"com.fasterxml.jackson.module:jackson-module-kotlin:2.15.2\")
+    compileOnly(\"javax.annotation:javax.annotation-api:1.3.2\")
+    runtimeOnly(\"io.grpc:grpc-netty-shaded:1.58.0\")
+}}
+
+kotlin {{
+    jvmToolchain(17)
+}}
+
+protobuf {{
+    protoc {{
+        artifact = \"com.google.protobuf:protoc:3.24.3\"
+    }}
+    plugins {{
+        id(\"grpc\") {{
+            artifact = \"io.grpc:protoc-gen-grpc-java:1.58.0\"
+        }}
+    }}
+    generateProtoTasks {{
+        all().forEach {{
+            it.plugins {{
+                id(\"grpc\")
+            }}
+        }}
+    }}
+}}
+",
+                dataset
+                    .description
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('$', "\\$")
+            );
+
+            return Manifest {
+                file_name: String::from("build.gradle.kts"),
+                description: contents,
+            };
+        }
+
+        let contents = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<project xmlns=\"http://maven.apache.org/POM/4.0.0\"
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"
+         xsi:schemaLocation=\"http://maven.apache.org/POM/4.0.0 http://maven.apache.org/xsd/maven-4.0.0.xsd\">
+  <modelVersion>4.0.0</modelVersion>
+
+  <groupId>{BASE_PACKAGE}</groupId>
+  <artifactId>{artifact_id}</artifactId>
+  <version>{version}</version>
+  <packaging>jar</packaging>
+  <description>{description}</description>
+
+  <properties>
+    <maven.compiler.release>17</maven.compiler.release>
+    <project.build.sourceEncoding>UTF-8</project.build.sourceEncoding>
+    <grpc.version>1.58.0</grpc.version>
+    <protobuf.version>3.24.3</protobuf.version>
+  </properties>
+
+  <dependencies>
+    <dependency>
+      <groupId>io.grpc</groupId>
+      <artifactId>grpc-netty-shaded</artifactId>
+      <version>${{grpc.version}}</version>
+      <scope>runtime</scope>
+    </dependency>
+    <dependency>
+      <groupId>io.grpc</groupId>
+      <artifactId>grpc-protobuf</artifactId>
+      <version>${{grpc.version}}</version>
+    </dependency>
+    <dependency>
+      <groupId>io.grpc</groupId>
+      <artifactId>grpc-stub</artifactId>
+      <version>${{grpc.version}}</version>
+    </dependency>
+    <dependency>
+      <groupId>com.google.protobuf</groupId>
+      <artifactId>protobuf-java</artifactId>
+      <version>${{protobuf.version}}</version>
+    </dependency>
+    <dependency>
+      <groupId>com.fasterxml.jackson.core</groupId>
+      <artifactId>jackson-databind</artifactId>
+      <version>2.15.2</version>
+    </dependency>
+    <dependency>
+      <groupId>javax.annotation</groupId>
+      <artifactId>javax.annotation-api</artifactId>
+      <version>1.3.2</version>
+    </dependency>
+  </dependencies>
+
+  <build>
+    <extensions>
+      <extension>
+        <groupId>kr.motd.maven</groupId>
+        <artifactId>os-maven-plugin</artifactId>
+        <version>1.7.1</version>
+      </extension>
+    </extensions>
+    <plugins>
+      <plugin>
+        <groupId>org.xolstice.maven.plugins</groupId>
+        <artifactId>protobuf-maven-plugin</artifactId>
+        <version>0.6.1</version>
+        <configuration>
+          <protocArtifact>com.google.protobuf:protoc:${{protobuf.version}}:exe:${{os.detected.classifier}}</protocArtifact>
+          <pluginId>grpc-java</pluginId>
+          <pluginArtifact>io.grpc:protoc-gen-grpc-java:${{grpc.version}}:exe:${{os.detected.classifier}}</pluginArtifact>
+        </configuration>
+        <executions>
+          <execution>
+            <goals>
+              <goal>compile</goal>
+              <goal>compile-custom</goal>
+            </goals>
+          </execution>
+        </executions>
+      </plugin>
+    </plugins>
+  </build>
+</project>
+"
+        );
+
+        Manifest {
+            file_name: String::from("pom.xml"),
+            description: contents,
+        }
+    }
+
+    fn entry_code(&self, _imports: Vec<ItemRef>) -> DynamicAsset {
+        let content = if self.kotlin {
+            format!(
+                "rootProject.name = \"{}\"\n",
+                self.dataset_name(&self.dataset().name)
+            )
+        } else {
+            String::from("")
+        };
+
+        DynamicAsset {
+            path: Box::new(PathBuf::from(self.entry_file_name())),
+            name: "".into(),
+            content,
+        }
+    }
+
+    /// Builds the generated package. E.g., for the `Java` target, generates the gRPC client and
+    /// compiles the project with `mvn package`; for its Kotlin flavour, with `gradle build`.
+    /// Requires Maven or Gradle to be installed; each fetches `protoc` itself.
+    fn build_package(&self, path: &Path) {
+        if self.kotlin {
+            eprintln!("Building Kotlin project");
+            exec_cmd("build Kotlin project", path, "gradle", &["build"]);
+        } else {
+            eprintln!("Building Java project");
+            exec_cmd("build Java project", path, "mvn", &["-B", "package"]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{api::DatasetVersion, descriptor::Dataset};
+
+    #[test]
+    fn clean_name_works() {
+        assert_eq!(clean_name("oneword"), "oneword");
+        assert_eq!(clean_name("two W0rds"), "two W0rds");
+        assert_eq!(clean_name("words, with fie;nds"), "words with fiends");
+        assert_eq!(clean_name("underscores_ are_ok"), "underscores_ are_ok");
+        assert_eq!(clean_name("dots.are.not"), "dotsarenot");
+        assert_eq!(clean_name("dine-and-dash"), "dine-and-dash");
+    }
+
+    #[test]
+    fn identifier_works() {
+        assert_eq!(java_identifier("price"), "price");
+        assert_eq!(java_identifier("default"), "default_");
+        assert_eq!(java_identifier("1stPlace"), "_1stPlace");
+        assert_eq!(kotlin_identifier("default"), "default");
+        assert_eq!(kotlin_identifier("object"), "`object`");
+        assert_eq!(kotlin_identifier("1stPlace"), "_1stPlace");
+    }
+
+    #[test]
+    fn root_dir_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let res = GetDatasetVersionResponse {
+            name: dataset.name.to_string(),
+            uuid: Uuid::from_bytes(dataset.id.as_bytes().to_owned()),
+            description: dataset.description.unwrap_or("".into()),
+            version: DatasetVersion {
+                version: dataset.version,
+                accelerated: false,
+                dataset: dataset.tables,
+                patch_state: None,
+                patch_state_data: None,
+            },
+        };
+        let expected_dir = format!("test-snowflake@0.1.0-{}", JAVA_VERSION);
+        let generator = Box::new(Java::new(&res, false));
+        assert_eq!(generator.root_dir(), Path::new("java").join(&expected_dir));
+        let generator = Box::new(Java::new(&res, true));
+        assert_eq!(generator.root_dir(), Path::new("kotlin").join(expected_dir));
+    }
+}
@@ -4,7 +4,10 @@ use std::collections::{BTreeSet, HashMap};
 
 use std::path::{Path, PathBuf};
 
-use super::generator::{exec_cmd, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset};
+use super::generator::{
+    exec_cmd, Changelog, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset,
+    CHANGELOG_FILE_NAME,
+};
 use crate::api::GetDatasetVersionResponse;
 use crate::descriptor::{FieldType, Table, TableSchema, TableSchemaField};
 use convert_case::{Case, Casing};
@@ -84,33 +87,54 @@ fn clean_name(name: &str) -> String {
         .join("")
 }
 
+/// Returns a JSDoc comment holding the description, followed by a newline and
+/// `indent` so that it can prefix a declaration. Returns an empty string if
+/// there is no description.
+fn doc_comment(description: Option<&str>, indent: &str) -> String {
+    let description = match description.map(str::trim) {
+        Some(d) if !d.is_empty() => d.replace("*/", "*\\/"),
+        _ => return String::new(),
+    };
+    let lines = description
+        .lines()
+        .map(|line| format!("{indent} * {line}").trim_end().to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("/**\n{lines}\n{indent} */\n{indent}")
+}
+
 static IMPORT_TEMPLATE_NAME: &str = "imports";
 static IMPORT_TEMPLATE: &str = "
 import \\{ {field_classes} } from \"../field\";
-import \\{ FieldExpr } from \"../field_expr\";
+import \\{ FieldExpr, Scalar } from \"../field_expr\";
 import \\{ Table } from \"../table\";
 ";
 
 static FIELD_DEF_TEMPLATE_NAME: &str = "field_def";
-static FIELD_DEF_TEMPLATE: &str = "{field_ref}: new {field_type}(\"{field_name}\")";
+static FIELD_DEF_TEMPLATE: &str = "{field_doc}{field_ref}: new {field_type}(\"{field_name}\")";
 
 static TABLE_CLASS_TEMPLATE_NAME: &str = "table";
 static TABLE_CLASS_TEMPLATE: &str = "
 {imports}
 
-export class {class_name} \\{
+/** A row of `{resource_name}`, as returned by executing a query on the table. */
+export interface {class_name}Row \\{
+    {row_fields}
+}
+
+{class_doc}export class {class_name} \\{
     // Fields.
     public static fields = \\{
         {field_defs}
     };
 
-    private table_: Table;
+    private table_: Table<{class_name}Row>;
 
     // Singleton.
     private static instance: {class_name};
 
     private constructor() \\{
-      this.table_ = new Table(\\{
+      this.table_ = new Table<{class_name}Row>(\\{
         packageId: \"{dataset_id}\",
         datasetName: \"{dataset_name}\",
         datasetVersion: \"{dataset_version}\",
@@ -127,11 +151,11 @@ export class {class_name} \\{
       return {class_name}.instance;
     }
 
-    public static table(): Table \\{
+    public static table(): Table<{class_name}Row> \\{
       return this.get().table_;
     }
 
-    public static select(...selection: ({selector} | FieldExpr)[]): Table \\{
+    public static select(...selection: ({selector} | FieldExpr)[]): Table<{class_name}Row> \\{
       return this.table().select(...selection);
     }
     // Rest of the stuff.
@@ -141,7 +165,7 @@ export class {class_name} \\{
 static ENTRY_POINT_TEMPLATE_NAME: &str = "entry";
 static ENTRY_POINT_TEMPLATE: &str = "
 {{ for item in imports }}
-export \\{ {item.ref_name} } from \"./{item.path}\";
+export \\{ {item.ref_name}, {item.ref_name}Row } from \"./{item.path}\";
 {{ endfor }}
 ";
 
@@ -224,20 +248,20 @@ impl<'a> NodeJs<'a> {
             FieldType::Date => (String::from("DateField"), String::from("DateField")),
             FieldType::Time => (String::from("TimeField"), String::from("TimeField")),
             FieldType::DateTime => (String::from("DateTimeField"), String::from("DateTimeField")),
-            FieldType::Array { .. } => {
-                unreachable!("Unsupported field type {:?}, please report a bug!", field)
-            }
+            FieldType::Array => (String::from("ArrayField"), String::from("ArrayField")),
         };
         let field_ref = self.variable_name(&field_name);
 
         #[derive(Serialize)]
         struct Context {
+            field_doc: String,
             field_ref: String,
             field_type: String,
             field_name: String,
         }
 
         let context = Context {
+            field_doc: doc_comment(field.description.as_deref(), "\t"),
             field_ref,
             field_type,
             field_name: field_name.clone(),
@@ -282,6 +306,28 @@ impl<'a> NodeJs<'a> {
         )
     }
 
+    /// Returns the property signatures of a table's row interface. Fields that
+    /// are not required may be `null`.
+    fn gen_row_fields(&self, fields: &[TableSchemaField]) -> String {
+        fields
+            .iter()
+            .map(|f| {
+                let row_type = match f.type_ {
+                    FieldType::Number => "number",
+                    FieldType::Boolean => "boolean",
+                    FieldType::String | FieldType::Date | FieldType::Time | FieldType::DateTime => {
+                        "string"
+                    }
+                    FieldType::Array => "Scalar[]",
+                };
+                let nullable = if f.is_required() { "" } else { " | null" };
+                let doc = doc_comment(f.description.as_deref(), "\t");
+                format!("{doc}\"{}\": {row_type}{nullable};", f.name)
+            })
+            .collect::<Vec<String>>()
+            .join("\n\t")
+    }
+
     fn gen_imports(&self, field_classes: Vec<String>) -> String {
         #[derive(Serialize)]
         struct Context {
@@ -321,22 +367,26 @@ impl Generator for NodeJs<'_> {
         #[derive(Serialize)]
         struct Context {
             imports: String,
+            class_doc: String,
             dataset_id: String,
             dataset_name: String,
             dataset_version: String,
             class_name: String,
             resource_name: String,
             field_defs: String,
+            row_fields: String,
             selector: String,
         }
         let context = Context {
             imports: self.gen_imports(field_classes),
+            class_doc: doc_comment(r.description.as_deref(), ""),
             dataset_id,
             dataset_name,
             dataset_version: dataset.version.version.to_string(),
             class_name: class_name.clone(),
             resource_name: resource_name.to_string(),
             field_defs,
+            row_fields: self.gen_row_fields(fields),
             selector,
         };
 
@@ -416,7 +466,7 @@ impl Generator for NodeJs<'_> {
         clean_name(name).to_case(Case::Kebab)
     }
 
-    fn manifest(&self) -> Manifest {
+    fn manifest(&self, changelog: &Changelog) -> Manifest {
         let dataset = self.dataset();
         let base_name = self.dataset_name(&dataset.name);
         let full_name = match &self.scope {
@@ -436,6 +486,14 @@ impl Generator for NodeJs<'_> {
             scripts: HashMap<&'a str, &'a str>,
             dev_dependencies: HashMap<&'a str, &'a str>,
             dependencies: HashMap<&'a str, &'a str>,
+            dpm: DpmMetadata<'a>,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct DpmMetadata<'a> {
+            changelog: &'a str,
+            release_notes: &'a str,
         }
 
         let pkg_json = PackageJson {
@@ -455,6 +513,10 @@ impl Generator for NodeJs<'_> {
                 ("google-protobuf", "^3.0.0"),
                 ("graphql-request", "^6.0.0"),
             ]),
+            dpm: DpmMetadata {
+                changelog: CHANGELOG_FILE_NAME,
+                release_notes: &changelog.release_notes,
+            },
         };
 
         let pkg_json = match serde_json::to_string_pretty(&pkg_json) {
@@ -507,7 +569,7 @@ impl Generator for NodeJs<'_> {
     /// Builds the generated package. E.g., for the `Node.js` target, builds the npm package using
     /// the recommended Node.js build commands: `npm install`, and `npm run build`.
     fn build_package(&self, path: &Path) {
-        println!("Building npm package");
+        eprintln!("Building npm package");
         exec_cmd("install npm package", path, "npm", &["install"]);
         exec_cmd("build npm package", path, "npm", &["run", "build"]);
         exec_cmd(
@@ -524,7 +586,10 @@ mod tests {
     use uuid::Uuid;
 
     use super::*;
-    use crate::{api::DatasetVersion, descriptor::Dataset};
+    use crate::{
+        api::DatasetVersion,
+        descriptor::{Constraints, Dataset},
+    };
 
     #[test]
     fn standardize_import_works() {
@@ -552,6 +617,50 @@ mod tests {
         assert_eq!(clean_name("dine-and-dash"), "dine-and-dash");
     }
 
+    #[test]
+    fn gen_row_fields_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let res = GetDatasetVersionResponse {
+            name: dataset.name.to_string(),
+            uuid: Uuid::from_bytes(dataset.id.as_bytes().to_owned()),
+            description: dataset.description.unwrap_or("".into()),
+            version: DatasetVersion {
+                version: dataset.version,
+                accelerated: false,
+                dataset: dataset.tables,
+                patch_state: None,
+                patch_state_data: None,
+            },
+        };
+        let generator = NodeJs::new(&res, None);
+        let field = |type_, required| TableSchemaField {
+            name: String::from("PRICE"),
+            type_,
+            description: None,
+            constraints: Some(Constraints {
+                required: Some(required),
+            }),
+        };
+        assert_eq!(
+            generator.gen_row_fields(&[field(FieldType::Number, true)]),
+            "\"PRICE\": number;"
+        );
+        assert_eq!(
+            generator.gen_row_fields(&[field(FieldType::Array, false)]),
+            "\"PRICE\": Scalar[] | null;"
+        );
+    }
+
+    #[test]
+    fn doc_comment_works() {
+        assert_eq!(doc_comment(None, "\t"), "");
+        assert_eq!(doc_comment(Some("  "), "\t"), "");
+        assert_eq!(
+            doc_comment(Some("Sale price.\n\nEnds a comment: */"), "\t"),
+            "/**\n\t * Sale price.\n\t *\n\t * Ends a comment: *\\/\n\t */\n\t"
+        );
+    }
+
     #[test]
     fn root_dir_works() {
         let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
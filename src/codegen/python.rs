@@ -2,7 +2,10 @@
 
 use std::collections::BTreeSet;
 
-use super::generator::{exec_cmd, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset};
+use super::generator::{
+    exec_cmd, Changelog, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset,
+    CHANGELOG_FILE_NAME,
+};
 use crate::api::GetDatasetVersionResponse;
 use crate::descriptor::{FieldType, Table, TableSchema, TableSchemaField};
 use convert_case::{Case, Casing};
@@ -95,9 +98,25 @@ fn clean_name(name: &str) -> String {
         .join("")
 }
 
+/// Returns the description, trimmed and escaped for use in a docstring, or
+/// `None` if it is empty.
+fn docstring_text(description: Option<&str>) -> Option<String> {
+    match description.map(str::trim) {
+        Some(d) if !d.is_empty() => Some(d.replace('\\', "\\\\").replace("\"\"\"", "\\\"\"\"")),
+        _ => None,
+    }
+}
+
+/// Indents all but the first line of `text` by `indent`.
+fn indent_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .collect::<Vec<&str>>()
+        .join(&format!("\n{indent}"))
+}
+
 static IMPORT_TEMPLATE_NAME: &str = "imports";
 static IMPORT_TEMPLATE: &str = "
-from typing import Literal, Union
+from typing import Any, List, Literal, Optional, TypedDict, Union
 
 from ..field import {field_classes}
 from ..field_expr import FieldExpr
@@ -111,8 +130,14 @@ static TABLE_CLASS_TEMPLATE_NAME: &str = "table";
 static TABLE_CLASS_TEMPLATE: &str = "
 {imports}
 
+# A row of `{resource_name}`, as returned by executing a query on the table.
+{class_name}Row = TypedDict(\"{class_name}Row\", \\{
+    {row_fields}
+})
+
+
 class {class_name}:
-    # Source path.
+    {class_doc}# Source path.
     source_path = \"https://example.snowflakecomputing.com\"
 
     class Map(dict):
@@ -144,18 +169,18 @@ class {class_name}:
         return {class_name}.instance
 
     @classmethod
-    def table(cls) -> Table:
+    def table(cls) -> Table[{class_name}Row]:
         return {class_name}.get().table_
 
     @classmethod
-    def select(cls, *selection: Union[{selector}, FieldExpr]) -> Table:
+    def select(cls, *selection: Union[{selector}, FieldExpr]) -> Table[{class_name}Row]:
         return {class_name}.table().select(*selection)
 ";
 
 static ENTRY_POINT_TEMPLATE_NAME: &str = "entry";
 static ENTRY_POINT_TEMPLATE: &str = "
 {{ for item in imports }}
-from {item.path} import {item.ref_name}
+from {item.path} import {item.ref_name}, {item.ref_name}Row
 {{ endfor }}
 ";
 
@@ -230,9 +255,7 @@ impl<'a> Python<'a> {
             FieldType::Date => String::from("DateField"),
             FieldType::Time => String::from("TimeField"),
             FieldType::DateTime => String::from("DateTimeField"),
-            FieldType::Array => {
-                unreachable!("Unsupported field type {:?}, please report a bug!", field)
-            }
+            FieldType::Array => String::from("ArrayField"),
         };
         let field_ref = self.variable_name(&field_name);
 
@@ -286,6 +309,62 @@ impl<'a> Python<'a> {
         )
     }
 
+    /// Returns the items of a table's row `TypedDict`. Fields that are not
+    /// required are `Optional`.
+    fn gen_row_fields(&self, fields: &[TableSchemaField]) -> String {
+        fields
+            .iter()
+            .map(|f| {
+                let row_type = match f.type_ {
+                    FieldType::Number => "float",
+                    FieldType::Boolean => "bool",
+                    FieldType::String | FieldType::Date | FieldType::Time | FieldType::DateTime => {
+                        "str"
+                    }
+                    FieldType::Array => "List[Any]",
+                };
+                if f.is_required() {
+                    format!("\"{}\": {row_type}", f.name)
+                } else {
+                    format!("\"{}\": Optional[{row_type}]", f.name)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(",\n\t")
+    }
+
+    /// Returns the table class's docstring, holding the table's description
+    /// followed by those of its fields, followed by a newline and indentation
+    /// for the next statement. Returns an empty string if there are no
+    /// descriptions.
+    fn gen_class_doc(&self, r: &Table) -> String {
+        let field_docs = r
+            .schema
+            .fields
+            .iter()
+            .filter_map(|f| {
+                docstring_text(f.description.as_deref()).map(|d| {
+                    format!(
+                        "        {}: {}",
+                        self.variable_name(&f.name),
+                        indent_lines(&d, "            ")
+                    )
+                })
+            })
+            .collect::<Vec<String>>();
+        let mut sections = Vec::new();
+        if let Some(d) = docstring_text(r.description.as_deref()) {
+            sections.push(format!("    {}", indent_lines(&d, "    ")));
+        }
+        if !field_docs.is_empty() {
+            sections.push(format!("    Fields:\n{}", field_docs.join("\n")));
+        }
+        if sections.is_empty() {
+            return String::new();
+        }
+        format!("\"\"\"\n{}\n    \"\"\"\n\n    ", sections.join("\n\n"))
+    }
+
     fn gen_imports(&self, field_classes: Vec<String>) -> String {
         #[derive(Serialize)]
         struct Context {
@@ -325,22 +404,26 @@ impl Generator for Python<'_> {
         #[derive(Serialize)]
         struct Context {
             imports: String,
+            class_doc: String,
             dataset_id: String,
             dataset_name: String,
             dataset_version: String,
             class_name: String,
             resource_name: String,
             field_defs: String,
+            row_fields: String,
             selector: String,
         }
         let context = Context {
             imports: self.gen_imports(field_classes),
+            class_doc: self.gen_class_doc(r),
             dataset_id,
             dataset_name,
             dataset_version: dp.version.version.to_string(),
             class_name: class_name.clone(),
             resource_name: resource_name.to_string(),
             field_defs,
+            row_fields: self.gen_row_fields(fields),
             selector,
         };
 
@@ -428,7 +511,7 @@ impl Generator for Python<'_> {
         clean_name(name).to_case(Case::Kebab)
     }
 
-    fn manifest(&self) -> Manifest {
+    fn manifest(&self, changelog: &Changelog) -> Manifest {
         let dp = self.dataset();
         let pkg_name: String = self.dataset_name(&dp.name);
         let version = package_instance_version(&dp.version.version);
@@ -436,6 +519,19 @@ impl Generator for Python<'_> {
         #[derive(Serialize)]
         struct PyprojectToml<'a> {
             project: Project<'a>,
+            tool: Tool<'a>,
+        }
+
+        #[derive(Serialize)]
+        struct Tool<'a> {
+            dpm: DpmMetadata<'a>,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct DpmMetadata<'a> {
+            changelog: &'a str,
+            release_notes: &'a str,
         }
 
         #[derive(Serialize)]
@@ -458,6 +554,12 @@ impl Generator for Python<'_> {
                     "python-dateutil ~= 2.8.2",
                 ]),
             },
+            tool: Tool {
+                dpm: DpmMetadata {
+                    changelog: CHANGELOG_FILE_NAME,
+                    release_notes: &changelog.release_notes,
+                },
+            },
         };
 
         let project_toml = match toml::ser::to_string_pretty(&project_toml) {
@@ -505,7 +607,7 @@ impl Generator for Python<'_> {
     /// Builds the generated package. E.g., for the `Python` target, builds the Python package using
     /// the recommended Python build tools: `virtualenv, pip`, and `python -m build`.
     fn build_package(&self, path: &Path) {
-        println!("Building Python package");
+        eprintln!("Building Python package");
         exec_cmd(
             "build virtual environment",
             path,
@@ -533,6 +635,16 @@ mod tests {
     use super::*;
     use crate::{api::DatasetVersion, descriptor::Dataset};
 
+    #[test]
+    fn docstring_text_works() {
+        assert_eq!(docstring_text(None), None);
+        assert_eq!(docstring_text(Some(" ")), None);
+        assert_eq!(
+            docstring_text(Some(" A \"\"\"quoted\"\"\" \\ path ")),
+            Some(String::from("A \\\"\"\"quoted\\\"\"\" \\\\ path"))
+        );
+    }
+
     #[test]
     fn standardize_import_works() {
         assert_eq!(
@@ -0,0 +1,655 @@
+//! Rust code generator.
+
+use super::generator::{
+    exec_cmd, Changelog, DynamicAsset, Generator, ItemRef, Manifest, StaticAsset,
+    CHANGELOG_FILE_NAME,
+};
+use crate::api::GetDatasetVersionResponse;
+use crate::descriptor::{FieldType, Table, TableSchema, TableSchemaField};
+use convert_case::{Case, Casing};
+use regress::Regex;
+use rust_embed::RustEmbed;
+use semver::Version;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tinytemplate::TinyTemplate;
+
+pub struct Rust<'a> {
+    pub dataset: &'a GetDatasetVersionResponse,
+    tt: TinyTemplate<'a>,
+}
+
+const RUST_VERSION: &str = "0.1.0";
+
+#[derive(RustEmbed)]
+#[folder = "static/rust/"]
+#[exclude = "Cargo.*"] // The crate manifest will be written as a manifest.
+#[exclude = "src/lib.rs"] // Written as the entry point.
+#[exclude = "src/version.rs"] // Written as the version.
+#[exclude = "proto/*"] // RustEmbed fails on symbolic links; handled by embedding original proto below.
+#[exclude = "tests/*"]
+#[exclude = "target/*"]
+#[exclude = ".gitignore"]
+struct Asset;
+
+// Embed the proto files available at the top-level proto directory.
+#[derive(RustEmbed)]
+#[folder = "proto"]
+#[prefix = "proto/"]
+struct ProtoAsset;
+
+// Helpers.
+struct FieldData {
+    field_type_decl: String,
+    field_init: String,
+    field_ref: String,
+    field_class: String,
+    row_field_decl: String,
+}
+
+struct FieldSnippets {
+    fields_types: String,
+    fields_inits: String,
+    fields_list: String,
+    field_classes: String,
+    row_fields: String,
+}
+
+/// Clean the name to retain only alphanumeric, underscore, hyphen, and space characters.
+fn clean_name(name: &str) -> String {
+    let re = Regex::new(r"[a-zA-Z0-9_\-\ ]+").unwrap();
+    re.find_iter(name)
+        .map(|m| &name[m.range()])
+        .collect::<Vec<&str>>()
+        .join("")
+}
+
+/// Rust keywords, which can only be used as identifiers in their raw form.
+static KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Keywords that cannot be used as raw identifiers.
+static RESERVED: &[&str] = &["crate", "self", "super", "Self"];
+
+/// Returns `name` as a valid Rust identifier, escaping keywords.
+fn identifier(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns doc comment lines holding the description, followed by a newline
+/// and `indent` so that they can prefix a declaration. Returns an empty string
+/// if there is no description.
+fn doc_comment(description: Option<&str>, indent: &str) -> String {
+    match description.map(str::trim) {
+        Some(d) if !d.is_empty() => d
+            .lines()
+            .map(|line| format!("/// {line}").trim_end().to_string() + "\n" + indent)
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+static FIELD_INIT_TEMPLATE_NAME: &str = "field_init";
+static FIELD_INIT_TEMPLATE: &str = "{field_ref}: {field_class}::new(\"{field_name}\")";
+
+static TABLE_STRUCT_TEMPLATE_NAME: &str = "table";
+static TABLE_STRUCT_TEMPLATE: &str = "
+use serde::Deserialize;
+
+use crate::field::\\{{field_classes}};
+use crate::field_expr::FieldExpr;
+use crate::table::Table;
+
+/// The fields of `{class_name}`.
+#[derive(Clone, Debug)]
+pub struct {class_name}Fields \\{
+    {fields_types}
+}
+
+/// A row of `{resource_name}`, as returned by executing a query on the table
+/// with `execute::<{class_name}Row>()`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct {class_name}Row \\{
+    {row_fields}
+}
+
+{class_doc}pub struct {class_name};
+
+impl {class_name} \\{
+    pub fn fields() -> {class_name}Fields \\{
+        {class_name}Fields \\{
+            {fields_inits}
+        }
+    }
+
+    pub fn table() -> Table \\{
+        let fields = Self::fields();
+        Table::new(
+            \"{dataset_id}\",
+            \"{dataset_name}\",
+            \"{dataset_version}\",
+            \"{resource_name}\",
+            vec![
+                {fields_list}
+            ],
+        )
+    }
+
+    pub fn select(selection: impl IntoIterator<Item = FieldExpr>) -> Table \\{
+        Self::table().select(selection)
+    }
+}
+";
+
+static ENTRY_POINT_TEMPLATE_NAME: &str = "entry";
+static ENTRY_POINT_TEMPLATE: &str = "
+pub mod backends;
+pub mod error;
+pub mod field;
+pub mod field_expr;
+pub mod table;
+pub mod version;
+
+pub mod tables \\{
+{{ for item in imports }}
+    mod {item.path};
+    pub use {item.path}::\\{{item.ref_name}, {item.ref_name}Row};
+{{ endfor }}
+}
+";
+
+static VERSION_TEMPLATE_NAME: &str = "version";
+static VERSION_TEMPLATE: &str = "
+// The version of the generated code.
+pub const CODE_VERSION: &str = \"{code_version}\";
+";
+
+/// Returns a version string for a Rust crate:
+///   dataset-version "-" code-version (".draft." draft-number)?
+/// See: https://doc.rust-lang.org/cargo/reference/manifest.html#the-version-field
+fn package_version(v: &Version) -> String {
+    if v.pre.is_empty() {
+        format!("{}-{}", v, RUST_VERSION)
+    } else {
+        format!(
+            "{}.{}.{}-{}.{}",
+            v.major,
+            v.minor,
+            v.patch,
+            RUST_VERSION,
+            // Assume this has form "draft.<number>", and so can be
+            // joined with the rest of the string via a ".".
+            v.pre.as_str()
+        )
+    }
+}
+
+impl<'a> Rust<'a> {
+    pub fn new(dp: &'a GetDatasetVersionResponse) -> Self {
+        let mut tt = TinyTemplate::new();
+        if tt
+            .add_template(FIELD_INIT_TEMPLATE_NAME, FIELD_INIT_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", FIELD_INIT_TEMPLATE_NAME);
+        }
+        if tt
+            .add_template(TABLE_STRUCT_TEMPLATE_NAME, TABLE_STRUCT_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", TABLE_STRUCT_TEMPLATE_NAME);
+        }
+        if tt
+            .add_template(ENTRY_POINT_TEMPLATE_NAME, ENTRY_POINT_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", ENTRY_POINT_TEMPLATE_NAME);
+        }
+        if tt
+            .add_template(VERSION_TEMPLATE_NAME, VERSION_TEMPLATE)
+            .is_err()
+        {
+            panic!("Failed to add {:?} template", VERSION_TEMPLATE_NAME);
+        }
+        // Do not perform HTML escaping.
+        tt.set_default_formatter(&tinytemplate::format_unescaped);
+
+        Self { dataset: dp, tt }
+    }
+
+    /// Returns a field's declaration, initialization, reference, and class.
+    fn gen_field(&self, field: &TableSchemaField) -> FieldData {
+        let field_name = field.name.to_owned();
+        let (field_type, field_class) = match field.type_ {
+            FieldType::Number => ("Field<f64>", "Field"),
+            FieldType::Boolean => ("Field<bool>", "Field"),
+            FieldType::String => ("StringField", "StringField"),
+            FieldType::Date => ("DateField", "DateField"),
+            FieldType::DateTime => ("DateTimeField", "DateTimeField"),
+            FieldType::Time => ("TimeField", "TimeField"),
+            FieldType::Array => ("ArrayField", "ArrayField"),
+        };
+        let field_ref = self.variable_name(&field_name);
+
+        #[derive(Serialize)]
+        struct Context<'a> {
+            field_ref: &'a str,
+            field_class: &'a str,
+            field_name: &'a str,
+        }
+
+        let context = Context {
+            field_ref: &field_ref,
+            field_class,
+            field_name: &field_name,
+        };
+
+        let doc = doc_comment(field.description.as_deref(), "    ");
+        let field_type_decl = format!("{doc}pub {field_ref}: {field_type},");
+        let row_type = match field.type_ {
+            FieldType::Number => "f64",
+            FieldType::Boolean => "bool",
+            FieldType::String | FieldType::Date | FieldType::DateTime | FieldType::Time => "String",
+            FieldType::Array => "Vec<serde_json::Value>",
+        };
+        // Fields that are not required may be null in query results.
+        let row_type = if field.is_required() {
+            row_type.to_string()
+        } else {
+            format!("Option<{row_type}>")
+        };
+        let row_field_decl =
+            format!("{doc}#[serde(rename = \"{field_name}\")]\n    pub {field_ref}: {row_type},");
+        let field_init = match self.tt.render(FIELD_INIT_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render field defs with error {:?}", e),
+        };
+
+        FieldData {
+            field_type_decl,
+            field_init,
+            field_ref,
+            field_class: field_class.to_string(),
+            row_field_decl,
+        }
+    }
+
+    /// Returns code snippets used for declaring field types, initializations,
+    /// references, and the field classes to import.
+    fn gen_field_defs(&self, fields: &[TableSchemaField]) -> FieldSnippets {
+        let fields_data = fields
+            .iter()
+            .map(|f| self.gen_field(f))
+            .collect::<Vec<FieldData>>();
+
+        let fields_types = fields_data
+            .iter()
+            .map(|fd| fd.field_type_decl.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n    ");
+        let fields_inits = fields_data
+            .iter()
+            .map(|fd| format!("{},", fd.field_init))
+            .collect::<Vec<String>>()
+            .join("\n            ");
+        let fields_list = fields_data
+            .iter()
+            .map(|fd| format!("fields.{}.into(),", fd.field_ref))
+            .collect::<Vec<String>>()
+            .join("\n                ");
+        let field_classes = fields_data
+            .iter()
+            .map(|fd| fd.field_class.as_str())
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let row_fields = fields_data
+            .iter()
+            .map(|fd| fd.row_field_decl.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n    ");
+        FieldSnippets {
+            fields_types,
+            fields_inits,
+            fields_list,
+            field_classes,
+            row_fields,
+        }
+    }
+}
+
+impl Generator for Rust<'_> {
+    fn dataset(&self) -> &GetDatasetVersionResponse {
+        self.dataset
+    }
+
+    fn resource_table(&self, r: &Table) -> DynamicAsset {
+        let dataset = self.dataset();
+        let dataset_id = dataset.uuid.to_string();
+        let dataset_name = self.dataset_name(&dataset.name);
+
+        let resource_name = &r.name;
+        let class_name = clean_name(resource_name).to_case(Case::Pascal);
+        let TableSchema { fields, .. } = &r.schema;
+        let FieldSnippets {
+            fields_types,
+            fields_inits,
+            fields_list,
+            field_classes,
+            row_fields,
+        } = self.gen_field_defs(fields);
+
+        #[derive(Serialize)]
+        struct Context {
+            class_doc: String,
+            dataset_id: String,
+            dataset_name: String,
+            dataset_version: String,
+            class_name: String,
+            resource_name: String,
+            fields_types: String,
+            fields_inits: String,
+            fields_list: String,
+            field_classes: String,
+            row_fields: String,
+        }
+        let context = Context {
+            class_doc: doc_comment(r.description.as_deref(), ""),
+            dataset_id,
+            dataset_name,
+            dataset_version: dataset.version.version.to_string(),
+            class_name: class_name.clone(),
+            resource_name: resource_name.to_string(),
+            fields_types,
+            fields_inits,
+            fields_list,
+            field_classes,
+            row_fields,
+        };
+
+        let code = match self.tt.render(TABLE_STRUCT_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render table struct with error {:?}", e),
+        };
+
+        let path = Path::new(&self.source_dir())
+            .join("tables")
+            .join(self.file_name(&class_name));
+        DynamicAsset {
+            path: Box::new(path),
+            name: class_name,
+            content: code,
+        }
+    }
+
+    fn version(&self) -> DynamicAsset {
+        let src_dir = self.source_dir();
+        let src_dir = Path::new(&src_dir);
+        #[derive(Serialize)]
+        struct Context {
+            code_version: String,
+        }
+        let context = Context {
+            code_version: String::from(RUST_VERSION),
+        };
+        let code = match self.tt.render(VERSION_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render version file with error {:?}", e),
+        };
+
+        DynamicAsset {
+            path: Box::new(src_dir.join("version.rs")),
+            name: "CODE_VERSION".into(),
+            content: code,
+        }
+    }
+
+    fn static_assets(&self) -> Vec<StaticAsset> {
+        Asset::iter()
+            .chain(ProtoAsset::iter())
+            .map(|p| {
+                // Static paths are relative to the crate root, e.g., `src/field.rs`.
+                let path = Box::new(PathBuf::from(p.to_string()));
+                let content = Asset::get(&p).unwrap_or_else(|| ProtoAsset::get(&p).unwrap());
+                StaticAsset { path, content }
+            })
+            .collect()
+    }
+
+    fn entry_file_name(&self) -> String {
+        String::from("lib.rs")
+    }
+
+    fn root_dir(&self) -> PathBuf {
+        let dataset = self.dataset();
+        let package_directory = format!(
+            "{}@{}",
+            self.dataset_name(&dataset.name),
+            package_version(&dataset.version.version)
+        );
+        Path::new("rust").join(package_directory)
+    }
+
+    fn source_dir(&self) -> String {
+        String::from("src")
+    }
+
+    fn variable_name(&self, name: &str) -> String {
+        identifier(&clean_name(name).to_case(Case::Snake))
+    }
+
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.rs", clean_name(name).to_case(Case::Snake))
+    }
+
+    fn dataset_name(&self, name: &str) -> String {
+        clean_name(name).to_case(Case::Kebab)
+    }
+
+    fn manifest(&self, changelog: &Changelog) -> Manifest {
+        let dp = self.dataset();
+        let pkg_name: String = self.dataset_name(&dp.name);
+        let version = package_version(&dp.version.version);
+
+        #[derive(Serialize)]
+        struct CargoToml<'a> {
+            package: Package<'a>,
+            dependencies: BTreeMap<&'a str, Dependency<'a>>,
+            #[serde(rename = "build-dependencies")]
+            build_dependencies: BTreeMap<&'a str, Dependency<'a>>,
+            // Keeps the crate out of any enclosing workspace.
+            workspace: BTreeMap<&'a str, &'a str>,
+        }
+
+        #[derive(Serialize)]
+        struct Package<'a> {
+            name: String,
+            version: String,
+            edition: String,
+            description: String,
+            metadata: PackageMetadata<'a>,
+        }
+
+        #[derive(Serialize)]
+        struct PackageMetadata<'a> {
+            dpm: DpmMetadata<'a>,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct DpmMetadata<'a> {
+            changelog: &'a str,
+            release_notes: &'a str,
+        }
+
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        enum Dependency<'a> {
+            Version(&'a str),
+            Detailed {
+                version: &'a str,
+                features: Vec<&'a str>,
+            },
+        }
+
+        let cargo_toml = CargoToml {
+            package: Package {
+                name: pkg_name,
+                version,
+                edition: String::from("2021"),
+                description: dp.description.clone(),
+                metadata: PackageMetadata {
+                    dpm: DpmMetadata {
+                        changelog: CHANGELOG_FILE_NAME,
+                        release_notes: &changelog.release_notes,
+                    },
+                },
+            },
+            dependencies: BTreeMap::from_iter([
+                ("chrono", Dependency::Version("0.4")),
+                ("directories", Dependency::Version("5")),
+                ("prost", Dependency::Version("0.11")),
+                (
+                    "serde",
+                    Dependency::Detailed {
+                        version: "1",
+                        features: vec!["derive"],
+                    },
+                ),
+                ("serde_json", Dependency::Version("1")),
+                (
+                    "tonic",
+                    Dependency::Detailed {
+                        version: "0.9",
+                        features: vec!["tls", "tls-roots"],
+                    },
+                ),
+            ]),
+            build_dependencies: BTreeMap::from_iter([
+                ("protoc-bin-vendored", Dependency::Version("3")),
+                ("tonic-build", Dependency::Version("0.9")),
+            ]),
+            workspace: BTreeMap::new(),
+        };
+
+        let cargo_toml = match toml::ser::to_string_pretty(&cargo_toml) {
+            Ok(res) => res,
+            Err(e) => panic!("Failed to TOML serialize \"Cargo.toml\" with error {e}"),
+        };
+
+        Manifest {
+            file_name: String::from("Cargo.toml"),
+            description: cargo_toml,
+        }
+    }
+
+    fn entry_code(&self, imports: Vec<ItemRef>) -> DynamicAsset {
+        #[derive(Serialize)]
+        struct Context {
+            imports: Vec<ItemRef>,
+        }
+
+        let src_dir = self.source_dir();
+        let src_dir = Path::new(&src_dir);
+
+        // Table modules are named after their file, e.g. `tables/my_table.rs`
+        // is declared as `mod my_table`.
+        let context = Context {
+            imports: imports
+                .iter()
+                .map(|x| ItemRef {
+                    path: Box::new(PathBuf::from(identifier(
+                        &x.path.file_stem().unwrap().to_string_lossy(),
+                    ))),
+                    ref_name: x.ref_name.to_string(),
+                })
+                .collect(),
+        };
+
+        let content = match self.tt.render(ENTRY_POINT_TEMPLATE_NAME, &context) {
+            Ok(result) => result,
+            Err(e) => panic!("Failed to render entry point code with error {:?}", e),
+        };
+
+        DynamicAsset {
+            path: Box::new(src_dir.join(self.entry_file_name())),
+            name: "".into(),
+            content,
+        }
+    }
+
+    /// Builds the generated package. E.g., for the `Rust` target, builds and packages the crate
+    /// using `cargo build`, and `cargo package`.
+    fn build_package(&self, path: &Path) {
+        eprintln!("Building Rust crate");
+        exec_cmd("build crate", path, "cargo", &["build"]);
+
+        exec_cmd(
+            "package crate",
+            path,
+            "cargo",
+            &["package", "--no-verify", "--allow-dirty"],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{api::DatasetVersion, descriptor::Dataset};
+
+    #[test]
+    fn clean_name_works() {
+        assert_eq!(clean_name("oneword"), "oneword");
+        assert_eq!(clean_name("two W0rds"), "two W0rds");
+        assert_eq!(clean_name("words, with fie;nds"), "words with fiends");
+        assert_eq!(clean_name("underscores_ are_ok"), "underscores_ are_ok");
+        assert_eq!(clean_name("dots.are.not"), "dotsarenot");
+        assert_eq!(clean_name("dine-and-dash"), "dine-and-dash");
+    }
+
+    #[test]
+    fn identifier_works() {
+        assert_eq!(identifier("price"), "price");
+        assert_eq!(identifier("type"), "r#type");
+        assert_eq!(identifier("self"), "self_");
+        assert_eq!(identifier("1st_place"), "_1st_place");
+    }
+
+    #[test]
+    fn root_dir_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let res = GetDatasetVersionResponse {
+            name: dataset.name.to_string(),
+            uuid: Uuid::from_bytes(dataset.id.as_bytes().to_owned()),
+            description: dataset.description.unwrap_or("".into()),
+            version: DatasetVersion {
+                version: dataset.version,
+                accelerated: false,
+                dataset: dataset.tables,
+                patch_state: None,
+                patch_state_data: None,
+            },
+        };
+        let generator = Box::new(Rust::new(&res));
+        let expected_dir = format!("test-snowflake@0.1.0-{}", RUST_VERSION);
+        assert_eq!(generator.root_dir(), Path::new("rust").join(expected_dir));
+    }
+}
@@ -6,16 +6,25 @@ use std::io;
 use std::path::PathBuf;
 
 mod build_package;
+mod changelog;
 mod dataset;
 mod init;
 mod login;
+mod output;
+pub mod postgres;
 mod publish;
+mod query;
+mod shell;
 pub mod snowflake;
 mod source;
+mod spec;
 mod update;
 
 use self::dataset::DatasetAction;
+use self::output::{Output, Render};
+use self::query::QueryArgs;
 use self::source::SourceAction;
+use self::spec::SpecAction;
 use super::codegen::Target;
 use super::descriptor::Name;
 use clap_complete::{self, generate, Shell};
@@ -25,7 +34,9 @@ enum Command {
     /// Create a dataset spec: a definition of a virtual dataset containing data from
     /// some source.
     Init {
-        /// Name of source that will supply data for the dataset.
+        /// Name of source that will supply data for the dataset. A local
+        /// DuckDB database or directory of Parquet files is given as
+        /// "duckdb:PATH" or "parquet:PATH".
         #[arg(short, long = "source", value_name = "NAME")]
         source_name: String,
 
@@ -37,6 +48,18 @@ enum Command {
         #[arg(short, long, value_name = "FILE", default_value = init::DEFAULT_SPEC_PATH)]
         output: PathBuf,
 
+        /// Table to include, and the fields that make up its primary key, given
+        /// as "TABLE=FIELD[,FIELD...]". TABLE may be a name or a qualified name.
+        /// May be given multiple times. When given, tables are not prompted for.
+        #[arg(long = "primary-key", value_name = "TABLE=FIELDS")]
+        primary_keys: Vec<init::PrimaryKeyArg>,
+
+        /// JSON file mapping each table to include to the fields that make up
+        /// its primary key, e.g. {"PUBLIC.ORDERS": ["ID"]}. When given, tables
+        /// are not prompted for.
+        #[arg(long, value_name = "FILE")]
+        selection: Option<PathBuf>,
+
         /// Additional, source-type-specific filters to apply while performing
         /// catalog discovery on the source.
         #[command(subcommand)]
@@ -103,6 +126,31 @@ enum Command {
         /// Spec defining the dataset to be published
         #[arg(short, long, value_name = "FILE", default_value = init::DEFAULT_SPEC_PATH)]
         spec: PathBuf,
+
+        /// Refuse to publish breaking changes, compared with the latest
+        /// release, unless the major version is bumped
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Query a published dataset, or a draft of one, via dpm-agent
+    ///
+    /// The query is given by flags, or by a JSON file (-f/--file) with the
+    /// keys "dataset", "table", "select", "filter", "groupBy", "orderBy",
+    /// "limit" and "dryRun". Expressions use a compact, SQL-like syntax:
+    ///
+    ///   dpm query -d orders@1.0.0 -t ORDERS \
+    ///     --select "STATE, sum(PRICE) as total" \
+    ///     --filter "year(ORDERED_AT) = 2023 and STATE in ('CA', 'NY')" \
+    ///     --order-by "total desc" --limit 10
+    #[command(verbatim_doc_comment)]
+    Query(QueryArgs),
+
+    /// Explore a dataset interactively, by executing statements via dpm-agent
+    Shell {
+        /// Dataset identifier of the form "<dataset name>@<version>".
+        #[arg(short = 'd', long = "dataset", value_name = "DATASET_REF")]
+        dataset_ref: String,
     },
 
     /// Create and list sources
@@ -111,6 +159,12 @@ enum Command {
         action: SourceAction,
     },
 
+    /// Work with dataset specs
+    Spec {
+        #[command(subcommand)]
+        action: SpecAction,
+    },
+
     /// Update (refresh) the tables in a dataset
     ///
     /// During an update the tables in the input spec are compared to
@@ -119,15 +173,62 @@ enum Command {
     /// the input spec is copied to a new file with the ".backup" suffix
     /// appended to its file name. In its place an updated spec is written
     /// that specifies a dataset containing the same set of tables, but with
-    /// up-to-date schemas.
+    /// up-to-date schemas. With --add-tables, tables in the source that aren't
+    /// in the dataset may be added to it as well.
     ///
     /// After this operation one will typically repeat `build-package` to
     /// validate the resulting dataset, and once they're satisfied will run
     /// `publish` to make the new version available to others.
+    ///
+    /// The exit status is 0 if there were no changes or they were handled, 1
+    /// if `--check` found changes, and 2 on error.
     Update {
         /// Dataset spec to update
         #[arg(short, long, value_name = "FILE", default_value = init::DEFAULT_SPEC_PATH)]
         spec: PathBuf,
+
+        /// Only report differences, exiting with status 1 if there are any;
+        /// don't write anything
+        #[arg(long, conflicts_with = "yes")]
+        check: bool,
+
+        /// Write the updated spec without prompting
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Format to report differences in. For JSON, use --output-format json
+        #[arg(long, value_enum, default_value_t = update::Format::Text)]
+        format: update::Format,
+
+        /// Offer to add tables that are in the source but not in the dataset.
+        /// Unless --primary-key is given, the tables are prompted for.
+        #[arg(long)]
+        add_tables: bool,
+
+        /// Table to add, and the fields that make up its primary key, given as
+        /// "TABLE=FIELD[,FIELD...]". May be given multiple times. Requires
+        /// --add-tables.
+        #[arg(
+            long = "primary-key",
+            value_name = "TABLE=FIELDS",
+            requires = "add_tables"
+        )]
+        primary_keys: Vec<init::PrimaryKeyArg>,
+
+        /// Similarity, as a percentage, at which a table or field is considered
+        /// renamed rather than removed and added
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            default_value_t = update::DEFAULT_RENAME_THRESHOLD,
+            value_parser = clap::value_parser!(u8).range(0..=100)
+        )]
+        find_renames: u8,
+
+        /// Source-type-specific filters on the tables to add. Requires
+        /// --add-tables.
+        #[command(subcommand)]
+        refinement: Option<init::DescribeRefinement>,
     },
 
     /// Write the tab completion file for a shell
@@ -140,6 +241,11 @@ enum Command {
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 pub struct App {
+    /// Format to print results in. With json or yaml, only the result is
+    /// written to stdout, and messages go to stderr.
+    #[arg(long, value_enum, default_value_t = Output::Table, global = true)]
+    output_format: Output,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -148,73 +254,159 @@ fn print_completions<G: clap_complete::Generator>(gen: G, cmd: &mut clap::Comman
     generate(gen, cmd, cmd.get_name().to_string(), &mut io::stdout());
 }
 
+/// Prints the result of a command in `output`. If the command failed, its
+/// error is printed after `failure` and the process exits with status 1.
+fn finish<R: Render>(result: anyhow::Result<R>, output: Output, failure: &str) {
+    if let Err(e) = result.and_then(|result| output.print(&result)) {
+        eprintln!("{failure}: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
 impl App {
     pub async fn exec(self) {
+        let output = self.output_format;
         match self.command {
             Command::Init {
                 source_name,
                 dataset_name,
-                output,
+                output: spec_path,
+                primary_keys,
+                selection,
                 refinement,
-            } => {
-                if let Err(source) =
-                    init::init(&source_name, &dataset_name, &output, refinement).await
-                {
-                    eprintln!("init failed: {:#}", source);
-                    std::process::exit(1);
-                };
-            }
+            } => finish(
+                init::init(
+                    &source_name,
+                    &dataset_name,
+                    &spec_path,
+                    primary_keys,
+                    selection.as_deref(),
+                    refinement,
+                )
+                .await,
+                output,
+                "init failed",
+            ),
             Command::BuildPackage {
                 spec,
                 dataset_ref,
                 target,
                 out_dir,
                 assume_yes,
+            } => finish(
+                build_package::build(spec, dataset_ref, target, out_dir, assume_yes).await,
+                output,
+                "package build failed",
+            ),
+            Command::Login => finish(login::login().await, output, "login failed"),
+            Command::Dataset {
+                action: DatasetAction::List,
+            } => finish(dataset::list().await, output, "dataset listing failed"),
+            Command::Dataset {
+                action: DatasetAction::Show { dataset },
+            } => finish(dataset::show(&dataset).await, output, "dataset show failed"),
+            Command::Dataset {
+                action:
+                    DatasetAction::Diff {
+                        from,
+                        to,
+                        format,
+                        find_renames,
+                    },
             } => {
-                if let Err(e) =
-                    build_package::build(spec, dataset_ref, target, out_dir, assume_yes).await
-                {
-                    eprintln!("package build failed: {:#}", e);
+                if let Err(e) = dataset::diff(&from, &to, format, output, find_renames).await {
+                    eprintln!("dataset diff failed: {:#}", e);
                     std::process::exit(1);
                 }
             }
-            Command::Login => {
-                if let Err(source) = login::login().await {
-                    eprintln!("login failed: {}", source)
-                };
+            Command::Publish { spec, strict } => match publish::publish(&spec, strict).await {
+                Ok(None) => (),
+                result => finish(result.map(Option::unwrap), output, "publish failed"),
+            },
+            Command::Query(args) => {
+                if let Err(e) = query::query(args, output).await {
+                    eprintln!("query failed: {:#}", e);
+                    std::process::exit(1);
+                }
             }
-            Command::Dataset {
-                action: DatasetAction::List,
-            } => {
-                if let Err(e) = dataset::list().await {
-                    eprintln!("dataset listing failed: {:#}", e);
+            Command::Shell { dataset_ref } => {
+                if let Err(e) = shell::shell(&dataset_ref).await {
+                    eprintln!("shell failed: {:#}", e);
                     std::process::exit(1);
                 }
             }
-            Command::Publish { spec } => match publish::publish(&spec).await {
-                Ok(_) => (),
-                Err(e) => eprintln!("publish failed: {}", e),
-            },
             Command::Source {
                 action: SourceAction::Create(cs),
-            } => match source::create(&cs).await {
-                Ok(()) => (),
-                Err(e) => eprintln!("error creating source: {}", e),
-            },
+            } => finish(source::create(*cs).await, output, "error creating source"),
             Command::Source {
                 action: SourceAction::List,
-            } => match source::list().await {
-                Ok(()) => (),
-                Err(e) => eprintln!("error listing sources: {}", e),
-            },
-            Command::Update { spec } => {
-                match update::update(&spec).await {
+            } => finish(source::list().await, output, "error listing sources"),
+            Command::Spec {
+                action: SpecAction::Lint { spec },
+            } => {
+                let lint = spec::lint(&spec);
+                let check = lint.as_ref().map_or(Ok(()), |lint| lint.check());
+                finish(lint, output, "lint failed");
+                if let Err(e) = check {
+                    eprintln!("lint failed: {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+            Command::Spec {
+                action:
+                    SpecAction::Convert {
+                        spec,
+                        output: converted,
+                        force,
+                    },
+            } => finish(
+                spec::convert(&spec, &converted, force),
+                output,
+                "conversion failed",
+            ),
+            Command::Spec {
+                action: SpecAction::Schema { output: path },
+            } => finish(
+                spec::schema(path.as_deref()),
+                output,
+                "error writing schema",
+            ),
+            Command::Update {
+                spec,
+                check,
+                yes,
+                format,
+                add_tables,
+                primary_keys,
+                refinement,
+                find_renames,
+            } => {
+                if refinement.is_some() && !add_tables {
+                    eprintln!("error: a refinement requires --add-tables");
+                    std::process::exit(update::EXIT_ERROR);
+                }
+                let add_tables = add_tables.then_some(update::AddTables {
+                    primary_keys,
+                    refinement,
+                });
+                let mode = if check {
+                    update::Mode::Check
+                } else if yes {
+                    update::Mode::Yes
+                } else {
+                    update::Mode::Prompt
+                };
+                match update::update(&spec, mode, format, output, add_tables, find_renames).await {
+                    Ok(true) if mode == update::Mode::Check => {
+                        std::process::exit(update::EXIT_CHANGES_FOUND)
+                    }
                     Ok(_) => (),
                     Err(e) => {
                         eprintln!("error: {:#}", e);
                         // e.chain()
                         //     .skip(1)
                         //     .for_each(|cause| eprintln!("  ...because: {}", cause));
+                        std::process::exit(update::EXIT_ERROR);
                     }
                 };
             }
@@ -225,3 +417,32 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_format_is_global() {
+        App::command().debug_assert();
+
+        let parse = |args: &[&str]| App::try_parse_from(args).unwrap();
+        let app = parse(&["dpm", "--output-format", "json", "source", "list"]);
+        assert_eq!(app.output_format, Output::Json);
+        let app = parse(&[
+            "dpm",
+            "init",
+            "-s",
+            "src",
+            "-n",
+            "ds",
+            "--output-format",
+            "yaml",
+        ]);
+        assert_eq!(app.output_format, Output::Yaml);
+        match app.command {
+            Command::Init { output, .. } => assert_eq!(output, PathBuf::from("dataset.json")),
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+}
@@ -5,77 +5,130 @@ use std::{
 
 use anyhow::{bail, Context, Result};
 use semver::Version;
+use serde::Serialize;
 
+use super::{changelog, output::Render};
 use crate::{
-    api::{Client, CreateDatasetVersion, GetDatasetVersionResponse, PatchState},
-    codegen::{generate_package, Target},
+    api::{Client, CreateDatasetVersion, DatasetVersion, GetDatasetVersionResponse, PatchState},
+    codegen::{generate_package, Changelog, Target},
     descriptor::Dataset,
     session,
 };
 
+/// A data package built by `build-package`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Built {
+    dataset: String,
+    version: Version,
+    target: &'static str,
+    /// Directory the package was generated and built in.
+    directory: PathBuf,
+    /// Files generated for the package, before it was built.
+    files: Vec<PathBuf>,
+}
+
+impl Render for Built {
+    fn print_table(&self) -> Result<()> {
+        eprintln!(
+            "built {} package for {}@{} in {}",
+            self.target,
+            self.dataset,
+            self.version,
+            self.directory.display()
+        );
+        Ok(())
+    }
+}
+
 pub async fn build(
     descriptor: PathBuf,
     dataset_ref: Option<String>,
     target: Target,
     out_dir: PathBuf,
     assume_yes: bool,
-) -> Result<()> {
-    let session = session::get_token().expect("unable to get session");
-    let client = Client::new(&session).expect("unable to get client");
-
+) -> Result<Built> {
     // `descriptor` is always defined (possibly via its default_value), whereas
     // the caller may instead opt to build a published dataset via --dataset.
     // Before reaching this function, clap will have verified that if --dataset
     // was given, --descriptor was not given.
-    let build_input: GetDatasetVersionResponse = if let Some(dataset_ref) = dataset_ref {
-        let dataset_identifier: Vec<&str> = dataset_ref.split('@').collect();
-        if dataset_identifier.len() != 2 {
-            bail!("invalid --dataset value; expected \"<dataset name>@<version>\"")
+    let dataset = match dataset_ref {
+        Some(_) => None,
+        None => {
+            let dataset = Dataset::read(&descriptor)
+                .with_context(|| format!("failed to read {}", descriptor.display()))?;
+            dataset.check()?;
+            Some(dataset)
         }
-        let version: Version =
-            Version::parse(dataset_identifier[1]).expect("dataset identifier `version` is invalid");
-
-        match client
-            .get_dataset_version(dataset_identifier[0], version)
-            .await?
-        {
-            Some(response) => response,
-            None => bail!("dataset or dataset version not found: \"{}\"", dataset_ref),
-        }
-    } else {
-        let dataset = Dataset::read(&descriptor)
-            .with_context(|| format!("failed to read {}", descriptor.display()))?;
+    };
 
+    // Datasets with local sources are unknown to DPM Cloud, so their packages
+    // are built from the spec alone.
+    if let Some(dataset) = dataset.as_ref().filter(|d| d.is_local()) {
         eprintln!(
-            "creating draft version of {}@{}",
+            "building {}@{} from local sources; no draft version is created",
             dataset.name, dataset.version
         );
-
-        let created_version = client
-            .create_version(
-                dataset.id,
-                &dataset.version,
-                &CreateDatasetVersion {
-                    name: &dataset.name,
-                    draft: true,
-                    accelerated: false,
-                    description: &dataset.description.clone().unwrap_or("".into()),
-                    tables: &dataset.tables,
-                },
-            )
-            .await?;
-
-        eprintln!(
-            "draft version created: {}@{}",
-            dataset.name, created_version.version
-        );
-        eprintln!("tip: Your drafts are queryable only by you. To enable access by others, create a release version with `dpm publish`.");
-
-        GetDatasetVersionResponse {
+        let build_input = GetDatasetVersionResponse {
             name: dataset.name.to_string(),
             uuid: uuid::Uuid::parse_str(&dataset.id.to_string()).unwrap(),
-            description: dataset.description.unwrap_or("".into()),
-            version: created_version,
+            description: dataset.description.clone().unwrap_or("".into()),
+            version: DatasetVersion {
+                version: dataset.version.clone(),
+                accelerated: false,
+                patch_state: None,
+                patch_state_data: None,
+                dataset: dataset.tables.clone(),
+            },
+        };
+        let changelog = changelog::changelog(&dataset.name, &[], &dataset.version, &dataset.tables);
+        return Ok(package(
+            build_input,
+            &changelog,
+            target,
+            out_dir,
+            assume_yes,
+        ));
+    }
+
+    let session = session::get_token().expect("unable to get session");
+    let client = Client::new(&session).expect("unable to get client");
+
+    let build_input: GetDatasetVersionResponse = match (dataset_ref, dataset) {
+        (Some(dataset_ref), _) => super::dataset::get_version(&client, &dataset_ref).await?,
+        (None, None) => unreachable!("the spec is read when no dataset is given"),
+        (None, Some(dataset)) => {
+            eprintln!(
+                "creating draft version of {}@{}",
+                dataset.name, dataset.version
+            );
+
+            let created_version = client
+                .create_version(
+                    dataset.id,
+                    &dataset.version,
+                    &CreateDatasetVersion {
+                        name: &dataset.name,
+                        draft: true,
+                        accelerated: false,
+                        description: &dataset.description.clone().unwrap_or("".into()),
+                        tables: &dataset.tables,
+                    },
+                )
+                .await?;
+
+            eprintln!(
+                "draft version created: {}@{}",
+                dataset.name, created_version.version
+            );
+            eprintln!("tip: Your drafts are queryable only by you. To enable access by others, create a release version with `dpm publish`.");
+
+            GetDatasetVersionResponse {
+                name: dataset.name.to_string(),
+                uuid: uuid::Uuid::parse_str(&dataset.id.to_string()).unwrap(),
+                description: dataset.description.unwrap_or("".into()),
+                version: created_version,
+            }
         }
     };
 
@@ -121,11 +174,44 @@ Resolve the error above, then try building again.", error_message);
         }
     }
 
+    let changelog = changelog::fetch(
+        &client,
+        &build_input.uuid.to_string(),
+        &build_input.name,
+        &build_input.version.version,
+        &build_input.version.dataset,
+    )
+    .await
+    .context("failed to fetch the dataset's versions for its changelog")?;
+
+    Ok(package(
+        build_input,
+        &changelog,
+        target,
+        out_dir,
+        assume_yes,
+    ))
+}
+
+/// Generates and builds the package of `build_input` in `out_dir`.
+fn package(
+    build_input: GetDatasetVersionResponse,
+    changelog: &Changelog,
+    target: Target,
+    out_dir: PathBuf,
+    assume_yes: bool,
+) -> Built {
     create_dir_all(&out_dir).expect("error creating output directory");
     check_output_dir(&out_dir);
-    generate_package(&build_input, &target, &out_dir, assume_yes);
-
-    Ok(())
+    let package = generate_package(&build_input, changelog, &target, &out_dir, assume_yes);
+
+    Built {
+        dataset: build_input.name,
+        version: build_input.version.version,
+        target: target.name(),
+        directory: package.root,
+        files: package.files,
+    }
 }
 
 /// Checks that the output directory exists and is accessible.
@@ -0,0 +1,174 @@
+//! Changelogs of datasets, derived from the differences between the tables of
+//! consecutive versions.
+
+use anyhow::Result;
+use semver::Version;
+
+use super::update::{self, Impact};
+use crate::{
+    api::{Client, DatasetVersion},
+    codegen::Changelog,
+    descriptor::Table,
+};
+
+/// Fetches the releases of the dataset identified by `dataset_id` and returns
+/// the changelog for `tables` as version `version` of it.
+pub async fn fetch(
+    client: &Client,
+    dataset_id: &str,
+    name: &str,
+    version: &Version,
+    tables: &[Table],
+) -> Result<Changelog> {
+    let releases = client
+        .get_dataset_versions(dataset_id)
+        .await?
+        .map(|response| response.dataset_versions)
+        .unwrap_or_default();
+    Ok(changelog(name, &releases, version, tables))
+}
+
+/// Returns the changelog of dataset `name` up to version `version`, which has
+/// tables `tables`, given the dataset's published versions. Only releases
+/// preceding `version` are included; pre-releases are ignored.
+pub fn changelog(
+    name: &str,
+    versions: &[DatasetVersion],
+    version: &Version,
+    tables: &[Table],
+) -> Changelog {
+    let mut history: Vec<(&Version, &[Table])> = versions
+        .iter()
+        .filter(|v| v.version.pre.is_empty() && &v.version < version)
+        .map(|v| (&v.version, v.dataset.as_slice()))
+        .collect();
+    history.sort_by_key(|(v, _)| *v);
+    history.push((version, tables));
+
+    let sections: Vec<(&Version, String)> = history
+        .iter()
+        .enumerate()
+        .map(|(i, &(version, tables))| {
+            let body = match i.checked_sub(1).map(|j| history[j].1) {
+                None => "Initial release.\n".to_string(),
+                Some(previous) => section(&update::changes(
+                    previous,
+                    tables,
+                    update::DEFAULT_RENAME_THRESHOLD,
+                )),
+            };
+            (version, body)
+        })
+        .collect();
+
+    let mut markdown = format!(
+        "# Changelog\n\nChanges to the tables and fields of the {name} dataset, by version.\n"
+    );
+    for (version, body) in sections.iter().rev() {
+        markdown.push_str(&format!("\n## {version}\n\n{body}"));
+    }
+
+    Changelog {
+        markdown,
+        release_notes: sections.last().map(|(_, body)| body.clone()).unwrap(),
+    }
+}
+
+/// Renders the changes in a version, grouped by their impact.
+fn section(changes: &[update::Change]) -> String {
+    if changes.is_empty() {
+        return "No changes to tables or fields.\n".into();
+    }
+
+    [
+        (Impact::Major, "Breaking changes"),
+        (Impact::Minor, "Additions"),
+        (Impact::Patch, "Other changes"),
+    ]
+    .into_iter()
+    .filter_map(|(impact, heading)| {
+        let items = changes
+            .iter()
+            .filter(|c| c.impact == impact)
+            .fold(String::new(), |items, c| {
+                items + "- " + &c.description + "\n"
+            });
+        (!items.is_empty()).then(|| format!("### {heading}\n\n{items}"))
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptor::Dataset;
+
+    #[test]
+    fn changelog_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let release = |version: &str, tables: &[Table]| DatasetVersion {
+            version: version.parse().unwrap(),
+            accelerated: false,
+            patch_state: None,
+            patch_state_data: None,
+            dataset: tables.to_vec(),
+        };
+
+        let mut tables = dataset.tables.clone();
+        tables[0].schema.fields.remove(2);
+        let mut described = dataset.tables.clone();
+        described[0].description = Some("Sales".into());
+        let versions = [
+            release("1.0.0", &dataset.tables),
+            release("1.1.0-rc.1", &[]),
+            release("1.0.1", &described),
+            release("3.0.0", &[]),
+        ];
+
+        let changelog = changelog(
+            "test-snowflake",
+            &versions,
+            &"2.0.0".parse().unwrap(),
+            &tables,
+        );
+        assert_eq!(
+            changelog.markdown,
+            r#"# Changelog
+
+Changes to the tables and fields of the test-snowflake dataset, by version.
+
+## 2.0.0
+
+### Breaking changes
+
+- field "UK_REAL_ESTATE_RECORDS"."DATE_OF_TRANSFER" removed
+
+### Other changes
+
+- table "UK_REAL_ESTATE_RECORDS" description changed
+
+## 1.0.1
+
+### Other changes
+
+- table "UK_REAL_ESTATE_RECORDS" description changed
+
+## 1.0.0
+
+Initial release.
+"#
+        );
+        assert_eq!(
+            changelog.release_notes,
+            r#"### Breaking changes
+
+- field "UK_REAL_ESTATE_RECORDS"."DATE_OF_TRANSFER" removed
+
+### Other changes
+
+- table "UK_REAL_ESTATE_RECORDS" description changed
+"#
+        );
+    }
+}
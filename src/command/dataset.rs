@@ -1,66 +1,293 @@
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
 use clap::Subcommand;
 use comfy_table::Table;
+use semver::Version;
+use serde::Serialize;
 
-use crate::{api::Client, session};
+use super::{
+    init,
+    output::{Output, Render},
+    update,
+};
+use crate::{
+    api::{Client, DatasetVersion, GetDatasetResponse, GetDatasetVersionResponse},
+    descriptor::{self, Dataset},
+    session,
+    util::SourcePath,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum DatasetAction {
     /// List datasets this account is authorized to use (build, query).
     List,
+
+    /// Show a dataset's description and versions, and a version's tables.
+    ///
+    /// Given "<dataset name>@<version>", the version's tables are described
+    /// too: their source paths, primary keys and fields.
+    Show {
+        /// Dataset name, optionally followed by "@<version>".
+        #[arg(value_name = "DATASET[@VERSION]")]
+        dataset: String,
+    },
+
+    /// Show the table and field changes between two versions of a dataset.
+    ///
+    /// Each version is either published, given as "<dataset name>@<version>",
+    /// or a local spec file.
+    Diff {
+        /// Version to compare from: a dataset identifier or spec file.
+        from: String,
+
+        /// Version to compare to: a dataset identifier or spec file.
+        #[arg(default_value = init::DEFAULT_SPEC_PATH)]
+        to: String,
+
+        /// Format to print the changes in. For JSON, use --output-format json.
+        #[arg(long, value_enum, default_value_t = update::Format::Text)]
+        format: update::Format,
+
+        /// Similarity, as a percentage, at which a table or field is
+        /// considered renamed rather than removed and added.
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            default_value_t = update::DEFAULT_RENAME_THRESHOLD,
+            value_parser = clap::value_parser!(u8).range(0..=100)
+        )]
+        find_renames: u8,
+    },
+}
+
+impl Render for Vec<GetDatasetResponse> {
+    fn print_table(&self) -> Result<()> {
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::UTF8_FULL);
+        table.set_header(vec!["Name", "Version", "Performance mode", "State"]);
+        table.add_rows(self.iter().flat_map(|p| {
+            p.dataset_versions.iter().map(|pv| {
+                vec![
+                    p.name.to_owned(),
+                    pv.version.to_string(),
+                    performance_mode(pv).into(),
+                    acceleration_state(pv),
+                ]
+            })
+        }));
+        println!("{table}");
+        Ok(())
+    }
 }
 
-pub async fn list() -> Result<()> {
+/// Returns the datasets this account is authorized to use, by name, with their
+/// versions in reverse version order.
+pub async fn list() -> Result<Vec<GetDatasetResponse>> {
     let token = session::get_token()?;
     let client = Client::new(&token)?;
 
-    let mut response = client.list_datasets().await?;
-    response
-        .datasets
-        .sort_unstable_by(|a, b| a.name.cmp(&b.name));
-    let rows: Vec<Vec<String>> = response
-        .datasets
-        .into_iter()
-        .flat_map(|mut p| {
-            p.dataset_versions
-                .sort_unstable_by(|a, b| b.version.cmp(&a.version));
-            p.dataset_versions
+    let mut datasets = client.list_datasets().await?.datasets;
+    datasets.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    for p in &mut datasets {
+        p.dataset_versions
+            .sort_unstable_by(|a, b| b.version.cmp(&a.version));
+    }
+    Ok(datasets)
+}
+
+pub async fn diff(
+    from: &str,
+    to: &str,
+    format: update::Format,
+    output: Output,
+    rename_threshold: u8,
+) -> Result<()> {
+    let mut client = None;
+    let old = tables(&mut client, from).await?;
+    let new = tables(&mut client, to).await?;
+
+    eprintln!("comparing {from} to {to}");
+    let diff = update::compare(&old, &new, format, rename_threshold);
+    output.print(&diff)
+}
+
+/// Returns the tables of a dataset version given either by a path to a spec or
+/// a dataset identifier. `client` is created when first needed, so that
+/// comparing specs doesn't require logging in.
+async fn tables(client: &mut Option<Client>, version: &str) -> Result<Vec<descriptor::Table>> {
+    if Path::new(version).is_file() {
+        let dataset =
+            Dataset::read(version).with_context(|| format!("failed to read {}", version))?;
+        return Ok(dataset.tables);
+    }
+    if !version.contains('@') {
+        bail!("\"{version}\" is neither a spec file nor a dataset identifier of the form \"<dataset name>@<version>\"");
+    }
+
+    let client = match client {
+        Some(client) => client,
+        None => client.insert(Client::new(&session::get_token()?)?),
+    };
+    Ok(get_version(client, version).await?.version.dataset)
+}
+
+/// A dataset as shown by `dataset show`: its versions and, if one was chosen,
+/// that version's tables.
+#[derive(Serialize)]
+pub struct DatasetDetails {
+    #[serde(flatten)]
+    dataset: GetDatasetResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<Version>,
+}
+
+impl Render for DatasetDetails {
+    fn print_table(&self) -> Result<()> {
+        let dataset = &self.dataset;
+        println!("Name: {}", dataset.name);
+        println!("ID: {}", dataset.uuid);
+        if !dataset.description.is_empty() {
+            println!("Description: {}", dataset.description);
+        }
+
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::UTF8_FULL);
+        table.set_header(vec!["Version", "Status", "Performance mode", "State"]);
+        table.add_rows(dataset.dataset_versions.iter().map(|pv| {
+            vec![
+                pv.version.to_string(),
+                if pv.version.pre.is_empty() {
+                    "Release"
+                } else {
+                    "Draft"
+                }
+                .into(),
+                performance_mode(pv).into(),
+                acceleration_state(pv),
+            ]
+        }));
+        println!("{table}");
+
+        let Some(dataset_version) = self.version.as_ref().and_then(|version| {
+            dataset
+                .dataset_versions
                 .iter()
-                .map(|pv| -> Vec<String> {
-                    vec![
-                        p.name.to_owned(),
-                        pv.version.to_string(),
-                        if pv.accelerated {
-                            "Accelerated".into()
-                        } else {
-                            "Direct".into()
-                        },
-                        pv.patch_state
-                            .as_ref()
-                            .map(|s| {
-                                if let Some(data) = pv
-                                    .patch_state_data
-                                    .as_ref()
-                                    .and_then(|data| serde_json::to_string_pretty(data).ok())
-                                {
-                                    format!("{}: {}", s, data)
-                                } else {
-                                    s.to_string()
-                                }
-                            })
-                            .unwrap_or("n/a".into()),
-                    ]
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
+                .find(|pv| &pv.version == version)
+        }) else {
+            return Ok(());
+        };
+        for t in &dataset_version.dataset {
+            println!();
+            println!("Table: {}", t.name);
+            if let Some(description) = &t.description {
+                println!("Description: {}", description);
+            }
+            println!(
+                "Source: {} {}",
+                match t.source.path {
+                    SourcePath::BigQuery { .. } => "BigQuery",
+                    SourcePath::Snowflake { .. } => "Snowflake",
+                    SourcePath::Postgres { .. } => "PostgreSQL",
+                    SourcePath::Databricks { .. } => "Databricks",
+                    SourcePath::DuckDb { .. } => "DuckDB",
+                },
+                t.qualified_name()
+            );
+            println!(
+                "Primary key: {}",
+                t.schema
+                    .primary_key()
+                    .map_or("none".to_string(), |pk| pk.to_string())
+            );
+
+            let mut fields = Table::new();
+            fields.load_preset(comfy_table::presets::UTF8_FULL);
+            fields.set_header(vec!["Field", "Type", "Nullable", "Description"]);
+            fields.add_rows(t.schema.fields.iter().map(|f| {
+                vec![
+                    f.name.clone(),
+                    update::field_type_name(&f.type_),
+                    if f.is_required() { "no" } else { "yes" }.into(),
+                    f.description.clone().unwrap_or_default(),
+                ]
+            }));
+            println!("{fields}");
+        }
+        Ok(())
+    }
+}
+
+/// Returns a dataset and its versions, checking that the version `dataset`
+/// names, if any, exists.
+pub async fn show(dataset: &str) -> Result<DatasetDetails> {
+    let (name, version) = match dataset.split_once('@') {
+        Some((name, version)) => {
+            let version = Version::parse(version)
+                .with_context(|| format!("dataset identifier version is invalid: \"{version}\""))?;
+            (name, Some(version))
+        }
+        None => (dataset, None),
+    };
+
+    let token = session::get_token()?;
+    let client = Client::new(&token)?;
+    let Some(response) = client.get_dataset_versions(name).await? else {
+        bail!("dataset not found: \"{}\"", name)
+    };
+    if let Some(version) = &version {
+        if !response
+            .dataset_versions
+            .iter()
+            .any(|pv| &pv.version == version)
+        {
+            bail!("dataset version not found: \"{}\"", dataset)
+        }
+    }
+
+    Ok(DatasetDetails {
+        dataset: response,
+        version,
+    })
+}
 
-    let mut table = Table::new();
-    table.load_preset(comfy_table::presets::UTF8_FULL);
-    table.set_header(vec!["Name", "Version", "Performance mode", "State"]);
-    table.add_rows(rows);
+fn performance_mode(pv: &DatasetVersion) -> &'static str {
+    if pv.accelerated {
+        "Accelerated"
+    } else {
+        "Direct"
+    }
+}
+
+/// Describes a version's acceleration state, with any data about it.
+fn acceleration_state(pv: &DatasetVersion) -> String {
+    pv.patch_state
+        .as_ref()
+        .map(|s| {
+            if let Some(data) = pv
+                .patch_state_data
+                .as_ref()
+                .and_then(|data| serde_json::to_string_pretty(data).ok())
+            {
+                format!("{}: {}", s, data)
+            } else {
+                s.to_string()
+            }
+        })
+        .unwrap_or("n/a".into())
+}
 
-    println!("{table}");
+/// Fetches the dataset version identified by `dataset_ref`, which is of the
+/// form "<dataset name>@<version>".
+pub async fn get_version(client: &Client, dataset_ref: &str) -> Result<GetDatasetVersionResponse> {
+    let Some((name, version)) = dataset_ref.split_once('@') else {
+        bail!("invalid dataset identifier; expected \"<dataset name>@<version>\"")
+    };
+    let version: Version = Version::parse(version)
+        .with_context(|| format!("dataset identifier version is invalid: \"{version}\""))?;
 
-    Ok(())
+    match client.get_dataset_version(name, version).await? {
+        Some(response) => Ok(response),
+        None => bail!("dataset or dataset version not found: \"{}\"", dataset_ref),
+    }
 }
@@ -1,18 +1,29 @@
 use std::fs;
-use std::{collections::HashSet, fs::write, path::Path};
+use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 use anyhow::{bail, Context, Result};
 use clap::Subcommand;
 use inquire::{list_option::ListOption, InquireError};
+use serde::Serialize;
 use uuid7::uuid7;
 
 use crate::{
     api,
-    descriptor::{Dataset, Name, Table, TableSchema},
+    descriptor::{
+        write_json_schema, Dataset, Name, SpecFormat, Table, TableSchema,
+        TableSchemaObjectPrimaryKey, SCHEMA_FILE,
+    },
+    duckdb::{self, LocalSource},
     env, session,
     util::AllowListItem,
 };
 
+use super::output::Render;
+
 pub const DEFAULT_SPEC_PATH: &str = "dataset.json";
 
 #[derive(Subcommand, Debug)]
@@ -31,11 +42,67 @@ pub enum DescribeRefinement {
         #[arg(long)]
         schema: Vec<String>,
     },
+
+    /// Filter the tables to include.
+    ///
+    /// A table is included in the output descriptor only if it matches any of
+    /// the arguments given here. If none of these arguments are given, all
+    /// tables in the database named by the source will be included.
+    Postgres {
+        /// Table to include in the descriptor. May be given multiple times.
+        #[arg(long)]
+        table: Vec<String>,
+
+        /// Schema whose tables to include in the descriptor. May be given multiple times.
+        #[arg(long)]
+        schema: Vec<String>,
+    },
+
+    /// Filter the tables to include.
+    ///
+    /// A table is included in the output descriptor only if it matches any of
+    /// the arguments given here. If none of these arguments are given, all
+    /// tables in the catalog named by the source will be included.
+    Databricks {
+        /// Table to include in the descriptor, given as "TABLE",
+        /// "SCHEMA.TABLE" or "CATALOG.SCHEMA.TABLE". May be given multiple
+        /// times.
+        #[arg(long)]
+        table: Vec<String>,
+
+        /// Schema whose tables to include in the descriptor, given as "SCHEMA"
+        /// or "CATALOG.SCHEMA". May be given multiple times.
+        #[arg(long)]
+        schema: Vec<String>,
+
+        /// Catalog whose tables to include in the descriptor. May be given
+        /// multiple times.
+        #[arg(long)]
+        catalog: Vec<String>,
+    },
+
+    /// Filter the tables to include.
+    ///
+    /// A table is included in the output descriptor only if it matches any of
+    /// the arguments given here. If none of these arguments are given, all
+    /// tables in the local database or directory of Parquet files will be
+    /// included.
+    #[command(name = "duckdb")]
+    DuckDb {
+        /// Table to include in the descriptor. May be given multiple times.
+        #[arg(long)]
+        table: Vec<String>,
+
+        /// Schema whose tables to include in the descriptor. May be given
+        /// multiple times. Tables read from Parquet files are in schema "main".
+        #[arg(long)]
+        schema: Vec<String>,
+    },
 }
 
 impl DescribeRefinement {
     pub fn into_allow_list(self) -> Vec<AllowListItem> {
-        let iter = match self {
+        match self {
             DescribeRefinement::Snowflake { table, schema } => {
                 let table_items = table.into_iter().map(|t| AllowListItem::SnowflakeTable {
                     schema: None,
@@ -43,11 +110,139 @@ impl DescribeRefinement {
                 });
                 let schema_items = schema.into_iter().map(AllowListItem::SnowflakeSchema);
 
-                table_items.chain(schema_items)
+                table_items.chain(schema_items).collect()
             }
+            DescribeRefinement::Postgres { table, schema } => {
+                let table_items = table.into_iter().map(|t| AllowListItem::PostgresTable {
+                    schema: None,
+                    table: t,
+                });
+                let schema_items = schema.into_iter().map(AllowListItem::PostgresSchema);
+
+                table_items.chain(schema_items).collect()
+            }
+            DescribeRefinement::Databricks {
+                table,
+                schema,
+                catalog,
+            } => {
+                let table_items = table.into_iter().map(|t| {
+                    let mut parts = t.rsplitn(3, '.').map(String::from);
+                    AllowListItem::DatabricksTable {
+                        table: parts.next().unwrap_or_default(),
+                        schema: parts.next(),
+                        catalog: parts.next(),
+                    }
+                });
+                let schema_items = schema.into_iter().map(|s| match s.split_once('.') {
+                    Some((catalog, schema)) => AllowListItem::DatabricksSchema {
+                        catalog: Some(catalog.into()),
+                        schema: schema.into(),
+                    },
+                    None => AllowListItem::DatabricksSchema {
+                        catalog: None,
+                        schema: s,
+                    },
+                });
+                let catalog_items = catalog.into_iter().map(AllowListItem::DatabricksCatalog);
+
+                table_items
+                    .chain(schema_items)
+                    .chain(catalog_items)
+                    .collect()
+            }
+            DescribeRefinement::DuckDb { table, schema } => {
+                let table_items = table.into_iter().map(|t| AllowListItem::DuckDbTable {
+                    schema: None,
+                    table: t,
+                });
+                let schema_items = schema.into_iter().map(AllowListItem::DuckDbSchema);
+
+                table_items.chain(schema_items).collect()
+            }
+        }
+    }
+}
+
+/// The primary key of a table to include in a spec, given on the command line
+/// as "TABLE=FIELD[,FIELD...]".
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrimaryKeyArg {
+    table: String,
+    fields: Vec<String>,
+}
+
+impl FromStr for PrimaryKeyArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((table, fields)) = s.split_once('=') else {
+            return Err("expected TABLE=FIELD[,FIELD...]".into());
         };
+        Ok(PrimaryKeyArg {
+            table: table.trim().into(),
+            fields: fields
+                .split(',')
+                .map(|f| f.trim().to_owned())
+                .filter(|f| !f.is_empty())
+                .collect(),
+        })
+    }
+}
 
-        iter.collect()
+/// Reads a selection file: a JSON object mapping each table to include to the
+/// fields that make up its primary key, e.g.
+/// `{"PUBLIC.ORDERS": ["ID"], "PUBLIC.ITEMS": ["ORDER_ID", "LINE"]}`.
+fn read_selection(path: &Path) -> Result<Vec<PrimaryKeyArg>> {
+    let contents = fs::read_to_string(path)?;
+    let selection: BTreeMap<String, Vec<String>> = serde_json::from_str(&contents)?;
+    Ok(selection
+        .into_iter()
+        .map(|(table, fields)| PrimaryKeyArg { table, fields })
+        .collect())
+}
+
+/// Returns the tables and keys given by `--selection` and `--primary-key`, or
+/// `None` if neither was given and they should be prompted for instead.
+fn selected_keys(
+    primary_keys: Vec<PrimaryKeyArg>,
+    selection: Option<&Path>,
+) -> Result<Option<Vec<PrimaryKeyArg>>> {
+    if selection.is_none() && primary_keys.is_empty() {
+        return Ok(None);
+    }
+
+    // Keys given by flags take precedence over those in the selection file.
+    let mut keys = match selection {
+        Some(path) => read_selection(path)
+            .with_context(|| format!("failed to read selection {}", path.display()))?,
+        None => vec![],
+    };
+    keys.extend(primary_keys);
+    if keys.is_empty() {
+        bail!("no tables selected; the selection must name at least one table");
+    }
+    Ok(Some(keys))
+}
+
+/// A spec created by `init`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Initialized {
+    spec: PathBuf,
+    /// JSON Schema written next to the spec, which the spec refers to.
+    schema: PathBuf,
+    /// Identifier of the dataset version the spec specifies.
+    dataset: String,
+    tables: Vec<String>,
+}
+
+impl Render for Initialized {
+    fn print_table(&self) -> Result<()> {
+        eprintln!("wrote descriptor: {}", self.spec.display());
+        eprintln!("wrote schema: {}", self.schema.display());
+        log_post_init(&self.spec, &self.dataset);
+        Ok(())
     }
 }
 
@@ -55,8 +250,72 @@ pub async fn init(
     source_name: &str,
     dataset_name: &Name,
     output: &Path,
+    primary_keys: Vec<PrimaryKeyArg>,
+    selection: Option<&Path>,
     refinement: Option<DescribeRefinement>,
-) -> Result<()> {
+) -> Result<Initialized> {
+    // Fail before any prompts if the spec can't be written.
+    SpecFormat::from_path(output)?;
+
+    let keys = selected_keys(primary_keys, selection)?;
+
+    let response = if let Some(local_source) = LocalSource::parse(source_name) {
+        if let Some(refinement) = &refinement {
+            if !matches!(refinement, DescribeRefinement::DuckDb { .. }) {
+                bail!(
+                    "Incorrect `init` refinement used, given source of type duckdb (tip: Try `dpm init --name \"{}\" --source \"{}\" duckdb ...` instead.)",
+                    dataset_name,
+                    source_name
+                )
+            }
+        }
+        let database = local_source.database_in_spec(output)?;
+        duckdb::source_metadata(&local_source, &database)?
+    } else {
+        cloud_source_metadata(source_name, dataset_name, refinement.as_ref()).await?
+    };
+
+    let allow_list = refinement.map(|r| r.into_allow_list());
+    let tables_for_prompt = tables_from_metadata(response, allow_list.as_ref())?;
+
+    let selected_tables = match keys {
+        Some(keys) => select_tables_by_keys(tables_for_prompt, keys)?,
+        None => select_tables_and_keys(tables_for_prompt)?,
+    };
+
+    let descriptor = Dataset {
+        schema: Some(SCHEMA_FILE.into()),
+        id: uuid7(),
+        name: dataset_name.to_owned(),
+        description: None,
+        version: "0.1.0".parse().unwrap(),
+        accelerated: false,
+        tables: selected_tables,
+    };
+
+    descriptor
+        .write(output)
+        .context("error while writing descriptor")?;
+    // The spec refers to the schema by a path relative to itself, so that
+    // editors can resolve it offline.
+    let schema = output.with_file_name(SCHEMA_FILE);
+    write_json_schema(&schema)?;
+
+    Ok(Initialized {
+        spec: output.to_owned(),
+        schema,
+        dataset: descriptor.reference(),
+        tables: descriptor.tables.iter().map(|t| t.name.clone()).collect(),
+    })
+}
+
+/// Returns the metadata of the tables in the DPM Cloud source named
+/// `source_name`, after checking that `refinement` applies to its type.
+async fn cloud_source_metadata(
+    source_name: &str,
+    dataset_name: &Name,
+    refinement: Option<&DescribeRefinement>,
+) -> Result<api::GetSourceMetadataResponse> {
     let token = session::get_token()?;
     let client = api::Client::new(&token)?;
     let source = client
@@ -64,12 +323,17 @@ pub async fn init(
         .await
         .context("Failed to get source")?;
 
-    if let Some(refinement) = &refinement {
+    if let Some(refinement) = refinement {
         // The arms of this match are intentionally no-ops. The purpose of the
         // match is to early-return if there's inconsistency between the
         // refinement used and the type of the source named in the command.
         match (refinement, &source.source_parameters) {
-            (DescribeRefinement::Snowflake { .. }, api::GetSourceParameters::Snowflake { .. }) => {}
+            (DescribeRefinement::Snowflake { .. }, api::GetSourceParameters::Snowflake { .. })
+            | (DescribeRefinement::Postgres { .. }, api::GetSourceParameters::Postgres { .. })
+            | (
+                DescribeRefinement::Databricks { .. },
+                api::GetSourceParameters::Databricks { .. },
+            ) => {}
             _ => bail!(
                 "Incorrect `init` refinement used, given source of type {} (tip: Try `dpm init --name \"{}\" --source \"{}\" {} ...` instead.)",
                 source.type_name(),
@@ -80,30 +344,7 @@ pub async fn init(
         }
     }
 
-    let response = client.get_source_metadata(source.uuid).await?;
-
-    let allow_list = refinement.map(|r| r.into_allow_list());
-    let tables_for_prompt = tables_from_metadata(response, allow_list.as_ref())?;
-
-    let selected_tables = select_tables_and_keys(tables_for_prompt)?;
-
-    let descriptor = Dataset {
-        id: uuid7(),
-        name: dataset_name.to_owned(),
-        description: None,
-        version: "0.1.0".parse().unwrap(),
-        accelerated: false,
-        tables: selected_tables,
-    };
-
-    match write(output, serde_json::to_string_pretty(&descriptor).unwrap()) {
-        Ok(()) => eprintln!("wrote descriptor: {}", output.display()),
-        Err(e) => eprintln!("error while writing descriptor: {}", e),
-    }
-
-    log_post_init(output, &descriptor.reference());
-
-    Ok(())
+    client.get_source_metadata(source.uuid).await
 }
 
 fn log_post_init(descriptor_path: &Path, dataset_ref: &str) {
@@ -134,8 +375,11 @@ fn log_post_init(descriptor_path: &Path, dataset_ref: &str) {
 Next, build a draft data package to validate your dataset:
 
   $ dpm {} csharp
+  $ dpm {} go
+  $ dpm {} java
   $ dpm {} nodejs
   $ dpm {} python
+  $ dpm {} rust
 
 Or jump straight to publishing it:
 
@@ -146,13 +390,16 @@ published, authorized users may query the dataset via GraphQL, or build data
 packages to query it:
 
   $ dpm {} csharp
+  $ dpm {} go
+  $ dpm {} java
   $ dpm {} nodejs
   $ dpm {} python
+  $ dpm {} rust
 
 API docs for the built data packages can be found here: https://docs.dpm.sh/querying-data/data-packages/",
-        build_draft_package_command, build_draft_package_command, build_draft_package_command,
+        build_draft_package_command, build_draft_package_command, build_draft_package_command, build_draft_package_command, build_draft_package_command, build_draft_package_command,
         publish_dataset_command,
-        build_release_package_command, build_release_package_command, build_release_package_command
+        build_release_package_command, build_release_package_command, build_release_package_command, build_release_package_command, build_release_package_command, build_release_package_command
     );
 }
 
@@ -250,7 +497,75 @@ fn filter_by_indexes<T>(
     )
 }
 
-fn select_tables_and_keys(mut tables: Vec<Table>) -> Result<Vec<Table>, InquireError> {
+/// Selects the tables named in `keys`, by name or qualified name, and sets
+/// their primary keys. Where a table is named more than once, the last key
+/// given is used.
+pub fn select_tables_by_keys(
+    mut tables: Vec<Table>,
+    keys: Vec<PrimaryKeyArg>,
+) -> Result<Vec<Table>> {
+    let mut keys_by_index: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for PrimaryKeyArg { table, fields } in keys {
+        let matches: Vec<usize> = tables
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.name == table || t.qualified_name() == table)
+            .map(|(i, _)| i)
+            .collect();
+        let index = match matches[..] {
+            [index] => index,
+            [] => bail!("table \"{}\" was not found in the source", table),
+            _ => bail!(
+                "table name \"{}\" is ambiguous; use one of: {}",
+                table,
+                matches
+                    .iter()
+                    .map(|&i| tables[i].qualified_name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        if fields.is_empty() {
+            bail!(
+                "table \"{}\" lacks a primary key; give one with --primary-key \"{}=FIELD[,FIELD...]\"",
+                table,
+                table
+            );
+        }
+        let schema_fields = &tables[index].schema.fields;
+        if let Some(missing) = fields
+            .iter()
+            .find(|f| !schema_fields.iter().any(|sf| &&sf.name == f))
+        {
+            bail!(
+                "primary key field \"{}\" is not a field of table \"{}\"; its fields are: {}",
+                missing,
+                table,
+                schema_fields
+                    .iter()
+                    .map(|f| f.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        keys_by_index.insert(index, fields);
+    }
+
+    let mut selected_tables: Vec<Table> =
+        filter_by_indexes(tables.drain(..), keys_by_index.keys().copied().collect())
+            .zip(keys_by_index.into_values())
+            .map(|(mut table, fields)| {
+                table.schema.primary_key = Some(TableSchemaObjectPrimaryKey::Variant0(fields));
+                table
+            })
+            .collect();
+    selected_tables.sort_unstable_by_key(|t| t.qualified_name());
+
+    Ok(selected_tables)
+}
+
+pub fn select_tables_and_keys(mut tables: Vec<Table>) -> Result<Vec<Table>, InquireError> {
     // inquire doesn't have a test interface:
     // https://github.com/mikaelmello/inquire/issues/71
     //
@@ -337,3 +652,167 @@ fn select_tables_and_keys(mut tables: Vec<Table>) -> Result<Vec<Table>, InquireE
 
     Ok(selected_tables)
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        descriptor::{FieldType, TableSchemaField, TableSource},
+        util::SourcePath,
+    };
+
+    fn table(schema: &str, name: &str) -> Table {
+        Table {
+            name: name.into(),
+            description: None,
+            source: TableSource {
+                id: Uuid::nil(),
+                path: SourcePath::Snowflake {
+                    schema: schema.into(),
+                    table: name.into(),
+                },
+            },
+            schema: TableSchema {
+                fields: ["ID", "LINE"]
+                    .into_iter()
+                    .map(|name| TableSchemaField {
+                        name: name.into(),
+                        type_: FieldType::Number,
+                        description: None,
+                        constraints: None,
+                    })
+                    .collect(),
+                primary_key: None,
+            },
+        }
+    }
+
+    #[test]
+    fn primary_key_arg_works() {
+        assert_eq!(
+            "PUBLIC.ITEMS=ID, LINE".parse(),
+            Ok(PrimaryKeyArg {
+                table: "PUBLIC.ITEMS".into(),
+                fields: vec!["ID".into(), "LINE".into()],
+            })
+        );
+        assert!("ITEMS".parse::<PrimaryKeyArg>().is_err());
+    }
+
+    #[test]
+    fn select_tables_by_keys_works() {
+        let tables = vec![
+            table("PUBLIC", "ORDERS"),
+            table("PUBLIC", "ITEMS"),
+            table("STAGING", "ITEMS"),
+        ];
+        let key = |s: &str| s.parse::<PrimaryKeyArg>().unwrap();
+
+        let selected = select_tables_by_keys(
+            tables.clone(),
+            vec![key("ORDERS=ID"), key("STAGING.ITEMS=ID,LINE")],
+        )
+        .unwrap();
+        assert_eq!(
+            selected
+                .iter()
+                .map(|t| (t.qualified_name(), t.schema.primary_key.clone().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("PUBLIC.ORDERS".into(), vec!["ID".to_string()].into()),
+                (
+                    "STAGING.ITEMS".into(),
+                    vec!["ID".to_string(), "LINE".to_string()].into()
+                ),
+            ]
+        );
+
+        assert!(select_tables_by_keys(tables.clone(), vec![key("ITEMS=ID")]).is_err());
+        assert!(select_tables_by_keys(tables.clone(), vec![key("ORDERS=")]).is_err());
+        assert!(select_tables_by_keys(tables.clone(), vec![key("ORDERS=NOPE")]).is_err());
+        assert!(select_tables_by_keys(tables, vec![key("CUSTOMERS=ID")]).is_err());
+    }
+
+    #[test]
+    fn selected_keys_works() {
+        let key = |s: &str| s.parse::<PrimaryKeyArg>().unwrap();
+        let path = std::env::temp_dir().join(format!("dpm-selection-{}.json", std::process::id()));
+
+        assert_eq!(selected_keys(vec![], None).unwrap(), None);
+        assert_eq!(
+            selected_keys(vec![key("ORDERS=ID")], None).unwrap(),
+            Some(vec![key("ORDERS=ID")])
+        );
+
+        fs::write(&path, r#"{"ORDERS": ["ID"]}"#).unwrap();
+        assert_eq!(
+            selected_keys(vec![key("ITEMS=ID")], Some(&path)).unwrap(),
+            Some(vec![key("ORDERS=ID"), key("ITEMS=ID")])
+        );
+
+        // An empty selection must not fall back to prompting.
+        fs::write(&path, "{}").unwrap();
+        let error = selected_keys(vec![], Some(&path)).unwrap_err();
+        assert!(error.to_string().starts_with("no tables selected"));
+        assert_eq!(
+            selected_keys(vec![key("ORDERS=ID")], Some(&path)).unwrap(),
+            Some(vec![key("ORDERS=ID")])
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn postgres_refinement_works() {
+        let allow_list = DescribeRefinement::Postgres {
+            table: vec!["ORDERS".into()],
+            schema: vec!["STAGING".into()],
+        }
+        .into_allow_list();
+        let allowed = |schema: &str, table: &str| {
+            let path = SourcePath::Postgres {
+                schema: schema.into(),
+                table: table.into(),
+            };
+            allow_list.iter().any(|item| item.allows(&path))
+        };
+
+        assert!(allowed("PUBLIC", "ORDERS"));
+        assert!(allowed("STAGING", "ITEMS"));
+        assert!(!allowed("PUBLIC", "ITEMS"));
+        assert!(!allow_list
+            .iter()
+            .any(|item| item.allows(&SourcePath::Snowflake {
+                schema: "PUBLIC".into(),
+                table: "ORDERS".into(),
+            })));
+    }
+
+    #[test]
+    fn databricks_refinement_works() {
+        let allow_list = DescribeRefinement::Databricks {
+            table: vec!["ORDERS".into(), "dev.sales.ITEMS".into()],
+            schema: vec!["main.staging".into()],
+            catalog: vec!["archive".into()],
+        }
+        .into_allow_list();
+        let path = |catalog: &str, schema: &str, table: &str| SourcePath::Databricks {
+            catalog: catalog.into(),
+            schema: schema.into(),
+            table: table.into(),
+        };
+        let allowed = |path: SourcePath| allow_list.iter().any(|item| item.allows(&path));
+
+        assert_eq!(
+            path("main", "sales", "ORDERS").qualified_name(),
+            "main.sales.ORDERS"
+        );
+        assert!(allowed(path("main", "sales", "ORDERS")));
+        assert!(allowed(path("dev", "sales", "ITEMS")));
+        assert!(!allowed(path("main", "sales", "ITEMS")));
+        assert!(allowed(path("main", "staging", "ITEMS")));
+        assert!(!allowed(path("dev", "staging", "ITEMS")));
+        assert!(allowed(path("archive", "sales", "ITEMS")));
+    }
+}
@@ -1,9 +1,35 @@
+use std::path::PathBuf;
+
 use anyhow::{Context, Result};
+use serde::Serialize;
 
+use super::output::Render;
 use crate::env;
 use crate::github;
 use crate::session;
 
+/// The session `login` ensured.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggedIn {
+    session_file: PathBuf,
+    /// Whether a valid session was already present.
+    already_logged_in: bool,
+}
+
+impl Render for LoggedIn {
+    fn print_table(&self) -> Result<()> {
+        if self.already_logged_in {
+            println!("Session file present at: {}", self.session_file.display());
+            println!("You are already logged in.");
+        } else {
+            println!("Session file written to: {}", self.session_file.display());
+            println!("You are now logged in.");
+        }
+        Ok(())
+    }
+}
+
 /// Ensures that a valid session is stored in the CLI's session.json file.
 ///
 /// The CLI authenticates its requests to the DPM Cloud API by presenting a
@@ -11,27 +37,29 @@ use crate::session;
 /// token](https://docs.github.com/en/apps/creating-github-apps/authenticating-with-a-github-app/generating-a-user-access-token-for-a-github-app)
 /// issued to the Patch GitHub App. Then this token is stored on the filesystem,
 /// in a location where running data packages can easily find it.
-pub async fn login() -> Result<()> {
+pub async fn login() -> Result<LoggedIn> {
     let session_path = env::session_path()?;
     if session_path.exists() {
         let token = session::get_token()?;
         if github::token_is_valid(&token).await? {
-            println!("Session file present at: {}", session_path.display());
-            println!("You are already logged in.");
-            return Ok(());
+            return Ok(LoggedIn {
+                session_file: session_path,
+                already_logged_in: true,
+            });
         }
         // If token exists but doesn't pass cursory validation, proceed with the
         // login flow as though the invalid session.json weren't present at all.
     }
 
     let token = github::login().await?;
-    println!();
+    eprintln!();
 
     let contents = serde_json::to_string_pretty(&token)?;
     std::fs::write(&session_path, contents)
         .with_context(|| format!("Error writing session file: {}", session_path.display()))?;
 
-    println!("Session file written to: {}", session_path.display());
-    println!("You are now logged in.");
-    Ok(())
+    Ok(LoggedIn {
+        session_file: session_path,
+        already_logged_in: false,
+    })
 }
@@ -0,0 +1,36 @@
+//! Rendering of command results in the format chosen with `--output-format`.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Format to print command results in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Output {
+    /// Tables and prose, for people
+    #[default]
+    Table,
+    /// JSON, on stdout
+    Json,
+    /// YAML, on stdout
+    Yaml,
+}
+
+/// The result of a command, printed for people or serialized for scripts.
+pub trait Render: Serialize {
+    /// Prints the result for people, as the command always has.
+    fn print_table(&self) -> Result<()>;
+}
+
+impl Output {
+    /// Prints `result` in this format. Serialized results are the only thing
+    /// written to stdout; messages for people go to stderr.
+    pub fn print<R: Render + ?Sized>(self, result: &R) -> Result<()> {
+        match self {
+            Output::Table => result.print_table()?,
+            Output::Json => println!("{}", serde_json::to_string_pretty(result)?),
+            Output::Yaml => print!("{}", serde_yaml::to_string(result)?),
+        }
+        Ok(())
+    }
+}
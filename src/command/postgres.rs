@@ -0,0 +1,25 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Whether and how a PostgreSQL connection is secured with TLS, as libpq's
+/// `sslmode` parameter.
+///
+/// See also: https://www.postgresql.org/docs/current/libpq-ssl.html#LIBPQ-SSL-PROTECTION
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    /// Don't use TLS
+    Disable,
+    /// Use TLS only if the server requires it
+    Allow,
+    /// Use TLS if the server supports it
+    Prefer,
+    /// Use TLS, without verifying the server's certificate
+    Require,
+    /// Use TLS, verifying that the server's certificate is issued by a
+    /// trusted CA
+    VerifyCa,
+    /// Use TLS, verifying the server's certificate and that its host name
+    /// matches the certificate
+    VerifyFull,
+}
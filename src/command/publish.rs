@@ -1,18 +1,67 @@
-use std::path::Path;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{bail, Context, Result};
 use dialoguer::Select;
 use semver::Version;
+use serde::Serialize;
+use uuid7::Uuid;
 
+use super::{
+    changelog,
+    output::Render,
+    update::{self, Change, Impact},
+};
 use crate::{
     api,
-    descriptor::{Dataset, TableSchemaObjectPrimaryKey},
+    descriptor::{Dataset, Name, TableSchemaObjectPrimaryKey},
     session,
 };
 
-pub async fn publish(descriptor_path: &Path) -> Result<()> {
+/// A dataset version published by `publish`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Published {
+    name: Name,
+    id: Uuid,
+    version: Version,
+    accelerated: bool,
+    /// Where the dataset's changelog was written, unless writing it failed.
+    changelog: Option<PathBuf>,
+}
+
+impl Render for Published {
+    fn print_table(&self) -> Result<()> {
+        eprintln!("Published package {} version {}", self.name, self.version);
+        if let Some(changelog) = &self.changelog {
+            eprintln!("wrote changelog: {}", changelog.display());
+        }
+        if self.accelerated {
+            eprintln!(
+                "This version is ✨ accelerated. ✨ What this means:
+- Patch is performing the intial data acceleration now.
+- Building release packages (`dpm build-package -p <REF>`) is not
+  supported until initial acceleration is complete. To check
+  the status of the acceleration, run `dpm dataset list`."
+            )
+        }
+        Ok(())
+    }
+}
+
+/// Publishes the dataset specified at `descriptor_path`, returning the
+/// published version, or `None` if the user cancelled. If it has breaking
+/// changes compared with the latest release, but isn't a major version bump,
+/// publishing is refused when `strict`, and warned about otherwise.
+pub async fn publish(descriptor_path: &Path, strict: bool) -> Result<Option<Published>> {
     let package = Dataset::read(descriptor_path)
         .with_context(|| format!("Failed to read descriptor at {}", descriptor_path.display()))?;
+    package.check()?;
+    if package.is_local() {
+        bail!("Cannot publish a dataset whose tables are in a local source (tip: Build its package with `dpm build-package` instead.)");
+    }
 
     let mut tables_missing_pk: Vec<&str> = package
         .tables
@@ -47,6 +96,10 @@ pub async fn publish(descriptor_path: &Path) -> Result<()> {
             .find(|package_version| package_version.version.pre.is_empty())
     });
 
+    if let Some(latest_version) = latest_release_version {
+        check_compatibility(latest_version, &package, strict)?;
+    }
+
     let resolved_accelerated = if let Some(latest_version) = latest_release_version {
         match (latest_version.accelerated, package.accelerated) {
             (_, true) => true,
@@ -56,7 +109,7 @@ pub async fn publish(descriptor_path: &Path) -> Result<()> {
                     Some(intention) => intention,
                     None => {
                         eprintln!("Publish cancelled");
-                        return Ok(());
+                        return Ok(None);
                     }
                 }
             }
@@ -65,6 +118,15 @@ pub async fn publish(descriptor_path: &Path) -> Result<()> {
         package.accelerated
     };
 
+    let changelog = changelog::changelog(
+        &package.name,
+        response
+            .as_ref()
+            .map_or(&[], |response| response.dataset_versions.as_slice()),
+        &package.version,
+        &package.tables,
+    );
+
     client
         .create_version(
             package.id,
@@ -79,20 +141,91 @@ pub async fn publish(descriptor_path: &Path) -> Result<()> {
         )
         .await?;
 
-    eprintln!(
-        "Published package {} version {}",
-        package.name, package.version
-    );
-    if package.accelerated {
+    let changelog_path = changelog_path(descriptor_path);
+    let changelog = match fs::write(&changelog_path, changelog.markdown) {
+        Ok(()) => Some(changelog_path),
+        Err(e) => {
+            eprintln!(
+                "error while writing changelog to {}: {}",
+                changelog_path.display(),
+                e
+            );
+            None
+        }
+    };
+
+    Ok(Some(Published {
+        name: package.name,
+        id: package.id,
+        version: package.version,
+        accelerated: resolved_accelerated,
+        changelog,
+    }))
+}
+
+/// Returns the path of the changelog saved with the spec at `spec_path`, e.g.
+/// "dataset.changelog.md" for "dataset.json".
+fn changelog_path(spec_path: &Path) -> PathBuf {
+    spec_path.with_extension("changelog.md")
+}
+
+/// Fails if `strict`, and warns otherwise, when `package` makes breaking
+/// changes to the latest release without bumping its major version. Warns when
+/// a patch release adds tables or fields.
+fn check_compatibility(
+    latest: &api::DatasetVersion,
+    package: &Dataset,
+    strict: bool,
+) -> Result<()> {
+    let Some(allowed) = update::allowed_impact(&latest.version, &package.version) else {
+        // Republishing or going back a version is for the API to judge.
+        return Ok(());
+    };
+    let changes: Vec<Change> = update::changes(
+        &latest.dataset,
+        &package.tables,
+        update::DEFAULT_RENAME_THRESHOLD,
+    )
+    .into_iter()
+    .filter(|c| c.impact > allowed)
+    .collect();
+    let Some(impact) = changes.iter().map(|c| c.impact).max() else {
+        return Ok(());
+    };
+    let list = |impact: Impact| {
+        changes
+            .iter()
+            .filter(|c| c.impact == impact)
+            .map(|c| format!("  {}", c.description))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let suggested = update::bump(&latest.version, impact);
+
+    if impact < Impact::Major {
         eprintln!(
-            "This version is ✨ accelerated. ✨ What this means:
-- Patch is performing the intial data acceleration now.
-- Building release packages (`dpm build-package -p <REF>`) is not
-  supported until initial acceleration is complete. To check
-  the status of the acceleration, run `dpm dataset list`."
-        )
+            "WARNING: version {} is a {} release after {}, but adds to the dataset:\n{}\nsuggested version: {}\n",
+            package.version,
+            allowed,
+            latest.version,
+            list(impact),
+            suggested,
+        );
+        return Ok(());
     }
 
+    let message = format!(
+        "version {} is a {} release after {}, but makes breaking changes:\n{}\nsuggested version: {}",
+        package.version,
+        allowed,
+        latest.version,
+        list(Impact::Major),
+        suggested,
+    );
+    if strict {
+        bail!("{message}");
+    }
+    eprintln!("WARNING: {message}\n");
     Ok(())
 }
 
@@ -132,3 +265,47 @@ fn verify_intended_acceleration(
 
     Ok(choice.map(|i| choices[i] == Choice::Accelerated))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_compatibility_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let latest = api::DatasetVersion {
+            version: "1.0.0".parse().unwrap(),
+            accelerated: false,
+            patch_state: None,
+            patch_state_data: None,
+            dataset: dataset.tables.clone(),
+        };
+        let release = |version: &str, edit: fn(&mut Dataset)| {
+            let mut package = dataset.clone();
+            package.version = version.parse().unwrap();
+            edit(&mut package);
+            package
+        };
+        let add_field = |package: &mut Dataset| {
+            let fields = &mut package.tables[0].schema.fields;
+            let mut field = fields[0].clone();
+            field.name = "NEW_FIELD".into();
+            fields.push(field);
+        };
+        let remove_field = |package: &mut Dataset| {
+            package.tables[0].schema.fields.remove(2);
+        };
+
+        // Additions in a patch release only warn.
+        assert!(check_compatibility(&latest, &release("1.0.1", add_field), true).is_ok());
+        assert!(check_compatibility(&latest, &release("1.1.0", add_field), true).is_ok());
+
+        let error = check_compatibility(&latest, &release("1.1.0", remove_field), true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("makes breaking changes"), "{error}");
+        assert!(error.contains("suggested version: 2.0.0"), "{error}");
+        assert!(check_compatibility(&latest, &release("1.1.0", remove_field), false).is_ok());
+        assert!(check_compatibility(&latest, &release("2.0.0", remove_field), true).is_ok());
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use comfy_table::Table;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::output::{Output, Render};
+use crate::{
+    agent::{
+        self,
+        proto::{query::expression::ExType, Query},
+    },
+    api::{Client, GetDatasetVersionResponse},
+    query::{find_table, QuerySpec},
+    session,
+};
+
+#[derive(Args, Debug)]
+pub struct QueryArgs {
+    /// Dataset identifier of the form "<dataset name>@<version>".
+    #[arg(short = 'd', long = "dataset", value_name = "DATASET_REF")]
+    dataset_ref: Option<String>,
+
+    /// JSON file containing the query. Flags override the file's values.
+    #[arg(short, long, value_name = "FILE")]
+    file: Option<PathBuf>,
+
+    /// Table to query.
+    #[arg(short, long)]
+    table: Option<String>,
+
+    /// Expressions to select, e.g. "STATE, sum(PRICE) as total". All fields
+    /// are selected by default.
+    #[arg(long, value_name = "EXPRS")]
+    select: Vec<String>,
+
+    /// Condition rows must meet, e.g. "STATE in ('CA', 'NY') and PRICE > 10".
+    #[arg(long, value_name = "EXPR")]
+    filter: Option<String>,
+
+    /// Expressions to group by. When omitted and aggregates are selected, the
+    /// other selected expressions are used.
+    #[arg(long, value_name = "EXPRS")]
+    group_by: Vec<String>,
+
+    /// Expressions to order by, each optionally followed by "asc" or "desc".
+    #[arg(long, value_name = "EXPRS")]
+    order_by: Vec<String>,
+
+    /// Maximum number of rows to return.
+    #[arg(long)]
+    limit: Option<u64>,
+
+    /// Only compile the query, and print the resulting query string.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Format to print rows in, unless --output-format is json or yaml.
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+/// The query string compiled by `query --dry-run`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DryRun {
+    query_string: String,
+}
+
+impl Render for DryRun {
+    fn print_table(&self) -> Result<()> {
+        println!("{}", self.query_string);
+        Ok(())
+    }
+}
+
+pub async fn query(args: QueryArgs, output: Output) -> Result<()> {
+    let mut spec = match &args.file {
+        Some(path) => {
+            QuerySpec::read(path).with_context(|| format!("failed to read {}", path.display()))?
+        }
+        None => QuerySpec::default(),
+    };
+    if args.dataset_ref.is_some() {
+        spec.dataset = args.dataset_ref;
+    }
+    if args.table.is_some() {
+        spec.table = args.table;
+    }
+    if !args.select.is_empty() {
+        spec.select = args.select;
+    }
+    if args.filter.is_some() {
+        spec.filter = args.filter;
+    }
+    if !args.group_by.is_empty() {
+        spec.group_by = args.group_by;
+    }
+    if !args.order_by.is_empty() {
+        spec.order_by = args.order_by;
+    }
+    if args.limit.is_some() {
+        spec.limit = args.limit;
+    }
+    spec.dry_run |= args.dry_run;
+
+    let Some(dataset_ref) = spec.dataset.as_deref() else {
+        bail!("no dataset given; pass -d/--dataset or set \"dataset\" in the query file");
+    };
+
+    let token = session::get_token()?;
+    let client = Client::new(&token)?;
+    let dataset = super::dataset::get_version(&client, dataset_ref).await?;
+    let query = spec.compile(&dataset)?;
+
+    let result = agent::Client::from_env(&token)?
+        .execute_query(query.clone())
+        .await?;
+    if spec.dry_run {
+        return output.print(&DryRun {
+            query_string: result.query_string,
+        });
+    }
+
+    let format = match output {
+        Output::Table => args.format,
+        Output::Json => Format::Json,
+        Output::Yaml => Format::Yaml,
+    };
+    print_result(&dataset, &query, &result.json_data, format)
+}
+
+/// Prints the rows of `json_data`, the result of executing `query` on
+/// `dataset`.
+pub fn print_result(
+    dataset: &GetDatasetVersionResponse,
+    query: &Query,
+    json_data: &str,
+    format: Format,
+) -> Result<()> {
+    let rows: Vec<Map<String, Value>> =
+        serde_json::from_str(json_data).context("failed to parse query results")?;
+
+    // Name columns as selected, falling back to the table's field order.
+    let mut columns: Vec<String> = if query.select.is_empty() {
+        find_table(dataset, &query.select_from)?
+            .schema
+            .fields
+            .iter()
+            .map(|f| f.name.clone())
+            .collect()
+    } else {
+        query
+            .select
+            .iter()
+            .filter_map(
+                |s| match (&s.alias, s.argument.as_ref().map(|a| &a.ex_type)) {
+                    (Some(alias), _) => Some(alias.clone()),
+                    (None, Some(Some(ExType::Field(f)))) => Some(f.field_name.clone()),
+                    _ => None,
+                },
+            )
+            .collect()
+    };
+    if let Some(first) = rows.first() {
+        columns.retain(|c| first.contains_key(c));
+        for key in first.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    print_rows(&columns, &rows, format)
+}
+
+fn print_rows(columns: &[String], rows: &[Map<String, Value>], format: Format) -> Result<()> {
+    let cell = |row: &Map<String, Value>, column: &String| match row.get(column) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    };
+
+    match format {
+        Format::Table => {
+            let mut table = Table::new();
+            table.load_preset(comfy_table::presets::UTF8_FULL);
+            table.set_header(columns);
+            table.add_rows(
+                rows.iter()
+                    .map(|row| columns.iter().map(|c| cell(row, c)).collect::<Vec<_>>()),
+            );
+            println!("{table}");
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(rows)?),
+        Format::Yaml => print!("{}", serde_yaml::to_string(rows)?),
+        Format::Csv => {
+            println!("{}", csv_record(columns.iter().cloned()));
+            for row in rows {
+                println!("{}", csv_record(columns.iter().map(|c| cell(row, c))));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Joins values into a CSV record, quoting them as RFC 4180 requires.
+fn csv_record(values: impl Iterator<Item = String>) -> String {
+    values
+        .map(|v| {
+            if v.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", v.replace('"', "\"\""))
+            } else {
+                v
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use comfy_table::Table;
+use inquire::{autocompletion::Replacement, Autocomplete, CustomUserError, InquireError, Text};
+
+use super::query::{print_result, Format};
+use crate::{
+    agent,
+    api::{Client, GetDatasetVersionResponse},
+    query::{complete, find_table, quote_name, Parser, FUNCTIONS, KEYWORDS},
+    session,
+};
+
+const HELP: &str = "Enter a statement to execute it, e.g.
+
+  select STATE, sum(PRICE) as total from ORDERS
+    where year(ORDERED_AT) = 2023 and STATE in ('CA', 'NY')
+    order by total desc limit 10
+
+The full form of a statement is:
+
+  [select EXPRS|*] from TABLE [as ALIAS]
+    [[inner|left|right|full [outer]] join TABLE [as ALIAS] on COND]...
+    [where COND] [group by EXPRS] [order by EXPRS [asc|desc]] [limit N]
+
+Prefix a statement with \"explain\" to only compile it. Press Tab to complete
+table and field names. Other commands:
+
+  .tables         List the dataset's tables
+  .fields TABLE   List a table's fields
+  .help           Show this help
+  .quit           Exit the shell";
+
+const COMMANDS: [&str; 4] = [".tables", ".fields", ".help", ".quit"];
+
+pub async fn shell(dataset_ref: &str) -> Result<()> {
+    let token = session::get_token()?;
+    let client = Client::new(&token)?;
+    let dataset = super::dataset::get_version(&client, dataset_ref).await?;
+    let agent = agent::Client::from_env(&token)?;
+
+    eprintln!(
+        "querying {}@{}; enter .help for help",
+        dataset.name, dataset.version.version
+    );
+    let completer = Completer::new(&dataset);
+    loop {
+        let line = match Text::new("dpm>")
+            .with_autocomplete(completer.clone())
+            .prompt()
+        {
+            Ok(line) => line,
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        let result = match line.trim().split_once(' ').unwrap_or((line.trim(), "")) {
+            ("", _) => Ok(()),
+            (".quit" | ".exit", _) => break,
+            (".help", _) => {
+                println!("{HELP}");
+                Ok(())
+            }
+            (".tables", _) => {
+                print_tables(&dataset);
+                Ok(())
+            }
+            (".fields", name) => print_fields(&dataset, name.trim()),
+            (command, _) if command.starts_with('.') => Err(anyhow::anyhow!(
+                "unknown command: {command}; enter .help for help"
+            )),
+            _ => execute(&agent, &dataset, &line).await,
+        };
+        if let Err(e) = result {
+            eprintln!("error: {:#}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn execute(
+    agent: &agent::Client,
+    dataset: &GetDatasetVersionResponse,
+    statement: &str,
+) -> Result<()> {
+    let mut parser = Parser::new(statement)?;
+    let dry_run = parser.eat_keyword("explain");
+    let mut query = complete(parser.statement()?, dataset)?;
+    query.dry_run = Some(dry_run);
+
+    let result = agent.execute_query(query.clone()).await?;
+    println!("{}", result.query_string);
+    if !dry_run {
+        print_result(dataset, &query, &result.json_data, Format::Table)?;
+    }
+    Ok(())
+}
+
+fn print_tables(dataset: &GetDatasetVersionResponse) {
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_FULL);
+    table.set_header(vec!["Name", "Fields", "Description"]);
+    table.add_rows(dataset.version.dataset.iter().map(|t| {
+        vec![
+            t.name.clone(),
+            t.schema.fields.len().to_string(),
+            t.description.clone().unwrap_or_default(),
+        ]
+    }));
+    println!("{table}");
+}
+
+fn print_fields(dataset: &GetDatasetVersionResponse, name: &str) -> Result<()> {
+    let fields = &find_table(dataset, &table_name(name)?)?.schema.fields;
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_FULL);
+    table.set_header(vec!["Name", "Type", "Required", "Description"]);
+    table.add_rows(fields.iter().map(|f| {
+        vec![
+            f.name.clone(),
+            format!("{:?}", f.type_).to_lowercase(),
+            f.is_required().to_string(),
+            f.description.clone().unwrap_or_default(),
+        ]
+    }));
+    println!("{table}");
+    Ok(())
+}
+
+/// Parses the table name given to ".fields", which may be double-quoted as
+/// in a statement (and as the completer inserts it).
+fn table_name(arg: &str) -> Result<String> {
+    let mut parser = Parser::new(arg)?;
+    let name = parser.name()?;
+    parser.expect_end()?;
+    Ok(name)
+}
+
+/// Completes the word under the cursor with a table name, after "from",
+/// "join" or ".fields"; a field of the table it is qualified by; or otherwise
+/// a field name, keyword or function.
+#[derive(Clone)]
+struct Completer {
+    fields_by_table: BTreeMap<String, Vec<String>>,
+    words: Vec<String>,
+}
+
+impl Completer {
+    fn new(dataset: &GetDatasetVersionResponse) -> Self {
+        let fields_by_table: BTreeMap<String, Vec<String>> = dataset
+            .version
+            .dataset
+            .iter()
+            .map(|t| {
+                let fields = t.schema.fields.iter().map(|f| quote_name(&f.name));
+                (quote_name(&t.name), fields.collect())
+            })
+            .collect();
+
+        let mut words: Vec<String> = fields_by_table.values().flatten().cloned().collect();
+        words.extend(
+            KEYWORDS
+                .iter()
+                .chain(FUNCTIONS.iter())
+                .map(|s| s.to_string()),
+        );
+        words.sort_unstable();
+        words.dedup();
+
+        Self {
+            fields_by_table,
+            words,
+        }
+    }
+
+    fn candidates(&self, input: &str) -> (usize, Vec<String>) {
+        let start = input
+            .rfind(|c: char| !(c.is_alphanumeric() || "_.\"".contains(c)))
+            .map_or(0, |i| i + 1);
+        let (before, word) = input.split_at(start);
+        let previous = before.split_whitespace().last().unwrap_or_default();
+
+        let candidates: Vec<String> = if before.is_empty() && word.starts_with('.') {
+            COMMANDS.iter().map(|s| s.to_string()).collect()
+        } else if word.is_empty() {
+            vec![]
+        } else if ["from", "join", ".fields"].contains(&previous.to_lowercase().as_str()) {
+            self.fields_by_table.keys().cloned().collect()
+        } else if let Some((table, _)) = word.split_once('.') {
+            self.fields_by_table
+                .get(table)
+                .into_iter()
+                .flatten()
+                .map(|f| format!("{table}.{f}"))
+                .collect()
+        } else {
+            self.words.clone()
+        };
+
+        let prefix = word.to_lowercase();
+        (
+            start,
+            candidates
+                .into_iter()
+                .filter(|c| c.to_lowercase().starts_with(&prefix) && c != word)
+                .collect(),
+        )
+    }
+}
+
+impl Autocomplete for Completer {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        let (start, candidates) = self.candidates(input);
+        Ok(candidates
+            .into_iter()
+            .map(|c| format!("{}{}", &input[..start], c))
+            .collect())
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        if highlighted_suggestion.is_some() {
+            return Ok(highlighted_suggestion);
+        }
+
+        // Complete as much as all the candidates have in common.
+        let suggestions = self.get_suggestions(input)?;
+        let Some(first) = suggestions.first() else {
+            return Ok(None);
+        };
+        let common = suggestions.iter().fold(first.as_str(), |common, s| {
+            let len = common
+                .char_indices()
+                .zip(s.chars())
+                .take_while(|((_, a), b)| a.eq_ignore_ascii_case(b))
+                .last()
+                .map_or(0, |((i, a), _)| i + a.len_utf8());
+            &common[..len]
+        });
+        Ok((common.len() > input.len()).then(|| common.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completer_works() {
+        let completer = Completer {
+            fields_by_table: BTreeMap::from([
+                ("ORDERS".into(), vec!["ID".into(), "ORDERED_AT".into()]),
+                ("\"line items\"".into(), vec!["ORDER_ID".into()]),
+            ]),
+            words: vec![
+                "ID".into(),
+                "ORDERED_AT".into(),
+                "or".into(),
+                "order".into(),
+            ],
+        };
+        let suggestions = |input| completer.clone().get_suggestions(input).unwrap();
+
+        assert_eq!(suggestions(".t"), vec![".tables"]);
+        assert_eq!(
+            suggestions("select * from OR"),
+            vec!["select * from ORDERS"]
+        );
+        assert_eq!(
+            suggestions("select ORDERS.O"),
+            vec!["select ORDERS.ORDERED_AT"]
+        );
+        assert_eq!(
+            suggestions("select or"),
+            vec!["select ORDERED_AT", "select order"]
+        );
+        assert_eq!(
+            completer
+                .clone()
+                .get_completion("select ORD", None)
+                .unwrap(),
+            Some("select ORDER".into())
+        );
+        assert!(suggestions("select ").is_empty());
+    }
+
+    #[test]
+    fn table_name_works() {
+        assert_eq!(table_name("ORDERS").unwrap(), "ORDERS");
+        assert_eq!(table_name("\"line items\"").unwrap(), "line items");
+        assert_eq!(table_name("\"from\"").unwrap(), "from");
+        assert!(table_name("line items").is_err());
+        assert!(table_name("").is_err());
+    }
+}
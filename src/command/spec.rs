@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use schemars::schema::RootSchema;
+use serde::Serialize;
+
+use super::{init, output::Render};
+use crate::descriptor::{json_schema, validate, write_json_schema, Dataset, Problem};
+
+#[derive(Debug, Subcommand)]
+pub enum SpecAction {
+    /// Check a spec for problems, such as duplicate names and primary keys
+    /// that name missing fields. Each problem is reported with a JSON pointer
+    /// to its location in the spec.
+    Lint {
+        /// Spec to check
+        #[arg(short, long, value_name = "FILE", default_value = init::DEFAULT_SPEC_PATH)]
+        spec: PathBuf,
+    },
+
+    /// Convert a spec from one format to another
+    ///
+    /// The formats are chosen by file extension: .json, .yaml (or .yml), or
+    /// .toml.
+    Convert {
+        /// Spec to convert
+        #[arg(short, long, value_name = "FILE", default_value = init::DEFAULT_SPEC_PATH)]
+        spec: PathBuf,
+
+        /// Path to write the converted spec to
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+
+        /// Overwrite the output file if it exists
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Print the JSON Schema that specs conform to
+    ///
+    /// Specs written by `init` refer to the schema by its `$id`, so that
+    /// editors can validate and complete them.
+    Schema {
+        /// File to write the schema to, instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+/// The problems `spec lint` found with a spec.
+#[derive(Serialize)]
+pub struct Lint {
+    spec: PathBuf,
+    problems: Vec<Problem>,
+}
+
+impl Lint {
+    /// Fails if any problems were found.
+    pub fn check(&self) -> Result<()> {
+        match self.problems.len() {
+            0 => Ok(()),
+            1 => bail!("found 1 problem"),
+            n => bail!("found {n} problems"),
+        }
+    }
+}
+
+impl Render for Lint {
+    fn print_table(&self) -> Result<()> {
+        for problem in &self.problems {
+            println!("{}: {}", self.spec.display(), problem);
+        }
+        if self.problems.is_empty() {
+            eprintln!("{}: no problems found", self.spec.display());
+        }
+        Ok(())
+    }
+}
+
+pub fn lint(spec: &Path) -> Result<Lint> {
+    let dataset = Dataset::read(spec)?;
+    Ok(Lint {
+        spec: spec.to_owned(),
+        problems: validate(&dataset),
+    })
+}
+
+/// The JSON Schema printed by `spec schema`, or where it was written.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Schema {
+    Printed(RootSchema),
+    Written { path: PathBuf },
+}
+
+impl Render for Schema {
+    fn print_table(&self) -> Result<()> {
+        match self {
+            Schema::Printed(schema) => println!("{}", serde_json::to_string_pretty(schema)?),
+            Schema::Written { path } => eprintln!("wrote schema: {}", path.display()),
+        }
+        Ok(())
+    }
+}
+
+pub fn schema(output: Option<&Path>) -> Result<Schema> {
+    match output {
+        Some(path) => {
+            write_json_schema(path)?;
+            Ok(Schema::Written {
+                path: path.to_owned(),
+            })
+        }
+        None => Ok(Schema::Printed(json_schema())),
+    }
+}
+
+/// A spec converted by `spec convert`.
+#[derive(Serialize)]
+pub struct Converted {
+    spec: PathBuf,
+    output: PathBuf,
+}
+
+impl Render for Converted {
+    fn print_table(&self) -> Result<()> {
+        eprintln!("wrote spec: {}", self.output.display());
+        Ok(())
+    }
+}
+
+pub fn convert(spec: &Path, output: &Path, force: bool) -> Result<Converted> {
+    let dataset =
+        Dataset::read(spec).with_context(|| format!("failed to read {}", spec.display()))?;
+    if !force && output.exists() {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            output.display()
+        );
+    }
+    dataset
+        .write(output)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+    Ok(Converted {
+        spec: spec.to_owned(),
+        output: output.to_owned(),
+    })
+}
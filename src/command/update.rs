@@ -1,45 +1,114 @@
-use std::{ffi::OsString, path::PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
 use dialoguer::Confirm;
+use semver::Version;
+use serde::{Serialize, Serializer};
+use uuid::Uuid;
 
 use crate::{
     api,
-    descriptor::{Dataset, Table, TableSchemaField, TableSchemaObjectPrimaryKey},
+    descriptor::{
+        Dataset, FieldType, SpecFormat, Table, TableSchemaField, TableSchemaObjectPrimaryKey,
+    },
+    duckdb::{self, LocalSource},
     session,
+    util::{AllowListItem, SourcePath},
 };
 
-use super::init;
+use super::{
+    init::{self, DescribeRefinement, PrimaryKeyArg},
+    output::{Output, Render},
+};
 
-pub async fn update(base_path: &PathBuf) -> Result<()> {
-    let current_dp = Dataset::read(base_path)
-        .with_context(|| format!("failed to read {}", base_path.display()))?;
+/// The similarity, as a percentage, at which tables and fields are considered
+/// renamed by default.
+pub const DEFAULT_RENAME_THRESHOLD: u8 = 70;
 
-    let token = session::get_token()?;
-    let client = api::Client::new(&token)?;
+/// The length, in characters, below which a field name must share a word with
+/// another for them to be considered similar at all. Otherwise e.g. `ID` and
+/// `IS` would be half similar.
+const MIN_SIMILAR_NAME_LEN: usize = 4;
 
-    let source_id = current_dp
-        .tables
-        .iter()
-        .map(|t| t.source.id)
-        .next()
-        .unwrap();
+/// Exit code of `update --check` when the spec is out of date.
+pub const EXIT_CHANGES_FOUND: i32 = 1;
+/// Exit code of `update` when it fails.
+pub const EXIT_ERROR: i32 = 2;
 
-    let source = client
-        .get_source(&source_id.to_string())
-        .await
-        .context("Failed to get source")?;
+/// How `update` proceeds once it has found changes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// Ask before writing the updated spec.
+    Prompt,
+    /// Write the updated spec without asking.
+    Yes,
+    /// Only report the changes; write nothing.
+    Check,
+}
+
+/// Format to report changes in, when printing them for people.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    /// Prose, on stderr
+    Text,
+    /// Markdown, e.g. for a pull request comment, on stdout
+    Markdown,
+}
 
-    let current_metadata = client.get_source_metadata(source.uuid).await?;
+/// Which tables new to the source `update` adds to the dataset.
+pub struct AddTables {
+    /// The tables to add and their primary keys. When empty, the user is
+    /// prompted to choose.
+    pub primary_keys: Vec<PrimaryKeyArg>,
+    /// Filter on the tables to choose from.
+    pub refinement: Option<DescribeRefinement>,
+}
+
+/// Compares the spec at `base_path` to its source and, depending on `mode`,
+/// writes the updated spec. The outcome is printed in `output`, with the
+/// differences reported in `format` for people. Returns whether any changes
+/// were found.
+pub async fn update(
+    base_path: &PathBuf,
+    mode: Mode,
+    format: Format,
+    output: Output,
+    add_tables: Option<AddTables>,
+    rename_threshold: u8,
+) -> Result<bool> {
+    let current_dp = Dataset::read(base_path)
+        .with_context(|| format!("failed to read {}", base_path.display()))?;
+    let spec_format = SpecFormat::from_path(base_path)?;
+
+    let refinement = add_tables.as_ref().and_then(|a| a.refinement.as_ref());
+    let current_metadata = match &current_dp.tables.first().unwrap().source.path {
+        SourcePath::DuckDb { database, .. } => {
+            if let Some(refinement) = refinement {
+                if !matches!(refinement, DescribeRefinement::DuckDb { .. }) {
+                    bail!("Incorrect `update` refinement used, given source of type duckdb");
+                }
+            }
+            let local_source = LocalSource::in_spec(database, base_path);
+            duckdb::source_metadata(&local_source, database)?
+        }
+        _ => {
+            let source_id = current_dp.tables.first().unwrap().source.id;
+            cloud_source_metadata(source_id, refinement).await?
+        }
+    };
 
     // `updated_tables` is a subset of those tables currently in the dataset. It
     // may contain all the same tables, or fewer, if some have been deleted in
-    // the source.
+    // the source. The source's other tables are candidates to add.
     let allow_list = current_dp.allow_list();
-    let mut updated_tables = init::tables_from_metadata(current_metadata, Some(&allow_list))?;
-
-    // Note: We don't yet support adding tables to a dataset. We just retain as
-    // many of the old tables as we can, and retain their current primary keys.
+    let (mut updated_tables, mut candidate_tables) = split_tables(
+        init::tables_from_metadata(current_metadata, None::<&[AllowListItem]>)?,
+        &allow_list,
+    );
 
     // For each of the updated tables, update its `None` primary key to be the
     // primary key of its mate in `current_dp`.
@@ -62,109 +131,800 @@ pub async fn update(base_path: &PathBuf) -> Result<()> {
         *primary_key = matching_old_t.cloned();
     }
 
-    let comparisons = diff(current_dp.tables.as_slice(), updated_tables.as_slice());
-    print_comparisons(&comparisons);
+    // Tables renamed in the source are no longer in the allow list; find them
+    // among the candidates.
+    find_renamed_tables(
+        &current_dp.tables,
+        &mut updated_tables,
+        &mut candidate_tables,
+        rename_threshold,
+    );
 
-    if comparisons.iter().all(|c| {
-        let DatasetComparison::ExistingTable { diff, .. } = c else {
-            return false;
-        };
-        matches!(diff, TableComparison::Unchanged)
-    }) {
-        eprintln!("no updates to be made");
-        return Ok(());
+    if let Some(add_tables) = add_tables {
+        updated_tables.extend(select_new_tables(candidate_tables, add_tables, mode)?);
     }
 
-    if !Confirm::new()
-        .with_prompt(format!("write {}?", base_path.display()))
-        .interact()?
-    {
-        eprintln!("update cancelled");
-        return Ok(());
+    let comparisons = diff(
+        current_dp.tables.as_slice(),
+        updated_tables.as_slice(),
+        rename_threshold,
+    );
+    let changes = classify(&comparisons);
+    let impact = changes.iter().map(|c| c.impact).max();
+    let latest_release = latest_release(&current_dp).await?;
+    let suggested_version = impact
+        .and_then(|impact| suggest_version(&current_dp.version, latest_release.as_ref(), impact));
+
+    // When the outcome is serialized, only report the differences if they're
+    // needed to answer the prompts.
+    let report = match output {
+        Output::Table => Some(format),
+        _ if mode == Mode::Prompt => Some(Format::Text),
+        _ => None,
+    };
+    if let Some(format) = report {
+        match format {
+            Format::Text => eprint!("{}", text(&comparisons)),
+            Format::Markdown => print!("{}", markdown(&comparisons)),
+        }
+        eprint!("{}", breaking_summary(&changes));
+        if let (Format::Markdown, Some(impact), Some(suggested)) =
+            (format, impact, &suggested_version)
+        {
+            println!("Suggested version: `{suggested}` ({impact} bump)");
+        }
+    }
+
+    let changed = !changes.is_empty();
+    let status = match mode {
+        _ if !changed => Status::UpToDate,
+        Mode::Check => Status::OutOfDate,
+        Mode::Yes => {
+            let mut version = current_dp.version.clone();
+            if let Some(suggested) = &suggested_version {
+                eprintln!("bumping version: {} -> {}", current_dp.version, suggested);
+                version = suggested.clone();
+            }
+            write_updated(
+                base_path,
+                &current_dp,
+                &updated_tables,
+                version,
+                spec_format,
+            )?
+        }
+        Mode::Prompt => {
+            let mut version = current_dp.version.clone();
+            if let (Some(impact), Some(suggested)) = (impact, &suggested_version) {
+                if Confirm::new()
+                    .with_prompt(format!(
+                        "bump version {} -> {} ({impact} bump)?",
+                        current_dp.version, suggested
+                    ))
+                    .default(true)
+                    .interact()?
+                {
+                    version = suggested.clone();
+                }
+            }
+            if Confirm::new()
+                .with_prompt(format!("write {}?", base_path.display()))
+                .interact()?
+            {
+                write_updated(
+                    base_path,
+                    &current_dp,
+                    &updated_tables,
+                    version,
+                    spec_format,
+                )?
+            } else {
+                Status::Cancelled
+            }
+        }
+    };
+
+    output.print(&Outcome {
+        spec: base_path,
+        diff: Diff {
+            tables: comparisons,
+            changes,
+            format,
+        },
+        suggested_version,
+        status,
+    })?;
+    Ok(changed)
+}
+
+/// Returns the version of the latest release of `dataset`, or `None` if it
+/// hasn't been released. Datasets with local sources are never released.
+async fn latest_release(dataset: &Dataset) -> Result<Option<Version>> {
+    if dataset.is_local() {
+        return Ok(None);
+    }
+    let token = session::get_token()?;
+    let client = api::Client::new(&token)?;
+    let response = client
+        .get_dataset_versions(&dataset.id.to_string())
+        .await
+        .context("failed to fetch the dataset's versions")?;
+    Ok(response.and_then(|response| {
+        response
+            .dataset_versions
+            .into_iter()
+            .map(|v| v.version)
+            .filter(|v| v.pre.is_empty())
+            .max()
+    }))
+}
+
+/// Returns the version to bump a spec at version `current` to, given a change
+/// of `impact` since release `latest`, or `None` if `current` is already far
+/// enough ahead of `latest`. Before the first release, nothing is bumped.
+fn suggest_version(current: &Version, latest: Option<&Version>, impact: Impact) -> Option<Version> {
+    let latest = latest?;
+    match allowed_impact(latest, current) {
+        Some(allowed) if allowed >= impact => None,
+        _ => Some(bump(latest, impact)),
     }
+}
 
+/// Returns the metadata of the tables in the DPM Cloud source with ID
+/// `source_id`, after checking that `refinement` applies to its type.
+async fn cloud_source_metadata(
+    source_id: Uuid,
+    refinement: Option<&DescribeRefinement>,
+) -> Result<api::GetSourceMetadataResponse> {
+    let token = session::get_token()?;
+    let client = api::Client::new(&token)?;
+
+    let source = client
+        .get_source(&source_id.to_string())
+        .await
+        .context("Failed to get source")?;
+
+    if let Some(refinement) = refinement {
+        match (refinement, &source.source_parameters) {
+            (DescribeRefinement::Snowflake { .. }, api::GetSourceParameters::Snowflake { .. })
+            | (DescribeRefinement::Postgres { .. }, api::GetSourceParameters::Postgres { .. })
+            | (
+                DescribeRefinement::Databricks { .. },
+                api::GetSourceParameters::Databricks { .. },
+            ) => {}
+            _ => bail!(
+                "Incorrect `update` refinement used, given source of type {}",
+                source.type_name()
+            ),
+        }
+    }
+
+    client.get_source_metadata(source.uuid).await
+}
+
+/// Backs up the spec at `base_path`, which specifies `current_dp`, and writes
+/// in its place a spec for version `version` of the dataset with tables
+/// `updated_tables`.
+fn write_updated(
+    base_path: &Path,
+    current_dp: &Dataset,
+    updated_tables: &[Table],
+    version: Version,
+    spec_format: SpecFormat,
+) -> Result<Status> {
     let mut backup_path = base_path.to_owned().into_os_string();
     backup_path.push(".backup");
 
     let updated_dp = Dataset {
+        schema: current_dp.schema.clone(),
         id: current_dp.id,
         name: current_dp.name.clone(),
         description: current_dp.description.clone(),
-        version: current_dp.version.clone(),
+        version: version.clone(),
         accelerated: current_dp.accelerated,
-        tables: updated_tables,
+        tables: updated_tables.to_vec(),
     };
 
     std::fs::write(
         &backup_path,
-        serde_json::to_string_pretty(&current_dp).context("serializing descriptor")?,
+        current_dp
+            .to_string(spec_format)
+            .context("serializing descriptor")?,
     )
     .context("writing backup of current descriptor")?;
-    eprintln!(
-        "wrote backup of previous descriptor to: {}",
-        <OsString as Into<PathBuf>>::into(backup_path).display()
-    );
     std::fs::write(
         base_path,
-        serde_json::to_string_pretty(&updated_dp).context("serializing descriptor")?,
+        updated_dp
+            .to_string(spec_format)
+            .context("serializing descriptor")?,
     )
     .context("writing updated descriptor")?;
-    eprintln!("wrote updated descriptor to: {}", base_path.display());
-    Ok(())
+    Ok(Status::Written {
+        version,
+        backup: backup_path.into(),
+    })
+}
+
+/// The outcome of `update`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Outcome<'a> {
+    spec: &'a Path,
+    #[serde(flatten)]
+    diff: Diff<'a>,
+    suggested_version: Option<Version>,
+    #[serde(flatten)]
+    status: Status,
+}
+
+/// What `update` did with the spec.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+enum Status {
+    /// The spec matches its source.
+    UpToDate,
+    /// The spec differs from its source, and `--check` was given.
+    OutOfDate,
+    /// The user declined to write the updated spec.
+    Cancelled,
+    /// The updated spec was written, after backing up the previous one.
+    Written { version: Version, backup: PathBuf },
+}
+
+impl Render for Outcome<'_> {
+    fn print_table(&self) -> Result<()> {
+        match &self.status {
+            Status::UpToDate => eprintln!("no updates to be made"),
+            Status::OutOfDate => {
+                let impact = self.diff.changes.iter().map(|c| c.impact).max();
+                if let (Some(impact), Some(suggested)) = (impact, &self.suggested_version) {
+                    eprintln!("suggested version: {suggested} ({impact} bump)");
+                }
+                eprintln!("{} is out of date", self.spec.display());
+            }
+            Status::Cancelled => eprintln!("update cancelled"),
+            Status::Written { backup, .. } => {
+                eprintln!(
+                    "wrote backup of previous descriptor to: {}",
+                    backup.display()
+                );
+                eprintln!("wrote updated descriptor to: {}", self.spec.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a change to a dataset affects its users, as the part of the version
+/// that a release with the change must bump.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Impact {
+    /// Documentation changes, e.g. to descriptions.
+    Patch,
+    /// Additions: new tables and fields, and fields that became required.
+    Minor,
+    /// Breaking changes: removed or renamed tables and fields, type changes,
+    /// fields that became nullable, and primary key changes.
+    Major,
+}
+
+impl std::fmt::Display for Impact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Impact::Patch => "patch",
+            Impact::Minor => "minor",
+            Impact::Major => "major",
+        })
+    }
+}
+
+/// A change between two versions of a dataset.
+#[derive(Serialize)]
+pub struct Change {
+    pub impact: Impact,
+    pub description: String,
+}
+
+/// The differences between two sets of tables.
+#[derive(Serialize)]
+pub struct Diff<'a> {
+    /// How each table compares.
+    tables: Vec<DatasetComparison<'a>>,
+    /// The changes among the tables, and their impact.
+    changes: Vec<Change>,
+    /// Format to print the differences in, for people.
+    #[serde(skip)]
+    format: Format,
 }
 
-fn print_comparisons(comparisons: &Vec<DatasetComparison>) {
+impl Render for Diff<'_> {
+    fn print_table(&self) -> Result<()> {
+        match self.format {
+            Format::Text if self.changes.is_empty() => println!("no changes"),
+            Format::Text => print!("{}{}", text(&self.tables), breaking_summary(&self.changes)),
+            Format::Markdown => print!("{}", markdown(&self.tables)),
+        }
+        Ok(())
+    }
+}
+
+/// Returns the differences between tables `old` and `new`, to be printed in
+/// `format`.
+pub fn compare<'a>(
+    old: &'a [Table],
+    new: &'a [Table],
+    format: Format,
+    rename_threshold: u8,
+) -> Diff<'a> {
+    let tables = diff(old, new, rename_threshold);
+    let changes = classify(&tables);
+    Diff {
+        tables,
+        changes,
+        format,
+    }
+}
+
+/// Lists the breaking changes among `changes`, if any.
+fn breaking_summary(changes: &[Change]) -> String {
+    let breaking: Vec<&Change> = changes
+        .iter()
+        .filter(|c| c.impact == Impact::Major)
+        .collect();
+    if breaking.is_empty() {
+        return String::new();
+    }
+    std::iter::once("breaking changes:\n".to_string())
+        .chain(breaking.iter().map(|c| format!("  {}\n", c.description)))
+        .collect()
+}
+
+/// Lists the changes from tables `old` to `new`.
+pub fn changes(old: &[Table], new: &[Table], rename_threshold: u8) -> Vec<Change> {
+    classify(&diff(old, new, rename_threshold))
+}
+
+fn classify(comparisons: &[DatasetComparison]) -> Vec<Change> {
+    let mut changes = vec![];
+    let mut change = |impact, description: String| {
+        changes.push(Change {
+            impact,
+            description,
+        })
+    };
+
     for c in comparisons {
         match c {
             DatasetComparison::ExistingTable { table, diff } => {
-                let old_name = &table.name;
-                match diff {
-                    TableComparison::Unchanged => (/* print nothing */),
-                    TableComparison::Renamed { new_name } => {
-                        eprintln!("table renamed: \"{old_name}\" => \"{new_name}\"")
+                let t = &table.name;
+                let (field_diffs, primary_key_diff, description_diff) = match diff {
+                    TableComparison::Unchanged => continue,
+                    TableComparison::Removed => {
+                        change(Impact::Major, format!("table \"{t}\" removed"));
+                        continue;
+                    }
+                    TableComparison::Renamed {
+                        new_name,
+                        field_diffs,
+                        primary_key_diff,
+                        description_diff,
+                        ..
+                    } => {
+                        change(
+                            Impact::Major,
+                            format!("table \"{t}\" renamed to \"{new_name}\""),
+                        );
+                        (field_diffs, primary_key_diff, description_diff)
                     }
-                    TableComparison::Removed => eprintln!("table removed: \"{old_name}\"",),
                     TableComparison::Modified {
+                        field_diffs,
                         primary_key_diff,
+                        description_diff,
+                    } => {
+                        if primary_key_diff.is_none()
+                            && description_diff.is_none()
+                            && !field_diffs.iter().any(FieldComparison::is_change)
+                        {
+                            change(Impact::Patch, format!("table \"{t}\" modified"));
+                        }
+                        (field_diffs, primary_key_diff, description_diff)
+                    }
+                };
+
+                if description_diff.is_some() {
+                    change(Impact::Patch, format!("table \"{t}\" description changed"));
+                }
+                if primary_key_diff.is_some() {
+                    change(Impact::Major, format!("table \"{t}\" primary key changed"));
+                }
+                for diff in field_diffs {
+                    match diff {
+                        FieldComparison::Added { new } => change(
+                            Impact::Minor,
+                            format!("field \"{t}\".\"{}\" added", new.name),
+                        ),
+                        FieldComparison::Removed { old } => change(
+                            Impact::Major,
+                            format!("field \"{t}\".\"{}\" removed", old.name),
+                        ),
+                        FieldComparison::Renamed { old, new, .. } => change(
+                            Impact::Major,
+                            format!("field \"{t}\".\"{}\" renamed to \"{}\"", old.name, new.name),
+                        ),
+                        FieldComparison::Modified { old, new } => {
+                            let impact = if old.type_ != new.type_
+                                || (old.is_required() && !new.is_required())
+                            {
+                                Impact::Major
+                            } else if !old.is_required() && new.is_required() {
+                                Impact::Minor
+                            } else {
+                                Impact::Patch
+                            };
+                            change(
+                                impact,
+                                format!(
+                                    "field \"{t}\".\"{}\" modified ({})",
+                                    old.name,
+                                    describe_field_change(old, new)
+                                ),
+                            )
+                        }
+                        FieldComparison::Unchanged { .. } => (),
+                    }
+                }
+            }
+            DatasetComparison::NewTable { table } => {
+                change(Impact::Minor, format!("table \"{}\" added", table.name))
+            }
+        }
+    }
+
+    changes
+}
+
+/// Returns `version` bumped for a release with changes of the given impact.
+/// As Cargo does, versions below 1.0.0 bump their minor part for breaking
+/// changes and their patch part for everything else.
+pub fn bump(version: &Version, impact: Impact) -> Version {
+    let (major, minor, patch) = (version.major, version.minor, version.patch);
+    let (major, minor, patch) = match (major, impact) {
+        (0, Impact::Major) => (0, minor + 1, 0),
+        (0, _) => (0, minor, patch + 1),
+        (_, Impact::Major) => (major + 1, 0, 0),
+        (_, Impact::Minor) => (major, minor + 1, 0),
+        (_, Impact::Patch) => (major, minor, patch + 1),
+    };
+    Version::new(major, minor, patch)
+}
+
+/// Returns the impact of the largest change that a release going from
+/// version `from` to `to` may contain, or `None` if `to` isn't greater.
+pub fn allowed_impact(from: &Version, to: &Version) -> Option<Impact> {
+    if to <= from {
+        return None;
+    }
+    // A pre-release of a version is allowed what the version is.
+    let to = Version::new(to.major, to.minor, to.patch);
+    [Impact::Major, Impact::Minor, Impact::Patch]
+        .into_iter()
+        .find(|&impact| to >= bump(from, impact))
+        .or(Some(Impact::Patch))
+}
+
+/// Splits the tables in a source into those `allow_list` allows, i.e. those
+/// already in the dataset, and the rest.
+fn split_tables(tables: Vec<Table>, allow_list: &[AllowListItem]) -> (Vec<Table>, Vec<Table>) {
+    tables
+        .into_iter()
+        .partition(|t| allow_list.iter().any(|item| item.allows(&t.source.path)))
+}
+
+/// Moves the tables in `candidates` that tables of `current` missing from
+/// `updated` were renamed to, by the same measure as [`diff`], into `updated`.
+/// Otherwise, as the dataset's allow list names tables by their source path, a
+/// table renamed in the source would be reported as removed.
+fn find_renamed_tables(
+    current: &[Table],
+    updated: &mut Vec<Table>,
+    candidates: &mut Vec<Table>,
+    rename_threshold: u8,
+) {
+    let removed: Vec<&Table> = current
+        .iter()
+        .filter(|old_t| !updated.iter().any(|new_t| new_t.source == old_t.source))
+        .collect();
+    let mut renames = pair_by_similarity(&removed, candidates, rename_threshold, |o, n| {
+        table_similarity(o, n)
+    });
+
+    // Remove the renamed candidates back to front, so the indexes of those
+    // still to remove stay valid.
+    renames.sort_by_key(|&(_, j, _)| std::cmp::Reverse(j));
+    for (i, j, _) in renames {
+        let mut table = candidates.remove(j);
+        table.schema.primary_key = removed[i].schema.primary_key.clone();
+        updated.push(table);
+    }
+}
+
+/// Chooses which of `candidates`, tables new to the source, to add to the
+/// dataset, and their primary keys.
+fn select_new_tables(
+    mut candidates: Vec<Table>,
+    AddTables {
+        primary_keys,
+        refinement,
+    }: AddTables,
+    mode: Mode,
+) -> Result<Vec<Table>> {
+    if let Some(refinement) = refinement {
+        let allow_list = refinement.into_allow_list();
+        if !allow_list.is_empty() {
+            candidates = split_tables(candidates, &allow_list).0;
+        }
+    }
+
+    if !primary_keys.is_empty() {
+        return init::select_tables_by_keys(candidates, primary_keys);
+    }
+    if candidates.is_empty() {
+        eprintln!("no tables to add: the source has no tables that aren't already in the dataset");
+        return Ok(vec![]);
+    }
+    if mode != Mode::Prompt {
+        bail!("give the tables to add, and their primary keys, with --primary-key");
+    }
+    Ok(init::select_tables_and_keys(candidates)?)
+}
+
+/// Renders comparisons as text, a line per change.
+fn text(comparisons: &[DatasetComparison]) -> String {
+    let mut lines: Vec<String> = vec![];
+    for c in comparisons {
+        match c {
+            DatasetComparison::ExistingTable { table, diff } => {
+                let old_name = &table.name;
+                let (field_diffs, primary_key_diff, description_diff) = match diff {
+                    TableComparison::Unchanged => continue,
+                    TableComparison::Removed => {
+                        lines.push(format!("table removed: \"{old_name}\""));
+                        continue;
+                    }
+                    TableComparison::Renamed {
+                        new_name,
+                        similarity,
+                        field_diffs,
+                        primary_key_diff,
+                        description_diff,
+                    } => {
+                        lines.push(format!(
+                            "table renamed: \"{old_name}\" => \"{new_name}\" ({similarity}% similar)"
+                        ));
+                        (field_diffs, primary_key_diff, description_diff)
+                    }
+                    TableComparison::Modified {
                         field_diffs,
+                        primary_key_diff,
+                        description_diff,
                     } => {
-                        eprintln!("table modified: \"{old_name}\" ->");
-                        if let Some(pk_diff) = primary_key_diff {
-                            eprintln!(
-                                "  primary key modified: {} -> {}",
-                                &pk_diff.old.map_or("none".to_string(), |pk| pk.to_string()),
-                                &pk_diff.new.map_or("none".to_string(), |pk| pk.to_string())
-                            );
+                        lines.push(format!("table modified: \"{old_name}\" ->"));
+                        (field_diffs, primary_key_diff, description_diff)
+                    }
+                };
+
+                if description_diff.is_some() {
+                    lines.push("  description modified".to_string());
+                }
+
+                if let Some(pk_diff) = primary_key_diff {
+                    lines.push(format!(
+                        "  primary key modified: {} -> {}",
+                        &pk_diff.old.map_or("none".to_string(), |pk| pk.to_string()),
+                        &pk_diff.new.map_or("none".to_string(), |pk| pk.to_string())
+                    ));
+                }
+                for diff in field_diffs {
+                    match diff {
+                        FieldComparison::Added { new } => {
+                            lines.push(format!("  field added: \"{}\"", new.name))
                         }
-                        for diff in field_diffs {
-                            if !matches!(diff, FieldComparison::Unchanged { .. }) {
-                                eprint!("  ");
-                            }
-                            match diff {
-                                FieldComparison::Added { new } => {
-                                    eprintln!("field added: \"{}\"", new.name)
-                                }
-                                FieldComparison::Modified { old, .. } => {
-                                    eprintln!("field modified: \"{}\"", old.name)
-                                }
-                                FieldComparison::Removed { old } => {
-                                    eprintln!("field removed: \"{}\"", old.name)
-                                }
-                                FieldComparison::Unchanged { .. } => (/* print nothing */),
-                            }
+                        FieldComparison::Modified { old, new } => lines.push(format!(
+                            "  field modified: \"{}\" ({})",
+                            old.name,
+                            describe_field_change(old, new)
+                        )),
+                        FieldComparison::Renamed {
+                            old,
+                            new,
+                            similarity,
+                        } => lines.push(format!(
+                            "  field renamed: \"{}\" => \"{}\" ({similarity}% similar)",
+                            old.name, new.name
+                        )),
+                        FieldComparison::Removed { old } => {
+                            lines.push(format!("  field removed: \"{}\"", old.name))
                         }
+                        FieldComparison::Unchanged { .. } => (/* print nothing */),
+                    }
+                }
+            }
+            DatasetComparison::NewTable { table } => {
+                lines.push(format!("table added: \"{}\"", &table.name))
+            }
+        }
+    }
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// Lists what differs between two versions of a field, e.g. "type: string ->
+/// number, nullable: yes -> no".
+fn describe_field_change(old: &TableSchemaField, new: &TableSchemaField) -> String {
+    let mut changes = vec![];
+    if old.type_ != new.type_ {
+        changes.push(format!(
+            "type: {} -> {}",
+            field_type_name(&old.type_),
+            field_type_name(&new.type_)
+        ));
+    }
+    if old.is_required() != new.is_required() {
+        let nullable = |f: &TableSchemaField| if f.is_required() { "no" } else { "yes" };
+        changes.push(format!("nullable: {} -> {}", nullable(old), nullable(new)));
+    }
+    if old.description != new.description {
+        changes.push("description changed".into());
+    }
+    if changes.is_empty() {
+        changes.push("constraints changed".into());
+    }
+    changes.join(", ")
+}
+
+/// Renders comparisons as Markdown: a section per changed table, with a table
+/// of its changed fields.
+fn markdown(comparisons: &[DatasetComparison]) -> String {
+    fn escape(s: &str) -> String {
+        s.replace('|', "\\|").replace('\n', " ")
+    }
+    fn describe(field: Option<&TableSchemaField>) -> [String; 3] {
+        field.map_or(Default::default(), |f| {
+            [
+                format!("`{}`", field_type_name(&f.type_)),
+                if f.is_required() { "no" } else { "yes" }.into(),
+                escape(f.description.as_deref().unwrap_or_default()),
+            ]
+        })
+    }
+    fn fields_table<'a>(
+        out: &mut String,
+        rows: impl Iterator<
+            Item = (
+                &'a str,
+                Option<&'a TableSchemaField>,
+                Option<&'a TableSchemaField>,
+            ),
+        >,
+    ) {
+        out.push_str("\n| Field | Change | Type | Nullable | Description |\n");
+        out.push_str("| --- | --- | --- | --- | --- |\n");
+        for (change, old, new) in rows {
+            let name = match (old, new) {
+                (Some(old), Some(new)) if old.name != new.name => {
+                    format!("`{}` → `{}`", escape(&old.name), escape(&new.name))
+                }
+                _ => format!(
+                    "`{}`",
+                    escape(old.or(new).map(|f| f.name.as_str()).unwrap_or_default())
+                ),
+            };
+            let cells = describe(old)
+                .into_iter()
+                .zip(describe(new))
+                .map(|(old, new)| match (old.is_empty(), new.is_empty()) {
+                    (_, true) => old,
+                    (true, false) => new,
+                    (false, false) if old == new => new,
+                    (false, false) => format!("{old} → {new}"),
+                })
+                .collect::<Vec<_>>();
+            out.push_str(&format!("| {name} | {change} | {} |\n", cells.join(" | ")));
+        }
+    }
+
+    let mut out = String::new();
+    for c in comparisons {
+        match c {
+            DatasetComparison::ExistingTable { table, diff } => {
+                let name = escape(&table.name);
+                let (field_diffs, primary_key_diff, description_diff) = match diff {
+                    TableComparison::Unchanged => continue,
+                    TableComparison::Removed => {
+                        out.push_str(&format!("### Table `{name}` removed\n\n"));
+                        continue;
+                    }
+                    TableComparison::Renamed {
+                        new_name,
+                        similarity,
+                        field_diffs,
+                        primary_key_diff,
+                        description_diff,
+                    } => {
+                        out.push_str(&format!(
+                            "### Table `{name}` renamed to `{}` ({similarity}% similar)\n",
+                            escape(new_name)
+                        ));
+                        (field_diffs, primary_key_diff, description_diff)
                     }
+                    TableComparison::Modified {
+                        field_diffs,
+                        primary_key_diff,
+                        description_diff,
+                    } => {
+                        out.push_str(&format!("### Table `{name}` modified\n"));
+                        (field_diffs, primary_key_diff, description_diff)
+                    }
+                };
+
+                if description_diff.is_some() {
+                    out.push_str("\nDescription modified.\n");
+                }
+
+                if let Some(pk_diff) = primary_key_diff {
+                    let key = |pk: Option<&TableSchemaObjectPrimaryKey>| {
+                        pk.map_or("none".to_string(), |pk| format!("`{pk}`"))
+                    };
+                    out.push_str(&format!(
+                        "\nPrimary key: {} → {}\n",
+                        key(pk_diff.old),
+                        key(pk_diff.new)
+                    ));
+                }
+                if field_diffs.iter().any(FieldComparison::is_change) {
+                    fields_table(
+                        &mut out,
+                        field_diffs.iter().filter_map(|d| match d {
+                            FieldComparison::Added { new } => Some(("added", None, Some(*new))),
+                            FieldComparison::Modified { old, new } => {
+                                Some(("modified", Some(*old), Some(*new)))
+                            }
+                            FieldComparison::Removed { old } => Some(("removed", Some(*old), None)),
+                            FieldComparison::Renamed { old, new, .. } => {
+                                Some(("renamed", Some(*old), Some(*new)))
+                            }
+                            FieldComparison::Unchanged { .. } => None,
+                        }),
+                    );
                 }
             }
             DatasetComparison::NewTable { table } => {
-                eprintln!("table added: \"{}\"", &table.name)
+                out.push_str(&format!("### Table `{}` added\n", escape(&table.name)));
+                fields_table(
+                    &mut out,
+                    table.schema.fields.iter().map(|f| ("added", None, Some(f))),
+                );
             }
         }
+        out.push('\n');
+    }
+
+    if out.is_empty() {
+        "No changes.\n".into()
+    } else {
+        out
     }
 }
 
-/// Compares two `Dataset` instances.
-fn diff<'a>(old: &'a [Table], new: &'a [Table]) -> Vec<DatasetComparison<'a>> {
+/// Compares two `Dataset` instances. Tables and fields that are at least
+/// `rename_threshold` percent similar to ones that were removed are
+/// considered renamed, like git-log's `--find-renames` option. See
+/// https://git-scm.com/docs/git-log#Documentation/git-log.txt--Mltngt.
+fn diff<'a>(
+    old: &'a [Table],
+    new: &'a [Table],
+    rename_threshold: u8,
+) -> Vec<DatasetComparison<'a>> {
     let mut old_tables: Vec<&Table> = old.iter().collect();
     let mut new_tables: Vec<&Table> = new.iter().collect();
     let mut comparisons: Vec<DatasetComparison> = vec![];
@@ -184,78 +944,105 @@ fn diff<'a>(old: &'a [Table], new: &'a [Table]) -> Vec<DatasetComparison<'a>> {
     });
 
     old_tables.retain(|old_t| {
-        let Some((idx, matching_t)) = new_tables
+        let Some((idx, new_t)) = new_tables
             .iter()
             .enumerate()
-            .find(|(_, new_t)| new_t.schema == old_t.schema)
+            .find(|(_, t)| t.name == old_t.name)
         else {
             return true;
         };
 
-        // Same schema, different something else => Renamed.
+        // Same name, different schema (at least) => Modified.
         comparisons.push(DatasetComparison::ExistingTable {
             table: old_t,
-            diff: TableComparison::Renamed {
-                new_name: &matching_t.name,
+            diff: TableComparison::Modified {
+                primary_key_diff: diff_primary_keys(old_t, new_t),
+                field_diffs: diff_fields(old_t, new_t, rename_threshold),
+                description_diff: diff_descriptions(old_t, new_t),
             },
         });
         new_tables.remove(idx);
         false
     });
 
-    old_tables.retain(|old_t| {
-        let Some((idx, new_t)) = new_tables
-            .iter()
-            .enumerate()
-            .find(|(_, t)| t.name == old_t.name)
-        else {
-            return true;
-        };
-
-        // Same name, different schema (at least) => Modified.
+    // Similar enough schema, different name => Renamed.
+    let renames = pair_by_similarity(&old_tables, &new_tables, rename_threshold, |o, n| {
+        table_similarity(o, n)
+    });
+    for &(i, j, similarity) in &renames {
+        let (old_t, new_t) = (old_tables[i], new_tables[j]);
         comparisons.push(DatasetComparison::ExistingTable {
             table: old_t,
-            diff: TableComparison::Modified {
-                primary_key_diff: {
-                    let old_pk = old_t.schema.primary_key();
-                    let new_pk = new_t.schema.primary_key();
-                    if old_pk == new_pk {
-                        None
-                    } else {
-                        Some(PrimaryKeyComparison {
-                            old: old_pk,
-                            new: new_pk,
-                        })
-                    }
-                },
-                field_diffs: diff_fields(old_t, new_t),
+            diff: TableComparison::Renamed {
+                new_name: &new_t.name,
+                similarity,
+                primary_key_diff: diff_primary_keys(old_t, new_t),
+                field_diffs: diff_fields(old_t, new_t, rename_threshold),
+                description_diff: diff_descriptions(old_t, new_t),
             },
         });
-        new_tables.remove(idx);
-        false
-    });
+    }
+    let (renamed_old, renamed_new): (HashSet<usize>, HashSet<usize>) =
+        renames.iter().map(|&(i, j, _)| (i, j)).unzip();
 
-    old_tables.retain(|old_t| {
+    for (_, old_t) in old_tables
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !renamed_old.contains(i))
+    {
         // No table with this name exists => Table was removed.
         comparisons.push(DatasetComparison::ExistingTable {
             table: old_t,
             diff: TableComparison::Removed,
         });
-        false
-    });
+    }
 
-    while let Some(new_t) = new_tables.pop() {
+    for (_, new_t) in new_tables
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(j, _)| !renamed_new.contains(j))
+    {
         comparisons.push(DatasetComparison::NewTable { table: new_t });
     }
 
     comparisons
 }
 
-fn diff_fields<'a>(old_table: &'a Table, new_table: &'a Table) -> Vec<FieldComparison<'a>> {
+fn diff_primary_keys<'a>(old_t: &'a Table, new_t: &'a Table) -> Option<PrimaryKeyComparison<'a>> {
+    let old_pk = old_t.schema.primary_key();
+    let new_pk = new_t.schema.primary_key();
+    if old_pk == new_pk {
+        None
+    } else {
+        Some(PrimaryKeyComparison {
+            old: old_pk,
+            new: new_pk,
+        })
+    }
+}
+
+fn diff_descriptions<'a>(old_t: &'a Table, new_t: &'a Table) -> Option<DescriptionComparison<'a>> {
+    if old_t.description == new_t.description {
+        None
+    } else {
+        Some(DescriptionComparison {
+            old: old_t.description.as_ref(),
+            new: new_t.description.as_ref(),
+        })
+    }
+}
+
+fn diff_fields<'a>(
+    old_table: &'a Table,
+    new_table: &'a Table,
+    rename_threshold: u8,
+) -> Vec<FieldComparison<'a>> {
     let old_fields = &old_table.schema.fields;
     let mut new_fields: Vec<&TableSchemaField> =
         new_table.schema.fields.as_slice().iter().collect();
     let mut comparisons: Vec<FieldComparison<'a>> = vec![];
+    let mut removed: Vec<&TableSchemaField> = vec![];
 
     for old_f in old_fields {
         if let Some((idx, &new_f)) = new_fields
@@ -274,65 +1061,260 @@ fn diff_fields<'a>(old_table: &'a Table, new_table: &'a Table) -> Vec<FieldCompa
             });
             new_fields.remove(idx);
         } else {
-            // No field exists with the same name => Removed.
-            comparisons.push(FieldComparison::Removed { old: old_f });
+            removed.push(old_f);
         }
     }
 
-    while let Some(new_f) = new_fields.pop() {
-        comparisons.push(FieldComparison::Added { new: new_f });
+    // No field exists with the same name => Renamed if a similar one was
+    // added, and Removed otherwise.
+    let renames = pair_by_similarity(&removed, &new_fields, rename_threshold, |o, n| {
+        field_similarity(o, n)
+    });
+    for (i, old_f) in removed.iter().enumerate() {
+        comparisons.push(match renames.iter().find(|&&(k, _, _)| k == i) {
+            Some(&(_, j, similarity)) => FieldComparison::Renamed {
+                old: old_f,
+                new: new_fields[j],
+                similarity,
+            },
+            None => FieldComparison::Removed { old: old_f },
+        });
+    }
+
+    for (j, new_f) in new_fields.iter().enumerate().rev() {
+        if !renames.iter().any(|&(_, k, _)| k == j) {
+            comparisons.push(FieldComparison::Added { new: new_f });
+        }
     }
 
     comparisons
 }
 
+/// Pairs elements of `old` with elements of `new` that are at least
+/// `threshold` percent similar, most similar pairs first. Returns the index in
+/// `old`, index in `new` and similarity of each pair.
+fn pair_by_similarity<T, U>(
+    old: &[T],
+    new: &[U],
+    threshold: u8,
+    similarity: impl Fn(&T, &U) -> u8,
+) -> Vec<(usize, usize, u8)> {
+    let mut candidates: Vec<(usize, usize, u8)> = old
+        .iter()
+        .enumerate()
+        .flat_map(|(i, o)| new.iter().enumerate().map(move |(j, n)| (i, j, o, n)))
+        .map(|(i, j, o, n)| (i, j, similarity(o, n)))
+        .filter(|&(_, _, s)| s >= threshold)
+        .collect();
+    candidates.sort_by_key(|&(i, j, s)| (std::cmp::Reverse(s), i, j));
+
+    let mut pairs: Vec<(usize, usize, u8)> = vec![];
+    for candidate @ (i, j, _) in candidates {
+        if !pairs.iter().any(|&(k, l, _)| k == i || l == j) {
+            pairs.push(candidate);
+        }
+    }
+    pairs
+}
+
+/// The percentage of fields two tables have in common, by name and type.
+fn table_similarity(old: &Table, new: &Table) -> u8 {
+    let (old_fields, new_fields) = (&old.schema.fields, &new.schema.fields);
+    let total = old_fields.len() + new_fields.len();
+    if total == 0 {
+        return 100;
+    }
+    let common = old_fields
+        .iter()
+        .filter(|o| {
+            new_fields
+                .iter()
+                .any(|n| n.name == o.name && n.type_ == o.type_)
+        })
+        .count();
+    (common * 2 * 100 / total) as u8
+}
+
+/// How similar two fields are, as a percentage. Fields of different types are
+/// 0% similar. Otherwise, it's the similarity of their names, by edit
+/// distance, reduced by a quarter each if their nullability or description
+/// differ. Names shorter than [`MIN_SIMILAR_NAME_LEN`] that share no word,
+/// e.g. `ID` and `IS`, aren't similar at all.
+fn field_similarity(old: &TableSchemaField, new: &TableSchemaField) -> u8 {
+    if old.type_ != new.type_ {
+        return 0;
+    }
+    let (old_name, new_name) = (old.name.to_lowercase(), new.name.to_lowercase());
+    let (old_len, new_len) = (old_name.chars().count(), new_name.chars().count());
+    let shares_word = words(&old_name).any(|w| words(&new_name).any(|v| v == w));
+    if old_name != new_name && old_len.min(new_len) < MIN_SIMILAR_NAME_LEN && !shares_word {
+        return 0;
+    }
+    let longest = old_len.max(new_len);
+    let name_similarity = if longest == 0 {
+        100
+    } else {
+        100 - edit_distance(&old_name, &new_name) * 100 / longest
+    };
+    let same_attributes = usize::from(old.is_required() == new.is_required())
+        + usize::from(old.description == new.description);
+    (name_similarity * (2 + same_attributes) / 4) as u8
+}
+
+/// The words in a field name, e.g. `order` and `id` in `order_id`.
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(previous + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
 /// The result of comparing one table to another.
+#[derive(Serialize)]
+#[serde(tag = "change", rename_all = "camelCase")]
 enum TableComparison<'a> {
     /// Table is unchanged.
     Unchanged,
-    /// Table was renamed. A table is considered renamed if it has exactly the
-    /// schema it did previously. This definition is akin to git-log's
-    /// `--find-renames` option with a similarity index of 100%. See
-    /// https://git-scm.com/docs/git-log#Documentation/git-log.txt--Mltngt.
-    Renamed { new_name: &'a String },
+    /// Table was renamed. A table is considered renamed if its schema is
+    /// similar enough to that of a table with a new name; see [`diff`].
+    Renamed {
+        #[serde(rename = "newName")]
+        new_name: &'a String,
+        /// Percentage similarity of the table's schemas.
+        similarity: u8,
+        #[serde(rename = "fieldDiffs", serialize_with = "serialize_changes")]
+        field_diffs: Vec<FieldComparison<'a>>,
+        #[serde(rename = "primaryKeyDiff")]
+        primary_key_diff: Option<PrimaryKeyComparison<'a>>,
+        #[serde(rename = "descriptionDiff", skip_serializing_if = "Option::is_none")]
+        description_diff: Option<DescriptionComparison<'a>>,
+    },
     /// Table was removed from the new dataset.
     Removed,
-    /// Table still exists, but its columns were modified.
+    /// Table still exists, but its columns or description were modified.
     Modified {
+        #[serde(rename = "fieldDiffs", serialize_with = "serialize_changes")]
         field_diffs: Vec<FieldComparison<'a>>,
+        #[serde(rename = "primaryKeyDiff")]
         primary_key_diff: Option<PrimaryKeyComparison<'a>>,
+        #[serde(rename = "descriptionDiff", skip_serializing_if = "Option::is_none")]
+        description_diff: Option<DescriptionComparison<'a>>,
     },
 }
 
+#[derive(Serialize)]
+struct DescriptionComparison<'a> {
+    pub old: Option<&'a String>,
+    pub new: Option<&'a String>,
+}
+
+#[derive(Serialize)]
 struct PrimaryKeyComparison<'a> {
     pub old: Option<&'a TableSchemaObjectPrimaryKey>,
     pub new: Option<&'a TableSchemaObjectPrimaryKey>,
 }
 
 /// A comparison between the fields of two tables.
+#[derive(Serialize)]
+#[serde(tag = "change", rename_all = "camelCase")]
 enum FieldComparison<'a> {
     /// A field with a fresh name is considered added.
-    Added { new: &'a TableSchemaField },
+    Added {
+        #[serde(serialize_with = "serialize_field")]
+        new: &'a TableSchemaField,
+    },
     /// A field with an existing name but different definition.
     Modified {
+        #[serde(serialize_with = "serialize_field")]
         old: &'a TableSchemaField,
-        #[allow(dead_code)]
+        #[serde(serialize_with = "serialize_field")]
         new: &'a TableSchemaField,
     },
+    /// A field with an existing name that no longer exists, but that is
+    /// similar enough to a field with a new name.
+    Renamed {
+        #[serde(serialize_with = "serialize_field")]
+        old: &'a TableSchemaField,
+        #[serde(serialize_with = "serialize_field")]
+        new: &'a TableSchemaField,
+        /// Percentage similarity of the fields.
+        similarity: u8,
+    },
     /// A field with an existing name that no longer exists.
-    Removed { old: &'a TableSchemaField },
+    Removed {
+        #[serde(serialize_with = "serialize_field")]
+        old: &'a TableSchemaField,
+    },
     /// Field is unchanged.
-    #[allow(dead_code)]
-    Unchanged { old: &'a TableSchemaField },
+    Unchanged {
+        #[serde(serialize_with = "serialize_field")]
+        old: &'a TableSchemaField,
+    },
+}
+
+impl FieldComparison<'_> {
+    fn is_change(&self) -> bool {
+        !matches!(self, FieldComparison::Unchanged { .. })
+    }
+}
+
+/// The parts of a field that a comparison reports.
+#[derive(Serialize)]
+struct FieldSummary<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    type_: &'a FieldType,
+    nullable: bool,
+    description: Option<&'a str>,
+}
+
+fn serialize_field<S: Serializer>(field: &&TableSchemaField, s: S) -> Result<S::Ok, S::Error> {
+    FieldSummary {
+        name: &field.name,
+        type_: &field.type_,
+        nullable: !field.is_required(),
+        description: field.description.as_deref(),
+    }
+    .serialize(s)
+}
+
+/// Serializes only the fields that changed.
+fn serialize_changes<S: Serializer>(diffs: &[FieldComparison], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(diffs.iter().filter(|d| d.is_change()))
+}
+
+/// Returns the name of a field type as it appears in specs.
+pub fn field_type_name(type_: &FieldType) -> String {
+    serde_json::to_value(type_)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
 }
 
 /// The difference between two datasets can be described as a set of
 /// `DatasetComparison` structs.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
 enum DatasetComparison<'a> {
     /// A description of possible change undergone by a table that appeared in
     /// the old dataset.
     ExistingTable {
         /// Table as it existed in the old dataset.
+        #[serde(serialize_with = "serialize_table_name")]
         table: &'a Table,
         /// A description of how this table is different in the new dataset.
         diff: TableComparison<'a>,
@@ -340,3 +1322,418 @@ enum DatasetComparison<'a> {
     /// A description of a table that exists only in the new dataset.
     NewTable { table: &'a Table },
 }
+
+fn serialize_table_name<S: Serializer>(table: &&Table, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&table.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{descriptor::Constraints, util::SourcePath};
+
+    fn tables() -> (Vec<Table>, Vec<Table>) {
+        let old = Dataset::read("tests/resources/dataset.json")
+            .unwrap()
+            .tables;
+        let mut new = old.clone();
+        let fields = &mut new[0].schema.fields;
+        fields[1].type_ = FieldType::Number;
+        fields[1].constraints = Some(Constraints {
+            required: Some(true),
+        });
+        fields.remove(2);
+        (old, new)
+    }
+
+    #[test]
+    fn comparisons_serialize() {
+        let (old, new) = tables();
+        assert_eq!(
+            serde_json::to_value(diff(&old, &new, DEFAULT_RENAME_THRESHOLD)).unwrap(),
+            json!([{
+                "kind": "existingTable",
+                "table": "UK_REAL_ESTATE_RECORDS",
+                "diff": {
+                    "change": "modified",
+                    "primaryKeyDiff": null,
+                    "fieldDiffs": [
+                        {
+                            "change": "modified",
+                            "old": {"name": "PRICE", "type": "string", "nullable": true, "description": null},
+                            "new": {"name": "PRICE", "type": "number", "nullable": false, "description": null},
+                        },
+                        {
+                            "change": "removed",
+                            "old": {"name": "DATE_OF_TRANSFER", "type": "date", "nullable": true, "description": null},
+                        },
+                    ],
+                },
+            }])
+        );
+    }
+
+    #[test]
+    fn outcome_serializes() {
+        let (old, new) = tables();
+        let outcome = Outcome {
+            spec: Path::new("dataset.json"),
+            diff: compare(&old, &new[..0], Format::Text, DEFAULT_RENAME_THRESHOLD),
+            suggested_version: Some("2.0.0".parse().unwrap()),
+            status: Status::Written {
+                version: "2.0.0".parse().unwrap(),
+                backup: "dataset.json.backup".into(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(outcome).unwrap(),
+            json!({
+                "spec": "dataset.json",
+                "tables": [{
+                    "kind": "existingTable",
+                    "table": "UK_REAL_ESTATE_RECORDS",
+                    "diff": {"change": "removed"},
+                }],
+                "changes": [{
+                    "impact": "major",
+                    "description": "table \"UK_REAL_ESTATE_RECORDS\" removed",
+                }],
+                "suggestedVersion": "2.0.0",
+                "status": "written",
+                "version": "2.0.0",
+                "backup": "dataset.json.backup",
+            })
+        );
+    }
+
+    #[test]
+    fn find_renamed_tables_works() {
+        let (old, _) = tables();
+        let renamed = |name: &str| {
+            let mut table = old[0].clone();
+            table.name = name.into();
+            table.source.path = SourcePath::Snowflake {
+                schema: "PUBLIC".into(),
+                table: name.into(),
+            };
+            table.schema.primary_key = None;
+            table
+        };
+        let mut unrelated = renamed("ORDERS");
+        unrelated.schema.fields.truncate(1);
+
+        let mut updated = vec![];
+        let mut candidates = vec![unrelated.clone(), renamed("UK_RE_RECORDS")];
+        find_renamed_tables(
+            &old,
+            &mut updated,
+            &mut candidates,
+            DEFAULT_RENAME_THRESHOLD,
+        );
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].name, "UK_RE_RECORDS");
+        assert_eq!(updated[0].schema.primary_key, old[0].schema.primary_key);
+        assert_eq!(candidates, vec![unrelated.clone()]);
+        assert_eq!(
+            text(&diff(&old, &updated, DEFAULT_RENAME_THRESHOLD)),
+            "table renamed: \"UK_REAL_ESTATE_RECORDS\" => \"UK_RE_RECORDS\" (100% similar)\n"
+        );
+
+        // Tables still in the source aren't matched again.
+        let mut updated = old.clone();
+        let mut candidates = vec![renamed("UK_RE_RECORDS")];
+        find_renamed_tables(
+            &old,
+            &mut updated,
+            &mut candidates,
+            DEFAULT_RENAME_THRESHOLD,
+        );
+        assert_eq!((updated.len(), candidates.len()), (1, 1));
+
+        let mut updated = vec![];
+        let mut candidates = vec![unrelated];
+        find_renamed_tables(
+            &old,
+            &mut updated,
+            &mut candidates,
+            DEFAULT_RENAME_THRESHOLD,
+        );
+        assert!(updated.is_empty());
+    }
+
+    #[test]
+    fn select_new_tables_works() {
+        let (old, _) = tables();
+        let with_path = |schema: &str, name: &str| {
+            let mut table = old[0].clone();
+            table.name = name.into();
+            table.source.path = SourcePath::Snowflake {
+                schema: schema.into(),
+                table: name.into(),
+            };
+            table
+        };
+        let source_tables = vec![
+            old[0].clone(),
+            with_path("PUBLIC", "ORDERS"),
+            with_path("STAGING", "ORDERS"),
+        ];
+
+        let (existing, candidates) = split_tables(
+            source_tables,
+            &Dataset::read("tests/resources/dataset.json")
+                .unwrap()
+                .allow_list(),
+        );
+        assert_eq!(existing, old);
+        assert_eq!(candidates.len(), 2);
+
+        let add = |keys: &[&str], schemas: &[&str], mode| {
+            select_new_tables(
+                candidates.clone(),
+                AddTables {
+                    primary_keys: keys.iter().map(|k| k.parse().unwrap()).collect(),
+                    refinement: Some(DescribeRefinement::Snowflake {
+                        table: vec![],
+                        schema: schemas.iter().map(|s| s.to_string()).collect(),
+                    }),
+                },
+                mode,
+            )
+        };
+
+        let added = add(&["ORDERS=PRICE"], &["STAGING"], Mode::Yes).unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].qualified_name(), "STAGING.ORDERS");
+        assert_eq!(
+            added[0].schema.primary_key,
+            Some(TableSchemaObjectPrimaryKey::Variant0(vec!["PRICE".into()]))
+        );
+
+        // Existing tables can't be added again.
+        assert!(add(&["UK_REAL_ESTATE_RECORDS=PRICE"], &[], Mode::Yes).is_err());
+        // Without a terminal to prompt on, keys must be given.
+        assert!(add(&[], &[], Mode::Check).is_err());
+    }
+
+    #[test]
+    fn classify_works() {
+        let (old, new) = tables();
+        assert_eq!(
+            changes(&old, &new, DEFAULT_RENAME_THRESHOLD)
+                .iter()
+                .map(|c| (c.impact, c.description.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Impact::Major,
+                    "field \"UK_REAL_ESTATE_RECORDS\".\"PRICE\" modified (type: string -> number, nullable: yes -> no)"
+                ),
+                (
+                    Impact::Major,
+                    "field \"UK_REAL_ESTATE_RECORDS\".\"DATE_OF_TRANSFER\" removed"
+                ),
+            ]
+        );
+
+        let mut described = old.clone();
+        described[0].schema.fields[0].description = Some("ID".into());
+        described[0].schema.fields[1].constraints = Some(Constraints {
+            required: Some(true),
+        });
+        assert_eq!(
+            changes(&old, &described, DEFAULT_RENAME_THRESHOLD)
+                .iter()
+                .map(|c| c.impact)
+                .collect::<Vec<_>>(),
+            vec![Impact::Patch, Impact::Minor]
+        );
+        assert!(changes(&old, &old, DEFAULT_RENAME_THRESHOLD).is_empty());
+
+        // A table's description change is listed even with other changes.
+        let mut new = new;
+        new[0].description = Some("Sales".into());
+        let change = &changes(&old, &new, DEFAULT_RENAME_THRESHOLD)[0];
+        assert_eq!(
+            (change.impact, change.description.as_str()),
+            (
+                Impact::Patch,
+                "table \"UK_REAL_ESTATE_RECORDS\" description changed"
+            )
+        );
+        assert!(
+            text(&diff(&old, &new, DEFAULT_RENAME_THRESHOLD)).starts_with(
+                "table modified: \"UK_REAL_ESTATE_RECORDS\" ->\n  description modified\n"
+            )
+        );
+    }
+
+    #[test]
+    fn bump_works() {
+        let v = |s: &str| s.parse::<Version>().unwrap();
+        assert_eq!(bump(&v("1.2.3"), Impact::Major), v("2.0.0"));
+        assert_eq!(bump(&v("1.2.3"), Impact::Minor), v("1.3.0"));
+        assert_eq!(bump(&v("1.2.3-rc.1"), Impact::Patch), v("1.2.4"));
+        assert_eq!(bump(&v("0.1.2"), Impact::Major), v("0.2.0"));
+        assert_eq!(bump(&v("0.1.2"), Impact::Minor), v("0.1.3"));
+
+        assert_eq!(allowed_impact(&v("1.2.3"), &v("1.2.3")), None);
+        assert_eq!(
+            allowed_impact(&v("1.2.3"), &v("1.2.4")),
+            Some(Impact::Patch)
+        );
+        assert_eq!(
+            allowed_impact(&v("1.2.3"), &v("1.4.0")),
+            Some(Impact::Minor)
+        );
+        assert_eq!(
+            allowed_impact(&v("1.2.3"), &v("2.0.0-beta")),
+            Some(Impact::Major)
+        );
+        assert_eq!(
+            allowed_impact(&v("0.1.2"), &v("0.1.3")),
+            Some(Impact::Minor)
+        );
+        assert_eq!(
+            allowed_impact(&v("0.1.2"), &v("0.2.0")),
+            Some(Impact::Major)
+        );
+    }
+
+    #[test]
+    fn suggest_version_works() {
+        let v = |s: &str| s.parse::<Version>().unwrap();
+        let latest = v("1.0.0");
+        assert_eq!(
+            suggest_version(&v("1.0.0"), Some(&latest), Impact::Major),
+            Some(v("2.0.0"))
+        );
+        // Updating again before publishing doesn't bump again.
+        assert_eq!(
+            suggest_version(&v("2.0.0"), Some(&latest), Impact::Major),
+            None
+        );
+        assert_eq!(
+            suggest_version(&v("1.1.0"), Some(&latest), Impact::Minor),
+            None
+        );
+        // A bigger change than the spec's version allows bumps from the
+        // release, not the spec.
+        assert_eq!(
+            suggest_version(&v("1.1.0"), Some(&latest), Impact::Major),
+            Some(v("2.0.0"))
+        );
+        assert_eq!(suggest_version(&v("0.1.0"), None, Impact::Major), None);
+    }
+
+    #[test]
+    fn renames_are_detected() {
+        let (old, _) = tables();
+        let mut new = old.clone();
+        new[0].name = "UK_RE_RECORDS".into();
+        new[0].schema.fields[1].name = "PRICES".into();
+        new[0].schema.fields[5].name = "TOWN".into();
+
+        let summary = |threshold| text(&diff(&old, &new, threshold));
+        assert_eq!(
+            summary(DEFAULT_RENAME_THRESHOLD),
+            r#"table renamed: "UK_REAL_ESTATE_RECORDS" => "UK_RE_RECORDS" (81% similar)
+  field renamed: "PRICE" => "PRICES" (84% similar)
+  field removed: "DURATION"
+  field added: "TOWN"
+"#
+        );
+        assert_eq!(
+            summary(100),
+            r#"table removed: "UK_REAL_ESTATE_RECORDS"
+table added: "UK_RE_RECORDS"
+"#
+        );
+    }
+
+    #[test]
+    fn similarity_works() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+
+        let (old, _) = tables();
+        let price = &old[0].schema.fields[1];
+        let renamed = |name: &str| TableSchemaField {
+            name: name.into(),
+            ..price.clone()
+        };
+        assert_eq!(field_similarity(price, &renamed("price")), 100);
+        assert_eq!(field_similarity(price, &renamed("PRICE_GBP")), 56);
+        assert_eq!(
+            field_similarity(
+                price,
+                &TableSchemaField {
+                    description: Some("In pounds".into()),
+                    ..renamed("PRICE_GBP")
+                }
+            ),
+            42
+        );
+        assert_eq!(
+            field_similarity(
+                price,
+                &TableSchemaField {
+                    type_: FieldType::Number,
+                    ..price.clone()
+                }
+            ),
+            0
+        );
+        assert_eq!(table_similarity(&old[0], &old[0]), 100);
+
+        // Short or unrelated names aren't similar enough to be renames.
+        let similarity = |old: &str, new: &str| field_similarity(&renamed(old), &renamed(new));
+        assert_eq!(similarity("ID", "IS"), 0);
+        assert_eq!(similarity("ID", "ORDER_ID"), 25);
+        assert!(similarity("AMOUNT", "COUNT") < DEFAULT_RENAME_THRESHOLD);
+        assert!(similarity("CUSTOMER_NAME", "CUSTOMER_NAMES") >= DEFAULT_RENAME_THRESHOLD);
+    }
+
+    #[test]
+    fn dissimilar_fields_are_not_renamed() {
+        let (old, _) = tables();
+        let mut new = old.clone();
+        new[0].schema.fields[1].name = "PRICE_GBP".into();
+
+        assert_eq!(
+            text(&diff(&old, &new, DEFAULT_RENAME_THRESHOLD)),
+            r#"table modified: "UK_REAL_ESTATE_RECORDS" ->
+  field removed: "PRICE"
+  field added: "PRICE_GBP"
+"#
+        );
+        assert_eq!(
+            text(&diff(&old, &new, 50)),
+            r#"table modified: "UK_REAL_ESTATE_RECORDS" ->
+  field renamed: "PRICE" => "PRICE_GBP" (56% similar)
+"#
+        );
+    }
+
+    #[test]
+    fn markdown_works() {
+        let (old, new) = tables();
+        assert_eq!(
+            markdown(&diff(&old, &new, DEFAULT_RENAME_THRESHOLD)),
+            "### Table `UK_REAL_ESTATE_RECORDS` modified
+
+| Field | Change | Type | Nullable | Description |
+| --- | --- | --- | --- | --- |
+| `PRICE` | modified | `string` → `number` | yes → no |  |
+| `DATE_OF_TRANSFER` | removed | `date` | yes |  |
+
+"
+        );
+        assert_eq!(
+            markdown(&diff(&old, &old, DEFAULT_RENAME_THRESHOLD)),
+            "No changes.\n"
+        );
+    }
+}
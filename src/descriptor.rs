@@ -1,7 +1,11 @@
 mod dataset;
+mod schema;
 mod table_schema;
+mod validation;
 
-pub use dataset::{Dataset, Name, Table, TableSource};
+pub use dataset::{Dataset, Name, SpecFormat, Table, TableSource};
+pub use schema::{json_schema, write_json_schema, SCHEMA_FILE, SCHEMA_ID};
 pub use table_schema::{
     Constraints, FieldType, TableSchema, TableSchemaField, TableSchemaObjectPrimaryKey,
 };
+pub use validation::{json_pointer, validate, Problem};
@@ -1,19 +1,45 @@
-use std::{fmt::Display, fs::File, io::BufReader, path::Path};
+use std::{fmt::Display, fs, path::Path};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use schemars::JsonSchema;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use uuid7::Uuid as Uuid7;
 
-use super::{table_schema::TableSchema, Constraints, FieldType, TableSchemaField};
+use super::{
+    json_pointer, table_schema::TableSchema, validate, Constraints, FieldType, TableSchemaField,
+};
 use crate::{
     api,
     util::{AllowListItem, SourcePath},
 };
 
+/// A format a spec may be written in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpecFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl SpecFormat {
+    /// Returns the format of the spec at path, according to its extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(SpecFormat::Json),
+            Some("yaml" | "yml") => Ok(SpecFormat::Yaml),
+            Some("toml") => Ok(SpecFormat::Toml),
+            _ => bail!(
+                "unrecognized spec format of {}; expected a .json, .yaml, .yml or .toml file",
+                path.display()
+            ),
+        }
+    }
+}
+
 /// The logical address of a table.
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 pub struct TableSource {
     /// Reference to a DPM Cloud source entity.
     pub id: Uuid,
@@ -21,8 +47,13 @@ pub struct TableSource {
     pub path: SourcePath,
 }
 
-#[derive(Deserialize, Clone, Serialize)]
+#[doc = "A dataset spec: a definition of a virtual dataset containing data from some source."]
+#[derive(Deserialize, Clone, JsonSchema, Serialize)]
 pub struct Dataset {
+    #[doc = "The JSON Schema the spec conforms to."]
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[schemars(with = "Uuid")]
     pub id: Uuid7,
     pub name: Name,
     pub description: Option<String>,
@@ -34,15 +65,70 @@ pub struct Dataset {
 }
 
 impl Dataset {
-    /// Reads dataset.json at path and returns a deserialized instance of Dataset.
+    /// Reads the spec at path and returns a deserialized instance of Dataset.
+    /// The spec's format is chosen by the path's extension.
     pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
+        let format = SpecFormat::from_path(path.as_ref())?;
+        let contents = fs::read_to_string(path)?;
 
-        let dataset = serde_json::from_reader(reader).context("deserialization failed")?;
+        let dataset = match format {
+            SpecFormat::Json => {
+                serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(&contents))
+                    .map_err(|e| anyhow!("{}: {}", json_pointer(e.path()), e.inner()))
+            }
+            SpecFormat::Yaml => {
+                serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(&contents))
+                    .map_err(|e| anyhow!("{}: {}", json_pointer(e.path()), e.inner()))
+            }
+            SpecFormat::Toml => {
+                serde_path_to_error::deserialize(toml::Deserializer::new(&contents))
+                    .map_err(|e| anyhow!("{}: {}", json_pointer(e.path()), e.inner()))
+            }
+        }
+        .context("deserialization failed")?;
         Ok(dataset)
     }
 
+    /// Serializes the spec in the given format.
+    pub fn to_string(&self, format: SpecFormat) -> Result<String> {
+        Ok(match format {
+            SpecFormat::Json => serde_json::to_string_pretty(self)?,
+            SpecFormat::Yaml => serde_yaml::to_string(self)?,
+            SpecFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    /// Writes the spec to path, in the format chosen by the path's extension.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let contents = self.to_string(SpecFormat::from_path(path.as_ref())?)?;
+        fs::write(path, contents).map_err(Into::into)
+    }
+
+    /// Fails, listing every problem found by [`validate`], if the dataset is
+    /// invalid.
+    pub fn check(&self) -> Result<()> {
+        let problems = validate(self);
+        if !problems.is_empty() {
+            bail!(
+                "spec is invalid:\n{}",
+                problems
+                    .iter()
+                    .map(|p| format!("  {p}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns whether any of the dataset's tables are in a local source,
+    /// which DPM Cloud can't access.
+    pub fn is_local(&self) -> bool {
+        self.tables
+            .iter()
+            .any(|table| matches!(table.source.path, SourcePath::DuckDb { .. }))
+    }
+
     /// Returns an allow list that may be used to recover the set of tables in
     /// `self` from a larger collection.
     pub fn allow_list(&self) -> Vec<AllowListItem> {
@@ -54,6 +140,23 @@ impl Dataset {
                     schema: Some(schema),
                     table,
                 },
+                SourcePath::Postgres { schema, table } => AllowListItem::PostgresTable {
+                    schema: Some(schema),
+                    table,
+                },
+                SourcePath::Databricks {
+                    catalog,
+                    schema,
+                    table,
+                } => AllowListItem::DatabricksTable {
+                    catalog: Some(catalog),
+                    schema: Some(schema),
+                    table,
+                },
+                SourcePath::DuckDb { schema, table, .. } => AllowListItem::DuckDbTable {
+                    schema: Some(schema),
+                    table,
+                },
             })
             .collect()
     }
@@ -65,9 +168,29 @@ impl Dataset {
     }
 }
 
+const NAME_PATTERN: &str = "^[A-Za-z0-9-_]+$";
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Name(String);
 
+impl JsonSchema for Name {
+    fn schema_name() -> String {
+        "Name".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            string: Some(Box::new(schemars::schema::StringValidation {
+                pattern: Some(NAME_PATTERN.into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl std::ops::Deref for Name {
     type Target = String;
     fn deref(&self) -> &String {
@@ -83,19 +206,19 @@ impl std::fmt::Display for Name {
 impl std::str::FromStr for Name {
     type Err = anyhow::Error;
     fn from_str(value: &str) -> Result<Self, anyhow::Error> {
-        if regress::Regex::new("^[A-Za-z0-9-_]+$")
+        if regress::Regex::new(NAME_PATTERN)
             .unwrap()
             .find(value)
             .is_none()
         {
-            bail!("doesn't match pattern \"^[A-Za-z0-9-_]+$\"");
+            bail!("doesn't match pattern \"{}\"", NAME_PATTERN);
         }
         Ok(Self(value.to_string()))
     }
 }
 
 #[doc = "Data Resource."]
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 pub struct Table {
     pub name: String,
     pub description: Option<String>,
@@ -122,7 +245,14 @@ impl TryFrom<api::TableMetadata> for Table {
                 SourcePath::Snowflake {
                     schema: _schema,
                     table,
+                }
+                | SourcePath::Postgres {
+                    schema: _schema,
+                    table,
                 } => table.to_owned(),
+                SourcePath::Databricks { table, .. } | SourcePath::DuckDb { table, .. } => {
+                    table.to_owned()
+                }
             },
             description: None,
             source: value.source,
@@ -214,3 +344,28 @@ impl TryFrom<api::FieldSchema> for TableSchemaField {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_round_trip() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let dir = std::env::temp_dir().join(format!("dpm-spec-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for extension in ["json", "yaml", "yml", "toml"] {
+            let path = dir.join(format!("dataset.{extension}"));
+            dataset.write(&path).unwrap();
+            assert_eq!(
+                serde_json::to_value(Dataset::read(&path).unwrap()).unwrap(),
+                serde_json::to_value(&dataset).unwrap(),
+                "{extension}"
+            );
+        }
+        assert!(SpecFormat::from_path(Path::new("dataset.txt")).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
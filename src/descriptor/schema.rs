@@ -0,0 +1,64 @@
+//! The JSON Schema that specs conform to.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use schemars::{gen::SchemaSettings, schema::RootSchema};
+
+use super::Dataset;
+
+/// The URI that identifies the schema, as its `$id`. Its version is to be
+/// incremented whenever the spec format changes incompatibly.
+pub const SCHEMA_ID: &str = "https://www.dpm.sh/schemas/dataset.v1.json";
+
+/// Name of the file that `init` writes the schema to, next to the spec, and
+/// that specs refer to with `$schema`.
+pub const SCHEMA_FILE: &str = "dataset.schema.v1.json";
+
+/// Returns the JSON Schema of specs.
+pub fn json_schema() -> RootSchema {
+    let mut schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<Dataset>();
+    let metadata = schema.schema.metadata();
+    metadata.id = Some(SCHEMA_ID.into());
+    metadata.title = Some("dpm dataset spec".into());
+    schema
+}
+
+/// Writes the JSON Schema of specs to `path`.
+pub fn write_json_schema(path: &Path) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(&json_schema())? + "\n")
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_schema_works() {
+        let schema = serde_json::to_value(json_schema()).unwrap();
+        assert_eq!(schema["$id"], SCHEMA_ID);
+        assert_eq!(schema["properties"]["name"]["$ref"], "#/definitions/Name");
+        assert_eq!(schema["definitions"]["Name"]["pattern"], "^[A-Za-z0-9-_]+$");
+        for definition in [
+            "Table",
+            "TableSource",
+            "SourcePath",
+            "TableSchema",
+            "TableSchemaField",
+            "TableSchemaObjectPrimaryKey",
+            "Constraints",
+        ] {
+            assert!(
+                schema["definitions"][definition].is_object(),
+                "{definition}"
+            );
+        }
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["dataset", "id", "name", "version"])
+        );
+    }
+}
@@ -1,15 +1,16 @@
 use std::fmt::Display;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 pub struct Constraints {
     #[doc = "Indicates whether a property must have a value for each instance."]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 pub struct TableSchema {
     pub fields: Vec<TableSchemaField>,
     #[serde(
@@ -29,7 +30,7 @@ impl From<&TableSchema> for TableSchema {
         value.clone()
     }
 }
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FieldType {
     String,
@@ -41,7 +42,7 @@ pub enum FieldType {
     Array,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub struct TableSchemaField {
     #[doc = "A name for this field."]
@@ -55,12 +56,24 @@ pub struct TableSchemaField {
     pub constraints: Option<Constraints>,
 }
 
+impl TableSchemaField {
+    /// Whether every row has a value for this field. Fields without a
+    /// `required` constraint are treated as nullable.
+    pub fn is_required(&self) -> bool {
+        self.constraints
+            .as_ref()
+            .and_then(|c| c.required)
+            .unwrap_or(false)
+    }
+}
+
 impl From<&TableSchemaField> for TableSchemaField {
     fn from(value: &TableSchemaField) -> Self {
         value.clone()
     }
 }
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[doc = "Field or fields that uniquely identify each row."]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum TableSchemaObjectPrimaryKey {
     Variant0(Vec<String>),
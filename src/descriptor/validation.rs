@@ -0,0 +1,182 @@
+//! Checks of a spec's consistency, beyond what deserializing it checks.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::Serialize;
+use serde_path_to_error::{Path, Segment};
+
+use super::{Dataset, Name, TableSchemaObjectPrimaryKey};
+use crate::codegen::table_identifiers;
+
+/// A problem with a spec, located by a JSON pointer (RFC 6901) into it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Problem {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "(root)"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+/// Returns the JSON pointer to the value at a deserializer's `path`.
+pub fn json_pointer(path: &Path) -> String {
+    path.iter()
+        .filter_map(|segment| match segment {
+            Segment::Seq { index } => Some(index.to_string()),
+            Segment::Map { key } => Some(key.replace('~', "~0").replace('/', "~1")),
+            Segment::Enum { .. } | Segment::Unknown => None,
+        })
+        .fold(String::new(), |pointer, token| pointer + "/" + &token)
+}
+
+/// Returns every problem found in the dataset: an invalid name, duplicate
+/// table or field names, bad primary keys, tables from different sources, and
+/// table names that coincide once generators make identifiers of them.
+pub fn validate(dataset: &Dataset) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut problem =
+        |pointer: String, message: String| problems.push(Problem { pointer, message });
+
+    if let Err(e) = dataset.name.parse::<Name>() {
+        problem("/name".into(), format!("invalid name: {e}"));
+    }
+
+    let mut table_indexes: HashMap<&str, usize> = HashMap::new();
+    let mut identifier_indexes: HashMap<String, usize> = HashMap::new();
+    for (i, table) in dataset.tables.iter().enumerate() {
+        let table_pointer = format!("/dataset/{i}");
+
+        if let Some(j) = table_indexes.insert(&table.name, i) {
+            problem(
+                format!("{table_pointer}/name"),
+                format!(
+                    "duplicate table name \"{}\"; also used by /dataset/{j}",
+                    table.name
+                ),
+            );
+        } else {
+            let identifiers = table_identifiers(&table.name);
+            if identifiers[0].is_empty() {
+                problem(
+                    format!("{table_pointer}/name"),
+                    format!(
+                        "table name \"{}\" has no characters usable in generated code",
+                        table.name
+                    ),
+                );
+            }
+            for identifier in identifiers.into_iter().filter(|id| !id.is_empty()) {
+                match identifier_indexes.insert(identifier.clone(), i) {
+                    Some(j) if j != i => {
+                        problem(
+                            format!("{table_pointer}/name"),
+                            format!(
+                                "table name \"{}\" collides with \"{}\" (/dataset/{j}) in generated code, as \"{}\"",
+                                table.name, dataset.tables[j].name, identifier
+                            ),
+                        );
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if i > 0 && table.source.id != dataset.tables[0].source.id {
+            problem(
+                format!("{table_pointer}/source/id"),
+                format!(
+                    "table's source {} differs from that of /dataset/0, {}; all tables must come from one source",
+                    table.source.id, dataset.tables[0].source.id
+                ),
+            );
+        }
+
+        let fields = &table.schema.fields;
+        let mut field_indexes: HashMap<&str, usize> = HashMap::new();
+        for (k, field) in fields.iter().enumerate() {
+            if let Some(l) = field_indexes.insert(&field.name, k) {
+                problem(
+                    format!("{table_pointer}/schema/fields/{k}/name"),
+                    format!(
+                        "duplicate field name \"{}\"; also used by {table_pointer}/schema/fields/{l}",
+                        field.name
+                    ),
+                );
+            }
+        }
+
+        let key_pointer = format!("{table_pointer}/schema/primaryKey");
+        let key: Vec<(String, &str)> = match &table.schema.primary_key {
+            None => vec![],
+            Some(TableSchemaObjectPrimaryKey::Variant0(names)) => {
+                if names.is_empty() {
+                    problem(key_pointer.clone(), "primary key is empty".into());
+                }
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(n, name)| (format!("{key_pointer}/{n}"), name.as_str()))
+                    .collect()
+            }
+            Some(TableSchemaObjectPrimaryKey::Variant1(name)) => {
+                if name.is_empty() {
+                    problem(key_pointer.clone(), "primary key is empty".into());
+                    vec![]
+                } else {
+                    vec![(key_pointer.clone(), name.as_str())]
+                }
+            }
+        };
+        for (pointer, name) in key {
+            if !field_indexes.contains_key(name) {
+                problem(
+                    pointer,
+                    format!("primary key field \"{name}\" is not one of the table's fields"),
+                );
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_works() {
+        let mut dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        assert_eq!(validate(&dataset), vec![]);
+
+        let mut table = dataset.tables[0].clone();
+        table.name = "uk real-estate records".into();
+        table.source.id = uuid::Uuid::nil();
+        table.schema.fields.push(table.schema.fields[0].clone());
+        table.schema.primary_key = Some(vec!["NOPE".to_string()].into());
+        dataset.tables.push(table);
+
+        assert_eq!(
+            validate(&dataset)
+                .into_iter()
+                .map(|p| p.pointer)
+                .collect::<Vec<_>>(),
+            vec![
+                "/dataset/1/name",
+                "/dataset/1/source/id",
+                "/dataset/1/schema/fields/11/name",
+                "/dataset/1/schema/primaryKey/0",
+            ]
+        );
+    }
+}
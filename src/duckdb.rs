@@ -0,0 +1,321 @@
+//! Local sources: DuckDB databases and directories of Parquet files, whose
+//! tables are discovered with the DuckDB CLI rather than by DPM Cloud.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+use uuid::Uuid;
+
+use crate::api::{FieldSchema, GetSourceMetadataResponse, TableMetadata, TableSchema};
+use crate::descriptor::TableSource;
+use crate::util::SourcePath;
+
+/// Source ID of tables in local sources, which DPM Cloud doesn't know of.
+pub const LOCAL_SOURCE_ID: Uuid = Uuid::nil();
+
+/// Schema that tables read from Parquet files are placed in, as DuckDB does
+/// for tables created without one.
+const PARQUET_SCHEMA: &str = "main";
+
+/// A local source, named on the command line as "duckdb:PATH" for a DuckDB
+/// database or "parquet:PATH" for a directory of Parquet files.
+#[derive(Debug, PartialEq)]
+pub enum LocalSource {
+    DuckDb(PathBuf),
+    Parquet(PathBuf),
+}
+
+impl LocalSource {
+    /// Parses `source` as a local source, or returns `None` if it names a DPM
+    /// Cloud source.
+    pub fn parse(source: &str) -> Option<Self> {
+        if let Some(path) = source.strip_prefix("duckdb:") {
+            Some(LocalSource::DuckDb(path.into()))
+        } else {
+            source
+                .strip_prefix("parquet:")
+                .map(|path| LocalSource::Parquet(path.into()))
+        }
+    }
+
+    /// Returns the local source that a spec at `spec` refers to as
+    /// `database`, which is relative to the spec's directory unless absolute.
+    pub fn in_spec(database: &str, spec: &Path) -> Self {
+        let path = spec_dir(spec).join(database);
+        if path.is_dir() {
+            LocalSource::Parquet(path)
+        } else {
+            LocalSource::DuckDb(path)
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            LocalSource::DuckDb(path) | LocalSource::Parquet(path) => path,
+        }
+    }
+
+    /// Returns how a spec at `spec` refers to this source: by its path
+    /// relative to the spec's directory if it's within it, and by its absolute
+    /// path otherwise.
+    pub fn database_in_spec(&self, spec: &Path) -> Result<String> {
+        let path = fs::canonicalize(self.path())
+            .with_context(|| format!("failed to read {}", self.path().display()))?;
+        let dir = fs::canonicalize(spec_dir(spec))?;
+        let path = path.strip_prefix(&dir).unwrap_or(&path);
+        Ok(path.to_string_lossy().into_owned())
+    }
+}
+
+/// Returns the directory of the spec at `spec`.
+fn spec_dir(spec: &Path) -> &Path {
+    match spec.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// A column of a table, as described by DuckDB.
+#[derive(Deserialize)]
+struct Column {
+    table_schema: String,
+    table_name: String,
+    column_name: String,
+    data_type: String,
+    is_nullable: String,
+}
+
+/// Returns the tables of `source` in the form DPM Cloud describes those of
+/// other sources, with `database` as the source path's database. In a
+/// directory of Parquet files, each file, and each subdirectory of files, is a
+/// table.
+pub fn source_metadata(source: &LocalSource, database: &str) -> Result<GetSourceMetadataResponse> {
+    let path = source.path();
+    let columns = match source {
+        LocalSource::Parquet(_) if !path.is_dir() => {
+            bail!("{} is not a directory of Parquet files", path.display())
+        }
+        LocalSource::Parquet(_) => parquet_columns(path)?,
+        LocalSource::DuckDb(_) if !path.is_file() => {
+            bail!("{} is not a DuckDB database", path.display())
+        }
+        LocalSource::DuckDb(_) => query(
+            Some(path),
+            "SELECT table_schema, table_name, column_name, data_type, is_nullable
+             FROM information_schema.columns
+             WHERE table_catalog = current_database()
+             ORDER BY table_schema, table_name, ordinal_position",
+        )
+        .with_context(|| format!("failed to describe the tables in {}", path.display()))?,
+    };
+
+    let mut metadata: Vec<TableMetadata> = Vec::new();
+    for column in columns {
+        let source_path = SourcePath::DuckDb {
+            database: database.to_owned(),
+            schema: column.table_schema,
+            table: column.table_name,
+        };
+        let field = FieldSchema {
+            dpm_beta_type: dpm_type(&column.data_type).map(String::from),
+            name: column.column_name,
+            nullable: column.is_nullable == "YES",
+            source_type: column.data_type,
+        };
+        match metadata.last_mut() {
+            Some(table) if table.source.path == source_path => table.schema.fields.push(field),
+            _ => metadata.push(TableMetadata {
+                schema: TableSchema {
+                    fields: vec![field],
+                },
+                source: TableSource {
+                    id: LOCAL_SOURCE_ID,
+                    path: source_path,
+                },
+            }),
+        }
+    }
+    Ok(GetSourceMetadataResponse { metadata })
+}
+
+/// Returns the columns of the tables in the directory of Parquet files at
+/// `path`.
+fn parquet_columns(path: &Path) -> Result<Vec<Column>> {
+    /// A column of a query's results, as described by DuckDB.
+    #[derive(Deserialize)]
+    struct ResultColumn {
+        column_name: String,
+        column_type: String,
+        null: String,
+    }
+
+    let mut entries = fs::read_dir(path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let mut columns = Vec::new();
+    for entry in entries {
+        let entry_path = entry.path();
+        let (table, files) = if entry_path.is_dir() {
+            (entry.file_name(), entry_path.join("**").join("*.parquet"))
+        } else if entry_path.extension().map_or(false, |e| e == "parquet") {
+            (entry_path.file_stem().unwrap().to_owned(), entry_path)
+        } else {
+            continue;
+        };
+        let table = table.to_string_lossy().into_owned();
+        let files = files.to_string_lossy().replace('\'', "''");
+
+        let result_columns: Vec<ResultColumn> = query(
+            None,
+            &format!("DESCRIBE SELECT * FROM read_parquet('{files}')"),
+        )
+        .with_context(|| format!("failed to describe the Parquet files of {table}"))?;
+        columns.extend(result_columns.into_iter().map(|c| Column {
+            table_schema: PARQUET_SCHEMA.into(),
+            table_name: table.clone(),
+            column_name: c.column_name,
+            data_type: c.column_type,
+            is_nullable: c.null,
+        }));
+    }
+    Ok(columns)
+}
+
+/// Executes `sql` with the DuckDB CLI, on the database at `database` or in
+/// memory, and returns the resulting rows.
+fn query<T: DeserializeOwned>(database: Option<&Path>, sql: &str) -> Result<Vec<T>> {
+    let mut command = Command::new("duckdb");
+    command.arg("-json");
+    if let Some(database) = database {
+        command.arg("-readonly").arg(database);
+    } else {
+        command.arg(":memory:");
+    }
+    let output = command.arg(sql).output().context(
+        "failed to run duckdb (tip: Install the DuckDB CLI, https://duckdb.org/docs/installation/)",
+    )?;
+    if !output.status.success() {
+        bail!(
+            "duckdb exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8(output.stdout)?;
+    if stdout.trim().is_empty() {
+        // DuckDB prints nothing, rather than an empty array, for no rows.
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&stdout)?)
+}
+
+/// Returns the dpm type of values of the DuckDB type `data_type`, as DPM Cloud
+/// reports it for sources' columns, or `None` if the type isn't supported.
+fn dpm_type(data_type: &str) -> Option<&'static str> {
+    let data_type = data_type.trim().to_uppercase();
+    if data_type.ends_with(']') {
+        // Lists, e.g. "INTEGER[]", and fixed-size arrays, e.g. "INTEGER[3]".
+        return Some("array");
+    }
+    let name = data_type.split('(').next().unwrap_or_default().trim();
+    match name {
+        "VARCHAR" | "CHAR" | "BPCHAR" | "TEXT" | "STRING" | "UUID" | "ENUM" => Some("string"),
+        "BOOLEAN" | "BOOL" => Some("boolean"),
+        "TINYINT" | "SMALLINT" | "INTEGER" | "INT" | "BIGINT" | "HUGEINT" | "UTINYINT"
+        | "USMALLINT" | "UINTEGER" | "UBIGINT" | "UHUGEINT" | "FLOAT" | "REAL" | "DOUBLE"
+        | "DECIMAL" | "NUMERIC" => Some("number"),
+        "DATE" => Some("date"),
+        "TIME" | "TIME WITH TIME ZONE" => Some("time"),
+        "TIMESTAMP"
+        | "TIMESTAMP WITH TIME ZONE"
+        | "TIMESTAMPTZ"
+        | "TIMESTAMP_S"
+        | "TIMESTAMP_MS"
+        | "TIMESTAMP_NS"
+        | "DATETIME" => Some("datetime"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptor::TableSchemaField;
+
+    #[test]
+    fn local_source_works() {
+        assert_eq!(
+            LocalSource::parse("duckdb:data/shop.duckdb"),
+            Some(LocalSource::DuckDb("data/shop.duckdb".into()))
+        );
+        assert_eq!(
+            LocalSource::parse("parquet:/data/shop"),
+            Some(LocalSource::Parquet("/data/shop".into()))
+        );
+        assert_eq!(LocalSource::parse("orders"), None);
+
+        assert_eq!(
+            LocalSource::in_spec("shop.duckdb", Path::new("specs/dataset.json")),
+            LocalSource::DuckDb("specs/shop.duckdb".into())
+        );
+        assert_eq!(
+            LocalSource::in_spec("/data/shop.duckdb", Path::new("dataset.json")),
+            LocalSource::DuckDb("/data/shop.duckdb".into())
+        );
+        assert_eq!(
+            LocalSource::in_spec("src", Path::new("dataset.json")),
+            LocalSource::Parquet("./src".into())
+        );
+
+        let source = LocalSource::DuckDb("src/lib.rs".into());
+        assert_eq!(
+            source.database_in_spec(Path::new("dataset.json")).unwrap(),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            source
+                .database_in_spec(Path::new("src/dataset.json"))
+                .unwrap(),
+            "lib.rs"
+        );
+        let outside = source
+            .database_in_spec(Path::new("tests/dataset.json"))
+            .unwrap();
+        assert!(Path::new(&outside).is_absolute(), "{outside}");
+    }
+
+    #[test]
+    fn dpm_type_works() {
+        for (data_type, expected) in [
+            ("VARCHAR", Some("string")),
+            ("DECIMAL(18,3)", Some("number")),
+            ("BIGINT", Some("number")),
+            ("boolean", Some("boolean")),
+            ("DATE", Some("date")),
+            ("TIME", Some("time")),
+            ("TIMESTAMP WITH TIME ZONE", Some("datetime")),
+            ("INTEGER[]", Some("array")),
+            ("STRUCT(a INTEGER)[]", Some("array")),
+            ("STRUCT(a INTEGER)", None),
+            ("BLOB", None),
+        ] {
+            assert_eq!(dpm_type(data_type), expected, "{data_type}");
+            if let Some(dpm_type) = expected {
+                let field: Result<TableSchemaField, _> = FieldSchema {
+                    name: "F".into(),
+                    dpm_beta_type: Some(dpm_type.into()),
+                    nullable: true,
+                    source_type: data_type.into(),
+                }
+                .try_into();
+                assert!(field.is_ok(), "{data_type}");
+            }
+        }
+    }
+}
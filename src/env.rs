@@ -22,6 +22,16 @@ pub fn api_base_url() -> Result<Url> {
     Url::parse(&s).map_err(Into::into)
 }
 
+/// Returns the address of the `dpm-agent` to send queries to.
+pub fn dpm_agent_url() -> Result<Url> {
+    let s = match env::var("DPM_AGENT_URL") {
+        Ok(v) => v,
+        Err(VarError::NotPresent) => "https://agent.dpm.sh".into(),
+        Err(VarError::NotUnicode(_)) => bail!("DPM_AGENT_URL is not Unicode"),
+    };
+    Url::parse(&s).map_err(Into::into)
+}
+
 /// Returns the path to the CLI's configuration directory, which surely exists.
 pub fn ensure_config_dir() -> Result<PathBuf> {
     let project_dir =
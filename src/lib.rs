@@ -1,9 +1,12 @@
+mod agent;
 mod api;
 mod codegen;
 mod command;
 mod descriptor;
+mod duckdb;
 mod env;
 mod github;
+mod query;
 mod session;
 mod util;
 
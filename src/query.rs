@@ -0,0 +1,1025 @@
+//! A compact expression language for building `dpm-agent` queries.
+//!
+//! Expressions look like a small subset of SQL:
+//!
+//! ```text
+//! STATE, year(CREATED_AT) as year, sum(PRICE) as total
+//! STATE in ('CA', 'NY') and (PRICE >= 10.5 or NOTES is not null)
+//! total desc
+//! ```
+//!
+//! Field names are bare identifiers, optionally qualified by a table name
+//! (`orders.ID`), or double-quoted when they contain other characters. String
+//! literals are single-quoted, with `''` standing for a quote. Keywords and
+//! function names are case-insensitive.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::agent::proto::query::{
+    aggregate_expression::AggregateOperator,
+    boolean_expression::BooleanOperator,
+    derived_expression::ProjectionOperator,
+    expression::ExType,
+    group_by_expression,
+    join_expression::JoinType,
+    literal::{self, LiteralType},
+    order_by_expression::Direction,
+    AggregateExpression, BooleanExpression, DerivedExpression, Expression, FieldReference,
+    GroupByExpression, Id, JoinExpression, Literal, OrderByExpression, SelectExpression,
+};
+use crate::agent::{self, proto::Query};
+use crate::api::GetDatasetVersionResponse;
+use crate::descriptor::Table;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    String(String),
+    Int(i64),
+    Float(f64),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 12] = [
+    "<=", ">=", "!=", "<>", "(", ")", ",", ".", "=", "<", ">", "*",
+];
+
+/// Words that can only be used as names when double-quoted.
+pub const KEYWORDS: [&str; 28] = [
+    "all", "and", "any", "as", "asc", "between", "by", "desc", "from", "full", "group", "has",
+    "in", "inner", "is", "join", "left", "like", "limit", "not", "null", "on", "or", "order",
+    "outer", "right", "select", "where",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, ch)) if ch == c => {
+                        // A doubled quote stands for the quote itself.
+                        if chars.peek().map(|&(_, next)| next) == Some(c) {
+                            chars.next();
+                            s.push(c);
+                        } else {
+                            break;
+                        }
+                    }
+                    Some((_, ch)) => s.push(ch),
+                    None => bail!("unterminated quote starting at offset {start}"),
+                }
+            }
+            tokens.push(if c == '\'' {
+                Token::String(s)
+            } else {
+                Token::QuotedIdent(s)
+            });
+        } else if c.is_ascii_digit()
+            || (c == '-' && input[start + 1..].starts_with(|n: char| n.is_ascii_digit()))
+        {
+            chars.next();
+            let mut end = start + 1;
+            while let Some(&(i, ch)) = chars.peek() {
+                if !(ch.is_ascii_digit() || ch == '.' || ch == 'e' || ch == 'E') {
+                    break;
+                }
+                chars.next();
+                end = i + ch.len_utf8();
+            }
+            let text = &input[start..end];
+            tokens.push(match text.parse::<i64>() {
+                Ok(n) => Token::Int(n),
+                Err(_) => Token::Float(
+                    text.parse()
+                        .with_context(|| format!("invalid number: {text}"))?,
+                ),
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, ch)) = chars.peek() {
+                if !(ch.is_alphanumeric() || ch == '_') {
+                    break;
+                }
+                chars.next();
+                end = i + ch.len_utf8();
+            }
+            tokens.push(Token::Ident(input[start..end].into()));
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| input[start..].starts_with(*s)) {
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push(Token::Symbol(symbol));
+        } else {
+            bail!("unexpected character {c:?} at offset {start}");
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Names of the functions that project or aggregate their argument.
+pub const FUNCTIONS: [&str; 22] = [
+    "year",
+    "month",
+    "day",
+    "hour",
+    "minute",
+    "second",
+    "millisecond",
+    "date",
+    "time",
+    "week",
+    "day_of_week",
+    "date_of_week",
+    "min",
+    "max",
+    "avg",
+    "avg_distinct",
+    "mean",
+    "mean_distinct",
+    "median",
+    "count",
+    "count_distinct",
+    "sum",
+];
+
+fn projection_operator(name: &str) -> Option<ProjectionOperator> {
+    Some(match name {
+        "year" => ProjectionOperator::Year,
+        "month" => ProjectionOperator::Month,
+        "day" => ProjectionOperator::Day,
+        "hour" => ProjectionOperator::Hour,
+        "minute" => ProjectionOperator::Minute,
+        "second" => ProjectionOperator::Second,
+        "millisecond" => ProjectionOperator::Millisecond,
+        "date" => ProjectionOperator::Date,
+        "time" => ProjectionOperator::Time,
+        "week" => ProjectionOperator::Week,
+        "day_of_week" => ProjectionOperator::DayOfWeek,
+        "date_of_week" => ProjectionOperator::DateOfWeek,
+        _ => return None,
+    })
+}
+
+fn aggregate_operator(name: &str) -> Option<AggregateOperator> {
+    Some(match name {
+        "min" => AggregateOperator::Min,
+        "max" => AggregateOperator::Max,
+        "avg" | "mean" => AggregateOperator::Mean,
+        "avg_distinct" | "mean_distinct" => AggregateOperator::MeanDistinct,
+        "median" => AggregateOperator::Median,
+        "count" => AggregateOperator::Count,
+        "count_distinct" => AggregateOperator::CountDistinct,
+        "sum" => AggregateOperator::Sum,
+        _ => return None,
+    })
+}
+
+fn boolean(op: BooleanOperator, arguments: Vec<Expression>) -> Expression {
+    Expression {
+        ex_type: Some(ExType::Condition(BooleanExpression {
+            op: op.into(),
+            arguments,
+        })),
+    }
+}
+
+fn literal(literal_type: LiteralType) -> Expression {
+    Expression {
+        ex_type: Some(ExType::Literal(Literal {
+            literal_type: Some(literal_type),
+        })),
+    }
+}
+
+/// A recursive descent parser over the tokens of one input string.
+pub struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    pub fn new(input: &str) -> Result<Self> {
+        Ok(Self {
+            tokens: tokenize(input)?,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Fails unless all input has been consumed.
+    pub fn expect_end(&self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => bail!("unexpected {} after expression", describe(token)),
+        }
+    }
+
+    /// Consumes the next token if it is the (case-insensitive) keyword `kw`.
+    pub fn eat_keyword(&mut self, kw: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case(kw) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            bail!("expected \"{symbol}\", found {}", self.describe_next())
+        }
+    }
+
+    fn describe_next(&self) -> String {
+        self.peek()
+            .map(describe)
+            .unwrap_or_else(|| "end of input".into())
+    }
+
+    /// Parses a name: an identifier that isn't a keyword, or a double-quoted
+    /// string.
+    pub fn name(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Ident(s)) if KEYWORDS.contains(&s.to_lowercase().as_str()) => {
+                self.pos -= 1;
+                bail!("expected a name, found keyword \"{s}\"; double-quote it to use it as a name")
+            }
+            Some(Token::Ident(s)) | Some(Token::QuotedIdent(s)) => Ok(s),
+            other => {
+                self.pos -= 1;
+                bail!(
+                    "expected a name, found {}",
+                    other
+                        .as_ref()
+                        .map(describe)
+                        .unwrap_or("end of input".into())
+                )
+            }
+        }
+    }
+
+    /// Parses comma-separated items, each parsed by `item`.
+    pub fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.eat_symbol(",") {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Parses an expression, which may be a condition.
+    pub fn expression(&mut self) -> Result<Expression> {
+        let mut arguments = vec![self.conjunction()?];
+        while self.eat_keyword("or") {
+            arguments.push(self.conjunction()?);
+        }
+        Ok(if arguments.len() == 1 {
+            arguments.pop().unwrap()
+        } else {
+            boolean(BooleanOperator::Or, arguments)
+        })
+    }
+
+    fn conjunction(&mut self) -> Result<Expression> {
+        let mut arguments = vec![self.comparison()?];
+        while self.eat_keyword("and") {
+            arguments.push(self.comparison()?);
+        }
+        Ok(if arguments.len() == 1 {
+            arguments.pop().unwrap()
+        } else {
+            boolean(BooleanOperator::And, arguments)
+        })
+    }
+
+    fn comparison(&mut self) -> Result<Expression> {
+        let lhs = self.operand()?;
+
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => BooleanOperator::Eq,
+            Some(Token::Symbol("!=")) | Some(Token::Symbol("<>")) => BooleanOperator::Neq,
+            Some(Token::Symbol("<")) => BooleanOperator::Lt,
+            Some(Token::Symbol("<=")) => BooleanOperator::Lte,
+            Some(Token::Symbol(">")) => BooleanOperator::Gt,
+            Some(Token::Symbol(">=")) => BooleanOperator::Gte,
+            _ => {
+                if self.eat_keyword("like") {
+                    let rhs = self.operand()?;
+                    return Ok(boolean(BooleanOperator::Like, vec![lhs, rhs]));
+                } else if self.eat_keyword("in") {
+                    let list = self.literal_list()?;
+                    return Ok(boolean(BooleanOperator::In, vec![lhs, list]));
+                } else if self.eat_keyword("between") {
+                    let low = self.operand()?;
+                    if !self.eat_keyword("and") {
+                        bail!("expected \"and\", found {}", self.describe_next());
+                    }
+                    let high = self.operand()?;
+                    return Ok(boolean(BooleanOperator::Between, vec![lhs, low, high]));
+                } else if self.eat_keyword("has") {
+                    let op = if self.eat_keyword("any") {
+                        BooleanOperator::HasAny
+                    } else if self.eat_keyword("all") {
+                        BooleanOperator::HasAll
+                    } else {
+                        bail!(
+                            "expected \"any\" or \"all\", found {}",
+                            self.describe_next()
+                        );
+                    };
+                    let list = self.literal_list()?;
+                    return Ok(boolean(op, vec![lhs, list]));
+                } else if self.eat_keyword("is") {
+                    let op = if self.eat_keyword("not") {
+                        BooleanOperator::IsNotNull
+                    } else {
+                        BooleanOperator::IsNull
+                    };
+                    if !self.eat_keyword("null") {
+                        bail!("expected \"null\", found {}", self.describe_next());
+                    }
+                    return Ok(boolean(op, vec![lhs]));
+                }
+                return Ok(lhs);
+            }
+        };
+        self.pos += 1;
+        let rhs = self.operand()?;
+        Ok(boolean(op, vec![lhs, rhs]))
+    }
+
+    fn literal_list(&mut self) -> Result<Expression> {
+        self.expect_symbol("(")?;
+        let values = self.list(|p| match p.operand()?.ex_type {
+            Some(ExType::Literal(l)) => Ok(l),
+            _ => bail!("expected a literal in list"),
+        })?;
+        self.expect_symbol(")")?;
+        Ok(literal(LiteralType::List(literal::List { values })))
+    }
+
+    fn operand(&mut self) -> Result<Expression> {
+        match self.next() {
+            Some(Token::Symbol("(")) => {
+                let expr = self.expression()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Some(Token::String(s)) => Ok(literal(LiteralType::String(s))),
+            Some(Token::Int(n)) => Ok(literal(LiteralType::I64(n))),
+            Some(Token::Float(x)) => Ok(literal(LiteralType::F64(x))),
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("true") => {
+                Ok(literal(LiteralType::Boolean(true)))
+            }
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("false") => {
+                Ok(literal(LiteralType::Boolean(false)))
+            }
+            Some(Token::Ident(s)) if self.peek() == Some(&Token::Symbol("(")) => {
+                self.pos += 1;
+                let argument = Some(Box::new(self.expression()?));
+                self.expect_symbol(")")?;
+                let function = s.to_lowercase();
+                let ex_type = if let Some(op) = projection_operator(&function) {
+                    ExType::Derived(Box::new(DerivedExpression {
+                        op: op.into(),
+                        argument,
+                    }))
+                } else if let Some(op) = aggregate_operator(&function) {
+                    ExType::Aggregate(Box::new(AggregateExpression {
+                        op: op.into(),
+                        argument,
+                    }))
+                } else {
+                    bail!("unknown function: {s}");
+                };
+                Ok(Expression {
+                    ex_type: Some(ex_type),
+                })
+            }
+            Some(Token::Ident(_)) | Some(Token::QuotedIdent(_)) => {
+                self.pos -= 1;
+                Ok(Expression {
+                    ex_type: Some(ExType::Field(self.field()?)),
+                })
+            }
+            other => {
+                self.pos -= 1;
+                bail!(
+                    "expected an expression, found {}",
+                    other
+                        .as_ref()
+                        .map(describe)
+                        .unwrap_or("end of input".into())
+                )
+            }
+        }
+    }
+
+    /// Parses a field reference, optionally qualified by a table name.
+    pub fn field(&mut self) -> Result<FieldReference> {
+        let name = self.name()?;
+        if self.eat_symbol(".") {
+            Ok(FieldReference {
+                field_name: self.name()?,
+                table_name: Some(name),
+            })
+        } else {
+            Ok(FieldReference {
+                field_name: name,
+                table_name: None,
+            })
+        }
+    }
+
+    /// Parses a selection: an expression with an optional `as` alias.
+    pub fn select(&mut self) -> Result<SelectExpression> {
+        let argument = self.expression()?;
+        let alias = if self.eat_keyword("as") {
+            Some(self.name()?)
+        } else {
+            None
+        };
+        Ok(SelectExpression {
+            argument: Some(argument),
+            alias,
+        })
+    }
+
+    /// Parses a filter, which must be a condition.
+    pub fn filter(&mut self) -> Result<BooleanExpression> {
+        match self.expression()?.ex_type {
+            Some(ExType::Condition(condition)) => Ok(condition),
+            _ => bail!("filter is not a condition"),
+        }
+    }
+
+    /// Parses a grouping: a field, or a derived expression.
+    pub fn group_by(&mut self) -> Result<GroupByExpression> {
+        to_group_by(self.expression()?)
+    }
+
+    /// Parses an ordering: an expression with an optional `asc` or `desc`.
+    pub fn order_by(&mut self) -> Result<OrderByExpression> {
+        let argument = self.expression()?;
+        let direction = if self.eat_keyword("desc") {
+            Some(Direction::Desc.into())
+        } else if self.eat_keyword("asc") {
+            Some(Direction::Asc.into())
+        } else {
+            None
+        };
+        Ok(OrderByExpression {
+            argument: Some(argument),
+            direction,
+        })
+    }
+
+    fn expect_keyword(&mut self, kw: &str) -> Result<()> {
+        if self.eat_keyword(kw) {
+            Ok(())
+        } else {
+            bail!("expected \"{kw}\", found {}", self.describe_next())
+        }
+    }
+
+    /// Parses a table name with an optional `as` alias.
+    fn table(&mut self) -> Result<(String, Option<String>)> {
+        let name = self.name()?;
+        let alias = if self.eat_keyword("as") {
+            Some(self.name()?)
+        } else {
+            None
+        };
+        Ok((name, alias))
+    }
+
+    fn join(&mut self) -> Result<Option<JoinExpression>> {
+        let join_type = if self.eat_keyword("join") {
+            JoinType::InnerJoin
+        } else {
+            let join_type = if self.eat_keyword("inner") {
+                JoinType::InnerJoin
+            } else if self.eat_keyword("left") {
+                JoinType::LeftJoin
+            } else if self.eat_keyword("right") {
+                JoinType::RightJoin
+            } else if self.eat_keyword("full") {
+                self.eat_keyword("outer");
+                JoinType::FullOuterJoin
+            } else {
+                return Ok(None);
+            };
+            self.expect_keyword("join")?;
+            join_type
+        };
+        let (join_table, table_alias) = self.table()?;
+        self.expect_keyword("on")?;
+        let join_condition = Some(self.filter()?);
+        Ok(Some(JoinExpression {
+            join_type: join_type.into(),
+            join_table,
+            join_condition,
+            table_alias,
+        }))
+    }
+
+    /// Parses a statement of the form:
+    ///
+    /// ```text
+    /// [select EXPRS|*] from TABLE [as ALIAS] [JOIN ...] [where COND]
+    ///   [group by EXPRS] [order by EXPRS] [limit N]
+    /// ```
+    ///
+    /// where each join is `[inner|left|right|full [outer]] join TABLE [as
+    /// ALIAS] on COND`. The query it returns has yet to be completed.
+    pub fn statement(&mut self) -> Result<Query> {
+        let mut query = Query::default();
+        if self.eat_keyword("select") && !self.eat_symbol("*") {
+            query.select = self.list(Parser::select)?;
+        }
+        self.expect_keyword("from")?;
+        (query.select_from, query.table_alias) = self.table()?;
+        while let Some(join) = self.join()? {
+            query.joins.push(join);
+        }
+        if self.eat_keyword("where") {
+            query.filter = Some(self.filter()?);
+        }
+        if self.eat_keyword("group") {
+            self.expect_keyword("by")?;
+            query.group_by = self.list(Parser::group_by)?;
+        }
+        if self.eat_keyword("order") {
+            self.expect_keyword("by")?;
+            query.order_by = self.list(Parser::order_by)?;
+        }
+        if self.eat_keyword("limit") {
+            match self.next() {
+                Some(Token::Int(n)) if n >= 0 => query.limit = Some(n as u64),
+                _ => bail!("expected a row count after \"limit\""),
+            }
+        }
+        self.expect_end()?;
+        Ok(query)
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(s) => format!("\"{s}\""),
+        Token::QuotedIdent(s) => format!("\"\\\"{s}\\\"\""),
+        Token::String(s) => format!("'{s}'"),
+        Token::Int(n) => n.to_string(),
+        Token::Float(x) => x.to_string(),
+        Token::Symbol(s) => format!("\"{s}\""),
+    }
+}
+
+/// Returns `name` as it must be written in an expression: double-quoted,
+/// unless it is an identifier other than a keyword.
+pub fn quote_name(name: &str) -> String {
+    let is_identifier = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if is_identifier && !KEYWORDS.contains(&name.to_lowercase().as_str()) {
+        name.into()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// Parses all of `input` with `parse`, adding `input` to any error.
+fn parse_all<T>(input: &str, parse: impl FnOnce(&mut Parser) -> Result<T>) -> Result<T> {
+    let mut parser = Parser::new(input)?;
+    parse(&mut parser)
+        .and_then(|v| parser.expect_end().map(|_| v))
+        .with_context(|| format!("failed to parse {input:?}"))
+}
+
+/// Parses each of `inputs` as a list of items, and concatenates the lists.
+fn parse_lists<T: Clone>(
+    inputs: &[String],
+    item: impl Fn(&mut Parser) -> Result<T> + Copy,
+) -> Result<Vec<T>> {
+    inputs
+        .iter()
+        .map(|s| parse_all(s, |p| p.list(item)))
+        .collect::<Result<Vec<_>>>()
+        .map(|lists| lists.concat())
+}
+
+fn to_group_by(expr: Expression) -> Result<GroupByExpression> {
+    Ok(GroupByExpression {
+        ex_type: Some(match expr.ex_type {
+            Some(ExType::Field(f)) => group_by_expression::ExType::Field(f),
+            Some(ExType::Derived(d)) => group_by_expression::ExType::Derived(*d),
+            _ => bail!("can only group by fields and derived expressions"),
+        }),
+    })
+}
+
+/// Whether the expression contains an aggregate.
+pub fn is_aggregate(expr: &Expression) -> bool {
+    match &expr.ex_type {
+        Some(ExType::Aggregate(_)) => true,
+        Some(ExType::Derived(d)) => d.argument.as_deref().map_or(false, is_aggregate),
+        Some(ExType::Condition(c)) => c.arguments.iter().any(is_aggregate),
+        _ => false,
+    }
+}
+
+/// Returns the field references in the expression.
+pub fn field_references(expr: &Expression) -> Vec<&FieldReference> {
+    match &expr.ex_type {
+        Some(ExType::Field(f)) => vec![f],
+        Some(ExType::Derived(d)) => d.argument.as_deref().map_or(vec![], field_references),
+        Some(ExType::Aggregate(a)) => a.argument.as_deref().map_or(vec![], field_references),
+        Some(ExType::Condition(c)) => c.arguments.iter().flat_map(field_references).collect(),
+        Some(ExType::Literal(_)) | None => vec![],
+    }
+}
+
+/// A query on one table of a dataset, written with the expression language.
+/// It may be read from a JSON file, in which case it looks like:
+///
+/// ```json
+/// {
+///   "table": "orders",
+///   "select": ["STATE", "sum(PRICE) as total"],
+///   "filter": "PRICE > 0",
+///   "orderBy": ["total desc"],
+///   "limit": 10
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QuerySpec {
+    /// Dataset identifier of the form "<dataset name>@<version>".
+    pub dataset: Option<String>,
+    pub table: Option<String>,
+    #[serde(default)]
+    pub select: Vec<String>,
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    #[serde(default)]
+    pub order_by: Vec<String>,
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl QuerySpec {
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(Into::into)
+    }
+
+    /// Compiles the spec into a query on `dataset`, checking that the table
+    /// and the fields it references exist.
+    pub fn compile(&self, dataset: &GetDatasetVersionResponse) -> Result<Query> {
+        let Some(table) = self.table.clone() else {
+            bail!("no table given");
+        };
+        let query = Query {
+            select_from: table,
+            select: parse_lists(&self.select, Parser::select)?,
+            filter: self
+                .filter
+                .as_deref()
+                .map(|s| parse_all(s, Parser::filter))
+                .transpose()?,
+            group_by: parse_lists(&self.group_by, Parser::group_by)?,
+            order_by: parse_lists(&self.order_by, Parser::order_by)?,
+            limit: self.limit,
+            dry_run: Some(self.dry_run),
+            ..Default::default()
+        };
+        complete(query, dataset)
+    }
+}
+
+/// Completes a parsed query on `dataset`: checks that the tables and fields it
+/// references exist, infers its grouping, and identifies the dataset and this
+/// client.
+pub fn complete(mut query: Query, dataset: &GetDatasetVersionResponse) -> Result<Query> {
+    // The tables in scope, by the name that qualifies their fields. The first
+    // is the one unqualified fields belong to.
+    let mut scope = vec![(
+        query.table_alias.as_ref().unwrap_or(&query.select_from),
+        find_table(dataset, &query.select_from)?,
+    )];
+    for join in &query.joins {
+        scope.push((
+            join.table_alias.as_ref().unwrap_or(&join.join_table),
+            find_table(dataset, &join.join_table)?,
+        ));
+    }
+
+    // Aggregating requires grouping by the remaining selections, so infer
+    // that grouping unless one was given.
+    let selections = || query.select.iter().filter_map(|s| s.argument.as_ref());
+    let group_by = if query.group_by.is_empty() && selections().any(is_aggregate) {
+        selections()
+            .filter(|e| !is_aggregate(e))
+            .map(|e| to_group_by(e.clone()))
+            .collect::<Result<_>>()?
+    } else {
+        query.group_by.clone()
+    };
+
+    let mut fields = selections()
+        .chain(query.filter.iter().flat_map(|f| &f.arguments))
+        .chain(
+            query
+                .joins
+                .iter()
+                .flat_map(|j| j.join_condition.iter().flat_map(|c| &c.arguments)),
+        )
+        .flat_map(field_references)
+        .collect::<Vec<_>>();
+    for g in &group_by {
+        match &g.ex_type {
+            Some(group_by_expression::ExType::Field(f)) => fields.push(f),
+            Some(group_by_expression::ExType::Derived(d)) => {
+                fields.extend(d.argument.as_deref().map_or(vec![], field_references))
+            }
+            None => (),
+        }
+    }
+    for field in fields {
+        check_field(&scope, field)?;
+    }
+    // Orderings may also name a selection's alias.
+    let aliases: Vec<&str> = query
+        .select
+        .iter()
+        .filter_map(|s| s.alias.as_deref())
+        .collect();
+    for field in query
+        .order_by
+        .iter()
+        .filter_map(|o| o.argument.as_ref())
+        .flat_map(field_references)
+    {
+        if field.table_name.is_some() || !aliases.contains(&field.field_name.as_str()) {
+            check_field(&scope, field)?;
+        }
+    }
+
+    query.group_by = group_by;
+    query.id = Some(Id {
+        id_type: Some(agent::proto::query::id::IdType::PackageId(
+            dataset.uuid.to_string(),
+        )),
+    });
+    query.client_version = Some(agent::client_version(&dataset.version.version.to_string()));
+    Ok(query)
+}
+
+/// Returns the table named `name` in `dataset`.
+pub fn find_table<'a>(dataset: &'a GetDatasetVersionResponse, name: &str) -> Result<&'a Table> {
+    match dataset.version.dataset.iter().find(|t| t.name == name) {
+        Some(table) => Ok(table),
+        None => bail!(
+            "table \"{}\" not found in {}@{}; its tables are: {}",
+            name,
+            dataset.name,
+            dataset.version.version,
+            dataset
+                .version
+                .dataset
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn check_field(scope: &[(&String, &Table)], field: &FieldReference) -> Result<()> {
+    let table = match &field.table_name {
+        Some(name) => match scope.iter().find(|(n, _)| *n == name) {
+            Some((_, table)) => table,
+            None => bail!("table \"{name}\" is not queried"),
+        },
+        None => scope[0].1,
+    };
+    if !table
+        .schema
+        .fields
+        .iter()
+        .any(|f| f.name == field.field_name)
+    {
+        bail!(
+            "field \"{}\" not found in table \"{}\"",
+            field.field_name,
+            table.name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{api::DatasetVersion, descriptor::Dataset};
+
+    fn field(name: &str) -> Expression {
+        Expression {
+            ex_type: Some(ExType::Field(FieldReference {
+                field_name: name.into(),
+                table_name: None,
+            })),
+        }
+    }
+
+    #[test]
+    fn tokenize_works() {
+        assert_eq!(
+            tokenize("a.\"b c\" <= -1.5 and 'it''s'").unwrap(),
+            vec![
+                Token::Ident("a".into()),
+                Token::Symbol("."),
+                Token::QuotedIdent("b c".into()),
+                Token::Symbol("<="),
+                Token::Float(-1.5),
+                Token::Ident("and".into()),
+                Token::String("it's".into()),
+            ]
+        );
+        assert!(tokenize("'open").is_err());
+        assert!(tokenize("a ; b").is_err());
+    }
+
+    #[test]
+    fn filter_works() {
+        let filter =
+            parse_all("A = 1 or B in ('x', 'y') and C is not null", Parser::filter).unwrap();
+        assert_eq!(
+            filter,
+            BooleanExpression {
+                op: BooleanOperator::Or.into(),
+                arguments: vec![
+                    boolean(
+                        BooleanOperator::Eq,
+                        vec![field("A"), literal(LiteralType::I64(1))]
+                    ),
+                    boolean(
+                        BooleanOperator::And,
+                        vec![
+                            boolean(
+                                BooleanOperator::In,
+                                vec![
+                                    field("B"),
+                                    literal(LiteralType::List(literal::List {
+                                        values: vec![
+                                            Literal {
+                                                literal_type: Some(LiteralType::String("x".into()))
+                                            },
+                                            Literal {
+                                                literal_type: Some(LiteralType::String("y".into()))
+                                            },
+                                        ]
+                                    }))
+                                ]
+                            ),
+                            boolean(BooleanOperator::IsNotNull, vec![field("C")]),
+                        ]
+                    ),
+                ]
+            }
+        );
+        assert!(parse_all("A", Parser::filter).is_err());
+        assert!(parse_all("A = 1 B", Parser::filter).is_err());
+    }
+
+    #[test]
+    fn select_and_order_by_work() {
+        let select = parse_all("A, Sum(B) as total", |p| p.list(Parser::select)).unwrap();
+        assert_eq!(select.len(), 2);
+        assert_eq!(select[0].argument, Some(field("A")));
+        assert_eq!(select[1].alias.as_deref(), Some("total"));
+        assert!(is_aggregate(select[1].argument.as_ref().unwrap()));
+
+        let order_by = parse_all("year(A), total desc", |p| p.list(Parser::order_by)).unwrap();
+        assert_eq!(order_by[0].direction, None);
+        assert_eq!(order_by[1].direction, Some(Direction::Desc.into()));
+
+        assert!(parse_all("frobnicate(A)", Parser::select).is_err());
+        for function in FUNCTIONS {
+            assert!(projection_operator(function)
+                .map(|_| ())
+                .or(aggregate_operator(function).map(|_| ()))
+                .is_some());
+        }
+        assert!(parse_all("sum(A)", Parser::group_by).is_err());
+    }
+
+    #[test]
+    fn compile_works() {
+        let dataset = Dataset::read("tests/resources/dataset.json").unwrap();
+        let res = GetDatasetVersionResponse {
+            name: dataset.name.to_string(),
+            uuid: Uuid::from_bytes(dataset.id.as_bytes().to_owned()),
+            description: dataset.description.unwrap_or("".into()),
+            version: DatasetVersion {
+                version: dataset.version,
+                accelerated: false,
+                dataset: dataset.tables,
+                patch_state: None,
+                patch_state_data: None,
+            },
+        };
+        let spec = |select: &str, order_by: &str| QuerySpec {
+            table: Some("UK_REAL_ESTATE_RECORDS".into()),
+            select: vec![select.into()],
+            order_by: vec![order_by.into()],
+            ..Default::default()
+        };
+
+        let query = spec(
+            "PROPERTY_TYPE, year(DATE_OF_TRANSFER), count(PRICE) as n",
+            "n desc",
+        )
+        .compile(&res)
+        .unwrap();
+        assert_eq!(query.select_from, "UK_REAL_ESTATE_RECORDS");
+        assert_eq!(
+            query.id.unwrap().id_type,
+            Some(agent::proto::query::id::IdType::PackageId(
+                res.uuid.to_string()
+            ))
+        );
+        // Grouping is inferred from the selections that aren't aggregates.
+        assert_eq!(query.group_by.len(), 2);
+
+        assert!(spec("NOPE", "PRICE").compile(&res).is_err());
+        assert!(spec("PRICE", "n").compile(&res).is_err());
+        assert!(QuerySpec::default().compile(&res).is_err());
+    }
+
+    #[test]
+    fn statement_works() {
+        let query = parse_all(
+            "select o.ID, count(i.ID) as n from ORDERS as o \
+             left join ITEMS as i on o.ID = i.ORDER_ID \
+             where o.STATE != 'CA' group by o.ID order by n desc limit 5",
+            Parser::statement,
+        )
+        .unwrap();
+        assert_eq!(query.select_from, "ORDERS");
+        assert_eq!(query.table_alias.as_deref(), Some("o"));
+        assert_eq!(query.select.len(), 2);
+        assert_eq!(query.joins.len(), 1);
+        assert_eq!(query.joins[0].join_type, JoinType::LeftJoin as i32);
+        assert_eq!(query.joins[0].table_alias.as_deref(), Some("i"));
+        assert!(query.filter.is_some());
+        assert_eq!(query.group_by.len(), 1);
+        assert_eq!(query.order_by.len(), 1);
+        assert_eq!(query.limit, Some(5));
+
+        let query = parse_all("from ORDERS", Parser::statement).unwrap();
+        assert!(query.select.is_empty());
+        assert!(parse_all("select from ORDERS", Parser::statement).is_err());
+        assert!(parse_all("select ID from ORDERS limit -1", Parser::statement).is_err());
+        assert_eq!(quote_name("from"), "\"from\"");
+        assert_eq!(quote_name("ORDER_ID"), "ORDER_ID");
+    }
+}
@@ -1,6 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+/// Information sufficient to find a table within a source.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SourcePath {
     #[serde(rename = "bigquery")]
@@ -11,6 +13,24 @@ pub enum SourcePath {
         schema: String,
         table: String,
     },
+    Postgres {
+        schema: String,
+        table: String,
+    },
+    Databricks {
+        catalog: String,
+        schema: String,
+        table: String,
+    },
+    /// A table in a local DuckDB database, or read from Parquet files.
+    #[serde(rename = "duckdb")]
+    DuckDb {
+        /// Path of the database file or directory of Parquet files, relative to
+        /// the spec's directory unless absolute.
+        database: String,
+        schema: String,
+        table: String,
+    },
 }
 
 impl SourcePath {
@@ -18,7 +38,14 @@ impl SourcePath {
     pub fn qualified_name(&self) -> String {
         match &self {
             SourcePath::BigQuery { table } => table.to_owned(),
-            SourcePath::Snowflake { schema, table } => format!("{}.{}", schema, table),
+            SourcePath::Snowflake { schema, table }
+            | SourcePath::Postgres { schema, table }
+            | SourcePath::DuckDb { schema, table, .. } => format!("{}.{}", schema, table),
+            SourcePath::Databricks {
+                catalog,
+                schema,
+                table,
+            } => format!("{}.{}.{}", catalog, schema, table),
         }
     }
 }
@@ -35,6 +62,37 @@ pub enum AllowListItem {
         schema: Option<String>,
         table: String,
     },
+    /// Allows any table in the given PostgreSQL schema
+    PostgresSchema(String),
+    /// Allows any table with a given name, optionally constrained further to
+    /// belong in a given schema.
+    PostgresTable {
+        schema: Option<String>,
+        table: String,
+    },
+    /// Allows any table in the given Databricks catalog
+    DatabricksCatalog(String),
+    /// Allows any table in a Databricks schema with the given name, optionally
+    /// constrained further to belong in a given catalog.
+    DatabricksSchema {
+        catalog: Option<String>,
+        schema: String,
+    },
+    /// Allows any table with a given name, optionally constrained further to
+    /// belong in a given catalog and schema.
+    DatabricksTable {
+        catalog: Option<String>,
+        schema: Option<String>,
+        table: String,
+    },
+    /// Allows any table in the given schema of a local DuckDB source
+    DuckDbSchema(String),
+    /// Allows any table with a given name, optionally constrained further to
+    /// belong in a given schema.
+    DuckDbTable {
+        schema: Option<String>,
+        table: String,
+    },
 }
 
 impl AllowListItem {
@@ -48,12 +106,31 @@ impl AllowListItem {
                 schema == target
             }
 
+            (AllowListItem::PostgresSchema(target), SourcePath::Postgres { schema, .. })
+            | (AllowListItem::DuckDbSchema(target), SourcePath::DuckDb { schema, .. }) => {
+                schema == target
+            }
+
             (
                 AllowListItem::SnowflakeTable {
                     schema: target_schema,
                     table: target_table,
                 },
                 SourcePath::Snowflake { schema, table },
+            )
+            | (
+                AllowListItem::PostgresTable {
+                    schema: target_schema,
+                    table: target_table,
+                },
+                SourcePath::Postgres { schema, table },
+            )
+            | (
+                AllowListItem::DuckDbTable {
+                    schema: target_schema,
+                    table: target_table,
+                },
+                SourcePath::DuckDb { schema, table, .. },
             ) => {
                 target_schema
                     .as_ref()
@@ -61,6 +138,46 @@ impl AllowListItem {
                     && table == target_table
             }
 
+            (AllowListItem::DatabricksCatalog(target), SourcePath::Databricks { catalog, .. }) => {
+                catalog == target
+            }
+
+            (
+                AllowListItem::DatabricksSchema {
+                    catalog: target_catalog,
+                    schema: target_schema,
+                },
+                SourcePath::Databricks {
+                    catalog, schema, ..
+                },
+            ) => {
+                target_catalog
+                    .as_ref()
+                    .map_or(true, |target_catalog| catalog == target_catalog)
+                    && schema == target_schema
+            }
+
+            (
+                AllowListItem::DatabricksTable {
+                    catalog: target_catalog,
+                    schema: target_schema,
+                    table: target_table,
+                },
+                SourcePath::Databricks {
+                    catalog,
+                    schema,
+                    table,
+                },
+            ) => {
+                target_catalog
+                    .as_ref()
+                    .map_or(true, |target_catalog| catalog == target_catalog)
+                    && target_schema
+                        .as_ref()
+                        .map_or(true, |target_schema| schema == target_schema)
+                    && table == target_table
+            }
+
             _ => false,
         }
     }
@@ -43,44 +43,41 @@ namespace Dpm
 
     public override IMessage ToDpmProto()
     {
+      // Dispatch on the value's runtime type, rather than on T, so that
+      // literals of `object` type, e.g. the elements of an array field, are
+      // converted too.
       static Query.Types.Literal makeLit(T s)
       {
         var lit = new Query.Types.Literal();
-        if (typeof(T) == typeof(string))
+        switch (s)
         {
-          lit.String = s.ToString();
-        }
-        else if (typeof(T) == typeof(Int32))
-        {
-          lit.I32 = Int32.Parse(s.ToString() ?? "0");
-        }
-        else if (typeof(T) == typeof(Int64))
-        {
-          lit.I64 = Int64.Parse(s.ToString() ?? "0");
-        }
-        else if (typeof(T) == typeof(UInt32))
-        {
-          lit.Ui32 = UInt32.Parse(s.ToString() ?? "0");
-        }
-        else if (typeof(T) == typeof(UInt64))
-        {
-          lit.Ui64 = UInt64.Parse(s.ToString() ?? "0");
-        }
-        else if (typeof(T) == typeof(float))
-        {
-          lit.F32 = float.Parse(s.ToString() ?? "0");
-        }
-        else if (typeof(T) == typeof(double))
-        {
-          lit.F64 = double.Parse(s.ToString() ?? "0");
-        }
-        else if (typeof(T) == typeof(bool))
-        {
-          lit.Boolean = bool.Parse(s.ToString() ?? "false");
-        }
-        else
-        {
-          lit.Timestamp = DateTime.Parse(s.ToString() ?? "0").Ticks / TimeSpan.TicksPerMillisecond;
+          case string x:
+            lit.String = x;
+            break;
+          case Int32 x:
+            lit.I32 = x;
+            break;
+          case Int64 x:
+            lit.I64 = x;
+            break;
+          case UInt32 x:
+            lit.Ui32 = x;
+            break;
+          case UInt64 x:
+            lit.Ui64 = x;
+            break;
+          case float x:
+            lit.F32 = x;
+            break;
+          case double x:
+            lit.F64 = x;
+            break;
+          case bool x:
+            lit.Boolean = x;
+            break;
+          default:
+            lit.Timestamp = DateTime.Parse(s?.ToString() ?? "0").Ticks / TimeSpan.TicksPerMillisecond;
+            break;
         }
         return lit;
       }
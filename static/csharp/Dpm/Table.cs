@@ -25,8 +25,8 @@ namespace Dpm
     public readonly string DatasetName;
     public readonly string DatasetVersion;
     public readonly string Name;
-    private readonly FieldExpr[] Fields;
-    private DpmAgentClient? Backend;
+    protected readonly FieldExpr[] Fields;
+    protected DpmAgentClient? Backend;
 
     public readonly BooleanFieldExpr? FilterExpr;
     public readonly FieldExpr[]? Selection;
@@ -80,7 +80,7 @@ namespace Dpm
       GetOrMakeBackend();
     }
 
-    private Table Copy(
+    protected virtual Table Copy(
       string? name = null,
       FieldExpr[]? fields = null,
       BooleanFieldExpr? filterExpr = null,
@@ -269,4 +269,77 @@ namespace Dpm
       return Array.Empty<object>();
     }
   }
+
+  /// <summary>
+  ///  A table whose query results are rows of type TRow. The generated table
+  ///  classes return a Table of their `Row` record, so that
+  ///  <c>await MyTable.Select(name).Execute()</c> returns <c>MyTable.Row[]</c>.
+  /// </summary>
+  public class Table<TRow> : Table
+  {
+    public Table(
+      string packageId,
+      string datasetName,
+      string datasetVersion,
+      string name,
+      FieldExpr[] fields,
+      BooleanFieldExpr? filterExpr = null,
+      FieldExpr[]? selection = null,
+      Ordering[]? ordering = null,
+      uint? limitTo = 1_000,
+      DpmAgentClient? backend = null
+    ) : base(packageId, datasetName, datasetVersion, name, fields, filterExpr, selection, ordering, limitTo, backend)
+    {
+    }
+
+    protected override Table Copy(
+      string? name = null,
+      FieldExpr[]? fields = null,
+      BooleanFieldExpr? filterExpr = null,
+      FieldExpr[]? selection = null,
+      Ordering[]? ordering = null,
+      uint? limitTo = null)
+    {
+      return new Table<TRow>(
+        packageId: PackageId,
+        datasetName: DatasetName,
+        datasetVersion: DatasetVersion,
+        name: name ?? Name,
+        fields: fields ?? Fields,
+        filterExpr: filterExpr ?? FilterExpr,
+        selection: selection ?? Selection,
+        ordering: ordering ?? Ordering,
+        limitTo: limitTo ?? LimitTo,
+        backend: Backend
+        );
+    }
+
+    public new Table<TRow> Filter(BooleanFieldExpr expr)
+    {
+      return (Table<TRow>)base.Filter(expr);
+    }
+
+    public new Table<TRow> Select(params FieldExpr[] selection)
+    {
+      return (Table<TRow>)base.Select(selection);
+    }
+
+    public new Table<TRow> OrderBy(params (FieldExpr Field, Direction Dir)[] ordering)
+    {
+      return (Table<TRow>)base.OrderBy(ordering);
+    }
+
+    public new Table<TRow> Limit(uint n)
+    {
+      return (Table<TRow>)base.Limit(n);
+    }
+
+    /// <summary>
+    /// Returns the results from executing the query, one TRow per result entry.
+    /// </summary>
+    public new Task<TRow[]> Execute()
+    {
+      return Execute<TRow>();
+    }
+  }
 }
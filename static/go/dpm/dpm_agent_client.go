@@ -0,0 +1,308 @@
+package dpm
+
+import (
+	"context"
+	"crypto/tls"
+	"encoding/json"
+	"fmt"
+	"net/url"
+
+	"google.golang.org/grpc"
+	"google.golang.org/grpc/credentials"
+	"google.golang.org/grpc/credentials/insecure"
+	"google.golang.org/grpc/metadata"
+)
+
+var dpmAggregateOperators = map[Operator]Query_AggregateExpression_AggregateOperator{
+	OpMin:           Query_AggregateExpression_MIN,
+	OpMax:           Query_AggregateExpression_MAX,
+	OpSum:           Query_AggregateExpression_SUM,
+	OpCount:         Query_AggregateExpression_COUNT,
+	OpCountDistinct: Query_AggregateExpression_COUNT_DISTINCT,
+	OpAvg:           Query_AggregateExpression_MEAN,
+	// dpm-agent uses Ibis, which does not support distinct mean.
+	OpAvgDistinct: Query_AggregateExpression_MEAN,
+}
+
+var dpmProjectionOperators = map[Operator]Query_DerivedExpression_ProjectionOperator{
+	OpYear:      Query_DerivedExpression_YEAR,
+	OpMonth:     Query_DerivedExpression_MONTH,
+	OpDay:       Query_DerivedExpression_DAY,
+	OpDayOfWeek: Query_DerivedExpression_DAY_OF_WEEK,
+	OpWeek:      Query_DerivedExpression_WEEK,
+	OpDate:      Query_DerivedExpression_DATE,
+	OpTime:      Query_DerivedExpression_TIME,
+	OpHour:      Query_DerivedExpression_HOUR,
+	OpMinute:    Query_DerivedExpression_MINUTE,
+	OpSecond:    Query_DerivedExpression_SECOND,
+}
+
+var dpmBooleanOperators = map[Operator]Query_BooleanExpression_BooleanOperator{
+	OpAnd:       Query_BooleanExpression_AND,
+	OpOr:        Query_BooleanExpression_OR,
+	OpEq:        Query_BooleanExpression_EQ,
+	OpNeq:       Query_BooleanExpression_NEQ,
+	OpGt:        Query_BooleanExpression_GT,
+	OpGte:       Query_BooleanExpression_GTE,
+	OpLt:        Query_BooleanExpression_LT,
+	OpLte:       Query_BooleanExpression_LTE,
+	OpLike:      Query_BooleanExpression_LIKE,
+	OpIn:        Query_BooleanExpression_IN,
+	OpHasAny:    Query_BooleanExpression_HAS_ANY,
+	OpHasAll:    Query_BooleanExpression_HAS_ALL,
+	OpIsNull:    Query_BooleanExpression_IS_NULL,
+	OpIsNotNull: Query_BooleanExpression_IS_NOT_NULL,
+}
+
+func makeDpmLiteral(value any) *Query_Literal {
+	switch v := value.(type) {
+	case string:
+		return &Query_Literal{LiteralType: &Query_Literal_String_{String_: v}}
+	case bool:
+		return &Query_Literal{LiteralType: &Query_Literal_Boolean{Boolean: v}}
+	case int:
+		return &Query_Literal{LiteralType: &Query_Literal_I64{I64: int64(v)}}
+	case int64:
+		return &Query_Literal{LiteralType: &Query_Literal_I64{I64: v}}
+	case float64:
+		return &Query_Literal{LiteralType: &Query_Literal_F64{F64: v}}
+	case []any:
+		values := make([]*Query_Literal, len(v))
+		for i, x := range v {
+			values[i] = makeDpmLiteral(x)
+		}
+		return &Query_Literal{LiteralType: &Query_Literal_List_{List: &Query_Literal_List{Values: values}}}
+	default:
+		panic(fmt.Sprintf("Unexpected literal value: %v", value))
+	}
+}
+
+func makeDpmFieldReference(name string) *Query_FieldReference {
+	return &Query_FieldReference{FieldName: name}
+}
+
+func makeDpmDerivedExpression(expr *FieldExpr) *Query_DerivedExpression {
+	return &Query_DerivedExpression{
+		Op:       dpmProjectionOperators[expr.Op],
+		Argument: makeDpmExpression(expr.Operands[0]),
+	}
+}
+
+func makeDpmBooleanExpression(expr *FieldExpr) *Query_BooleanExpression {
+	arguments := make([]*Query_Expression, len(expr.Operands))
+	for i, operand := range expr.Operands {
+		arguments[i] = makeDpmExpression(operand)
+	}
+	return &Query_BooleanExpression{
+		Op:        dpmBooleanOperators[expr.Op],
+		Arguments: arguments,
+	}
+}
+
+func makeDpmExpression(expr *FieldExpr) *Query_Expression {
+	switch {
+	case expr.Op == OpIdent:
+		return &Query_Expression{ExType: &Query_Expression_Field{Field: makeDpmFieldReference(expr.Name)}}
+	case expr.Op == OpLiteral:
+		return &Query_Expression{ExType: &Query_Expression_Literal{Literal: makeDpmLiteral(expr.Value)}}
+	case projectionOperators[expr.Op]:
+		return &Query_Expression{ExType: &Query_Expression_Derived{Derived: makeDpmDerivedExpression(expr)}}
+	case aggregateOperators[expr.Op]:
+		return &Query_Expression{ExType: &Query_Expression_Aggregate{Aggregate: &Query_AggregateExpression{
+			Op:       dpmAggregateOperators[expr.Op],
+			Argument: makeDpmExpression(expr.Operands[0]),
+		}}}
+	default:
+		return &Query_Expression{ExType: &Query_Expression_Condition{Condition: makeDpmBooleanExpression(expr)}}
+	}
+}
+
+func makeDpmGroupByExpression(expr *FieldExpr) *Query_GroupByExpression {
+	switch {
+	case expr.Op == OpIdent:
+		return &Query_GroupByExpression{ExType: &Query_GroupByExpression_Field{Field: makeDpmFieldReference(expr.Name)}}
+	case projectionOperators[expr.Op]:
+		return &Query_GroupByExpression{ExType: &Query_GroupByExpression_Derived{Derived: makeDpmDerivedExpression(expr)}}
+	default:
+		panic(fmt.Sprintf("Unexpected field expression in group_by: %v", expr))
+	}
+}
+
+func makeDpmSelectExpression(expr *FieldExpr) *Query_SelectExpression {
+	selectExpr := &Query_SelectExpression{Argument: makeDpmExpression(expr)}
+	if expr.Alias != "" {
+		alias := expr.Alias
+		selectExpr.Alias = &alias
+	}
+	return selectExpr
+}
+
+func makeDpmOrderByExpression(ordering Ordering) *Query_OrderByExpression {
+	direction := Query_OrderByExpression_ASC
+	if ordering.Direction == Descending {
+		direction = Query_OrderByExpression_DESC
+	}
+	return &Query_OrderByExpression{
+		Argument:  makeDpmExpression(ordering.Expr.Expr()),
+		Direction: &direction,
+	}
+}
+
+// makeDpmAgentQuery makes a query message from the table expression to send to
+// dpm-agent.
+func makeDpmAgentQuery(t *Table) *Query {
+	query := &Query{
+		Id:         &Query_Id{IdType: &Query_Id_PackageId{PackageId: t.PackageID}},
+		SelectFrom: t.Name,
+		ClientVersion: &ClientVersion{
+			Client:         ClientVersion_GO,
+			DatasetVersion: t.DatasetVersion,
+			CodeVersion:    CodeVersion,
+		},
+	}
+
+	for _, expr := range t.selection {
+		query.Select = append(query.Select, makeDpmSelectExpression(expr))
+	}
+
+	if t.filterExpr != nil {
+		query.Filter = makeDpmBooleanExpression(t.filterExpr.Expr())
+	}
+
+	// Process any groupings defined in selection or order_by.
+	selectionNames := map[string]bool{}
+	expandedSelection := append([]*FieldExpr{}, t.selection...)
+	for _, expr := range t.selection {
+		selectionNames[expr.Name] = true
+	}
+	for _, ordering := range t.ordering {
+		if expr := ordering.Expr.Expr(); !selectionNames[expr.Name] {
+			expandedSelection = append(expandedSelection, expr)
+		}
+	}
+	hasAggregate := false
+	for _, expr := range expandedSelection {
+		hasAggregate = hasAggregate || expr.IsAggregate()
+	}
+	if hasAggregate {
+		for _, expr := range expandedSelection {
+			if !expr.IsAggregate() {
+				query.GroupBy = append(query.GroupBy, makeDpmGroupByExpression(expr))
+			}
+		}
+	}
+
+	for _, ordering := range t.ordering {
+		query.OrderBy = append(query.OrderBy, makeDpmOrderByExpression(ordering))
+	}
+
+	if t.limitTo > 0 {
+		limit := t.limitTo
+		query.Limit = &limit
+	}
+
+	return query
+}
+
+// AgentClient uses a gRPC client to compile and execute queries by using the
+// `dpm-agent` which routes the queries to the specific source specified in the
+// query's dataset.
+type AgentClient struct {
+	conn         *grpc.ClientConn
+	client       DpmAgentClient
+	dpmAuthToken string
+}
+
+// NewAgentClient returns a client for the `dpm-agent` at `dpmAgentAddress`,
+// e.g. "http://localhost:50051" or "https://agent.dpm.sh". TLS is used if and
+// only if the scheme is `https`. The connection is established on first use.
+func NewAgentClient(dpmAgentAddress, dpmAuthToken string) (*AgentClient, error) {
+	u, err := url.Parse(dpmAgentAddress)
+	if err != nil {
+		return nil, fmt.Errorf("invalid dpm-agent address %q: %w", dpmAgentAddress, err)
+	}
+
+	creds := insecure.NewCredentials()
+	port := u.Port()
+	if u.Scheme == "https" || port == "443" {
+		creds = credentials.NewTLS(&tls.Config{})
+	}
+	address := u.Host
+	if port == "" {
+		if u.Scheme == "https" {
+			address += ":443"
+		} else {
+			address += ":80"
+		}
+	}
+
+	conn, err := grpc.Dial(address, grpc.WithTransportCredentials(creds))
+	if err != nil {
+		return nil, fmt.Errorf("error connecting to dpm-agent: %w", err)
+	}
+	return &AgentClient{
+		conn:         conn,
+		client:       NewDpmAgentClient(conn),
+		dpmAuthToken: dpmAuthToken,
+	}, nil
+}
+
+// NewAgentClientFromEnv returns a client for the `dpm-agent` named by
+// DPM_AGENT_URL, authenticated with the token discovered by `GetDpmAuthToken`.
+func NewAgentClientFromEnv() (*AgentClient, error) {
+	dpmAuthToken, err := GetDpmAuthToken()
+	if err != nil {
+		return nil, err
+	}
+	return NewAgentClient(GetDpmAgentURL(), dpmAuthToken)
+}
+
+// Close closes the client's connection.
+func (c *AgentClient) Close() error {
+	return c.conn.Close()
+}
+
+func (c *AgentClient) executeQuery(ctx context.Context, query *Query) (*QueryResult, error) {
+	ctx = metadata.AppendToOutgoingContext(ctx, "dpm-auth-token", c.dpmAuthToken)
+	result, err := c.client.ExecuteQuery(ctx, query)
+	if err != nil {
+		return nil, fmt.Errorf("error executing query: %w", err)
+	}
+	return result, nil
+}
+
+// Compile compiles the table expression using dpm-agent, and returns the
+// compiled query string.
+func (c *AgentClient) Compile(ctx context.Context, t *Table) (string, error) {
+	query := makeDpmAgentQuery(t)
+	dryRun := true
+	query.DryRun = &dryRun
+	result, err := c.executeQuery(ctx, query)
+	if err != nil {
+		return "", err
+	}
+	return result.QueryString, nil
+}
+
+// Execute executes the table expression using dpm-agent, and returns the
+// deserialized results.
+func (c *AgentClient) Execute(ctx context.Context, t *Table) ([]map[string]any, error) {
+	var rows []map[string]any
+	if err := c.ExecuteInto(ctx, t, &rows); err != nil {
+		return nil, err
+	}
+	return rows, nil
+}
+
+// ExecuteInto executes the table expression using dpm-agent, and unmarshals
+// the results into `rows`, which should be a pointer to a slice.
+func (c *AgentClient) ExecuteInto(ctx context.Context, t *Table, rows any) error {
+	result, err := c.executeQuery(ctx, makeDpmAgentQuery(t))
+	if err != nil {
+		return err
+	}
+	if err := json.Unmarshal([]byte(result.JsonData), rows); err != nil {
+		return fmt.Errorf("error parsing JSON: %w", err)
+	}
+	return nil
+}
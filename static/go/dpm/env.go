@@ -0,0 +1,71 @@
+package dpm
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"runtime"
+)
+
+// GetDpmAgentURL returns the address of the `dpm-agent` to send queries to.
+func GetDpmAgentURL() string {
+	if url, ok := os.LookupEnv("DPM_AGENT_URL"); ok {
+		return url
+	}
+	return "https://agent.dpm.sh"
+}
+
+// sessionPath returns the path of the session.json file stored by `dpm login`.
+func sessionPath() (string, error) {
+	switch runtime.GOOS {
+	case "darwin":
+		home, err := os.UserHomeDir()
+		if err != nil {
+			return "", err
+		}
+		return filepath.Join(home, "Library", "Application Support", "tech.patch.dpm", "session.json"), nil
+	case "windows":
+		appData, err := os.UserConfigDir()
+		if err != nil {
+			return "", err
+		}
+		return filepath.Join(appData, "patch", "dpm", "config", "session.json"), nil
+	default:
+		configDir, err := os.UserConfigDir()
+		if err != nil {
+			return "", err
+		}
+		return filepath.Join(configDir, "dpm", "session.json"), nil
+	}
+}
+
+// GetDpmAuthToken discovers the `dpm` authentication token by inspecting:
+//  1. Environment variable DPM_AUTH_TOKEN
+//  2. The session.json file stored by `dpm login`.
+func GetDpmAuthToken() (string, error) {
+	if token, ok := os.LookupEnv("DPM_AUTH_TOKEN"); ok {
+		return token, nil
+	}
+
+	path, err := sessionPath()
+	if err != nil {
+		return "", err
+	}
+	contents, err := os.ReadFile(path)
+	if err != nil {
+		if errors.Is(err, os.ErrNotExist) {
+			return "", errors.New("failed to find DPM authentication token. Please run `dpm login`")
+		}
+		return "", fmt.Errorf("error reading access token from %s: %w", path, err)
+	}
+
+	var session struct {
+		AccessToken string `json:"access_token"`
+	}
+	if err := json.Unmarshal(contents, &session); err != nil {
+		return "", fmt.Errorf("error parsing %s: %w", path, err)
+	}
+	return session.AccessToken, nil
+}
@@ -0,0 +1,395 @@
+package dpm
+
+import "time"
+
+// Field is a field in a Table, whose values have type T. Identifies the
+// underlying DB table column by its name, or, for a derived field, the
+// projection of another field.
+type Field[T any] struct {
+	expr *FieldExpr
+	// Converts a value of the field to a literal understood by dpm-agent.
+	toLiteral func(T) any
+}
+
+func identity[T any](v T) any {
+	return v
+}
+
+// NewField returns a field named `name`, whose values have type T.
+func NewField[T any](name string) *Field[T] {
+	return &Field[T]{expr: newIdent(name), toLiteral: identity[T]}
+}
+
+func derive[U any](f *FieldExpr, op Operator, toLiteral func(U) any) *Field[U] {
+	return &Field[U]{expr: newUnary(op, f), toLiteral: toLiteral}
+}
+
+func (f *Field[T]) compare(op Operator, that T) *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newBinary(f.expr, op, newLiteral(f.toLiteral(that)))}
+}
+
+// Expr returns the underlying expression.
+func (f *Field[T]) Expr() *FieldExpr {
+	return f.expr
+}
+
+// Name is a human-readable representation of the field.
+func (f *Field[T]) Name() string {
+	return f.expr.Name
+}
+
+// As aliases this field. E.g.,
+//
+//	query := MyTable.Select(fieldWithLongName.As("shortName"))
+func (f *Field[T]) As(alias string) *Field[T] {
+	return &Field[T]{expr: f.expr.withAlias(alias), toLiteral: f.toLiteral}
+}
+
+// Max returns a `max` aggregation applied on this field.
+func (f *Field[T]) Max() *AggregateFieldExpr[T] {
+	return &AggregateFieldExpr[T]{expr: newUnary(OpMax, f.expr)}
+}
+
+// Min returns a `min` aggregation applied on this field.
+func (f *Field[T]) Min() *AggregateFieldExpr[T] {
+	return &AggregateFieldExpr[T]{expr: newUnary(OpMin, f.expr)}
+}
+
+// Sum returns a `sum` aggregation applied on this field.
+func (f *Field[T]) Sum() *AggregateFieldExpr[float64] {
+	return &AggregateFieldExpr[float64]{expr: newUnary(OpSum, f.expr)}
+}
+
+// Count returns a `count` aggregation applied on this field.
+func (f *Field[T]) Count() *AggregateFieldExpr[int64] {
+	return &AggregateFieldExpr[int64]{expr: newUnary(OpCount, f.expr)}
+}
+
+// CountDistinct returns a distinct `count` aggregation applied on this field.
+func (f *Field[T]) CountDistinct() *AggregateFieldExpr[int64] {
+	return &AggregateFieldExpr[int64]{expr: newUnary(OpCountDistinct, f.expr)}
+}
+
+// Avg returns an `average` aggregation applied on this field.
+func (f *Field[T]) Avg() *AggregateFieldExpr[float64] {
+	return &AggregateFieldExpr[float64]{expr: newUnary(OpAvg, f.expr)}
+}
+
+// AvgDistinct returns a distinct `average` aggregation applied on this field.
+func (f *Field[T]) AvgDistinct() *AggregateFieldExpr[float64] {
+	return &AggregateFieldExpr[float64]{expr: newUnary(OpAvgDistinct, f.expr)}
+}
+
+// Eq returns a boolean expression with an equality check.
+func (f *Field[T]) Eq(that T) *BooleanFieldExpr {
+	return f.compare(OpEq, that)
+}
+
+// Neq returns a boolean expression with a not equal check.
+func (f *Field[T]) Neq(that T) *BooleanFieldExpr {
+	return f.compare(OpNeq, that)
+}
+
+// Gt returns a boolean expression with greater than (>) check.
+func (f *Field[T]) Gt(that T) *BooleanFieldExpr {
+	return f.compare(OpGt, that)
+}
+
+// Gte returns a boolean expression with greater than or equal (>=) check.
+func (f *Field[T]) Gte(that T) *BooleanFieldExpr {
+	return f.compare(OpGte, that)
+}
+
+// Lt returns a boolean expression with lesser than (<) check.
+func (f *Field[T]) Lt(that T) *BooleanFieldExpr {
+	return f.compare(OpLt, that)
+}
+
+// Lte returns a boolean expression with lesser than or equal (<=) check.
+func (f *Field[T]) Lte(that T) *BooleanFieldExpr {
+	return f.compare(OpLte, that)
+}
+
+// IsIn returns a boolean expression with an array membership check. The
+// field's value must exactly match at least one entry in `values` for the
+// check to be true.
+func (f *Field[T]) IsIn(values []T) *BooleanFieldExpr {
+	literals := make([]any, len(values))
+	for i, v := range values {
+		literals[i] = f.toLiteral(v)
+	}
+	return &BooleanFieldExpr{expr: newBinary(f.expr, OpIn, newLiteral(literals))}
+}
+
+// Between returns a boolean expression that checks if the field's value is in
+// between a range (inclusive of bounds).
+func (f *Field[T]) Between(minVal, maxVal T) *BooleanFieldExpr {
+	return f.Gte(minVal).And(f.Lte(maxVal))
+}
+
+// IsNull returns a boolean expression that checks if the field is null.
+func (f *Field[T]) IsNull() *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newUnary(OpIsNull, f.expr)}
+}
+
+// IsNotNull returns a boolean expression that checks if the field is not null.
+func (f *Field[T]) IsNotNull() *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newUnary(OpIsNotNull, f.expr)}
+}
+
+// StringField is a string field. Defines additional operators that are
+// specific to strings.
+type StringField struct {
+	*Field[string]
+}
+
+// NewStringField returns a string field named `name`.
+func NewStringField(name string) *StringField {
+	return &StringField{NewField[string](name)}
+}
+
+// As aliases this field.
+func (f *StringField) As(alias string) *StringField {
+	return &StringField{f.Field.As(alias)}
+}
+
+// Like returns a boolean expression for a string `like` check.
+// See: https://en.wikibooks.org/wiki/Structured_Query_Language/Like_Predicate#LIKE
+// E.g.,
+//
+//	query := MyTable.Select(name, price).Filter(name.Like("%shirt%")).Limit(10)
+func (f *StringField) Like(pattern string) *BooleanFieldExpr {
+	return f.compare(OpLike, pattern)
+}
+
+// ArrayField is an array field. Its values are lists of scalars, which may or
+// may not be homogeneous, so it only supports the array containment operators
+// and null checks.
+type ArrayField struct {
+	expr *FieldExpr
+}
+
+// NewArrayField returns an array field named `name`.
+func NewArrayField(name string) *ArrayField {
+	return &ArrayField{expr: newIdent(name)}
+}
+
+// Expr returns the underlying expression.
+func (f *ArrayField) Expr() *FieldExpr {
+	return f.expr
+}
+
+// Name is a human-readable representation of the field.
+func (f *ArrayField) Name() string {
+	return f.expr.Name
+}
+
+// As aliases this field.
+func (f *ArrayField) As(alias string) *ArrayField {
+	return &ArrayField{expr: f.expr.withAlias(alias)}
+}
+
+// HasAny returns a boolean expression that checks if the field has any of the
+// values present in `values`. Values may be strings, bools, ints, or floats.
+func (f *ArrayField) HasAny(values ...any) *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newBinary(f.expr, OpHasAny, newLiteral(values))}
+}
+
+// HasAll returns a boolean expression that checks if the field has all the
+// values present in `values`. Values may be strings, bools, ints, or floats.
+func (f *ArrayField) HasAll(values ...any) *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newBinary(f.expr, OpHasAll, newLiteral(values))}
+}
+
+// IsNull returns a boolean expression that checks if the field is null.
+func (f *ArrayField) IsNull() *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newUnary(OpIsNull, f.expr)}
+}
+
+// IsNotNull returns a boolean expression that checks if the field is not null.
+func (f *ArrayField) IsNotNull() *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newUnary(OpIsNotNull, f.expr)}
+}
+
+func formatDate(d time.Time) any {
+	return d.Format("2006-01-02")
+}
+
+func formatTime(t time.Time) any {
+	return t.Format("15:04:05.000")
+}
+
+func formatDateTime(dt time.Time) any {
+	return dt.UTC().Format("2006-01-02T15:04:05.000Z07:00")
+}
+
+func formatInt(i int64) any {
+	return i
+}
+
+// DateField is a date field. Only the date part of its `time.Time` values is
+// significant.
+type DateField struct {
+	*Field[time.Time]
+}
+
+// NewDateField returns a date field named `name`.
+func NewDateField(name string) *DateField {
+	return &DateField{&Field[time.Time]{expr: newIdent(name), toLiteral: formatDate}}
+}
+
+// As aliases this field.
+func (f *DateField) As(alias string) *DateField {
+	return &DateField{f.Field.As(alias)}
+}
+
+// Year projects the date to its year.
+func (f *DateField) Year() *Field[int64] {
+	return derive(f.expr, OpYear, formatInt)
+}
+
+// Month projects the date to its month.
+func (f *DateField) Month() *Field[int64] {
+	return derive(f.expr, OpMonth, formatInt)
+}
+
+// Day projects the date to its day.
+func (f *DateField) Day() *Field[int64] {
+	return derive(f.expr, OpDay, formatInt)
+}
+
+// DayOfWeek projects the date to its day of week.
+func (f *DateField) DayOfWeek() *Field[int64] {
+	return derive(f.expr, OpDayOfWeek, formatInt)
+}
+
+// Week projects the date to its week of the year.
+func (f *DateField) Week() *Field[int64] {
+	return derive(f.expr, OpWeek, formatInt)
+}
+
+// Before returns a boolean expression that checks if this date is before `d`.
+func (f *DateField) Before(d time.Time) *BooleanFieldExpr {
+	return f.Lt(d)
+}
+
+// After returns a boolean expression that checks if this date is after `d`.
+func (f *DateField) After(d time.Time) *BooleanFieldExpr {
+	return f.Gt(d)
+}
+
+// DateTimeField is a date-time field.
+type DateTimeField struct {
+	*Field[time.Time]
+}
+
+// NewDateTimeField returns a date-time field named `name`.
+func NewDateTimeField(name string) *DateTimeField {
+	return &DateTimeField{&Field[time.Time]{expr: newIdent(name), toLiteral: formatDateTime}}
+}
+
+// As aliases this field.
+func (f *DateTimeField) As(alias string) *DateTimeField {
+	return &DateTimeField{f.Field.As(alias)}
+}
+
+// Year projects the date-time to its year.
+func (f *DateTimeField) Year() *Field[int64] {
+	return derive(f.expr, OpYear, formatInt)
+}
+
+// Month projects the date-time to its month.
+func (f *DateTimeField) Month() *Field[int64] {
+	return derive(f.expr, OpMonth, formatInt)
+}
+
+// Day projects the date-time to its day.
+func (f *DateTimeField) Day() *Field[int64] {
+	return derive(f.expr, OpDay, formatInt)
+}
+
+// DayOfWeek projects the date-time to its day of week.
+func (f *DateTimeField) DayOfWeek() *Field[int64] {
+	return derive(f.expr, OpDayOfWeek, formatInt)
+}
+
+// Week projects the date-time to its week of the year.
+func (f *DateTimeField) Week() *Field[int64] {
+	return derive(f.expr, OpWeek, formatInt)
+}
+
+// Hour projects the date-time to its hour.
+func (f *DateTimeField) Hour() *Field[int64] {
+	return derive(f.expr, OpHour, formatInt)
+}
+
+// Minute projects the date-time to its minute.
+func (f *DateTimeField) Minute() *Field[int64] {
+	return derive(f.expr, OpMinute, formatInt)
+}
+
+// Second projects the date-time to its second.
+func (f *DateTimeField) Second() *Field[int64] {
+	return derive(f.expr, OpSecond, formatInt)
+}
+
+// Date projects the date-time to its date.
+func (f *DateTimeField) Date() *DateField {
+	return &DateField{derive(f.expr, OpDate, formatDate)}
+}
+
+// Time projects the date-time to its time.
+func (f *DateTimeField) Time() *TimeField {
+	return &TimeField{derive(f.expr, OpTime, formatTime)}
+}
+
+// Before returns a boolean expression that checks if this date-time is before `dt`.
+func (f *DateTimeField) Before(dt time.Time) *BooleanFieldExpr {
+	return f.Lt(dt)
+}
+
+// After returns a boolean expression that checks if this date-time is after `dt`.
+func (f *DateTimeField) After(dt time.Time) *BooleanFieldExpr {
+	return f.Gt(dt)
+}
+
+// TimeField is a time field. Only the time part of its `time.Time` values is
+// significant.
+type TimeField struct {
+	*Field[time.Time]
+}
+
+// NewTimeField returns a time field named `name`.
+func NewTimeField(name string) *TimeField {
+	return &TimeField{&Field[time.Time]{expr: newIdent(name), toLiteral: formatTime}}
+}
+
+// As aliases this field.
+func (f *TimeField) As(alias string) *TimeField {
+	return &TimeField{f.Field.As(alias)}
+}
+
+// Hour projects the time to its hour.
+func (f *TimeField) Hour() *Field[int64] {
+	return derive(f.expr, OpHour, formatInt)
+}
+
+// Minute projects the time to its minute.
+func (f *TimeField) Minute() *Field[int64] {
+	return derive(f.expr, OpMinute, formatInt)
+}
+
+// Second projects the time to its second.
+func (f *TimeField) Second() *Field[int64] {
+	return derive(f.expr, OpSecond, formatInt)
+}
+
+// Before returns a boolean expression that checks if this time is before `t`.
+func (f *TimeField) Before(t time.Time) *BooleanFieldExpr {
+	return f.Lt(t)
+}
+
+// After returns a boolean expression that checks if this time is after `t`.
+func (f *TimeField) After(t time.Time) *BooleanFieldExpr {
+	return f.Gt(t)
+}
@@ -0,0 +1,185 @@
+package dpm
+
+import (
+	"fmt"
+	"strings"
+)
+
+// Operator names the operation at the root of a FieldExpr.
+type Operator string
+
+const (
+	// OpIdent refers to a table column by name.
+	OpIdent Operator = "ident"
+	// OpLiteral is a constant value.
+	OpLiteral Operator = "literal"
+
+	// Unary operators.
+	OpIsNull    Operator = "isNull"
+	OpIsNotNull Operator = "isNotNull"
+
+	// Boolean operators.
+	OpAnd  Operator = "and"
+	OpOr   Operator = "or"
+	OpEq   Operator = "eq"
+	OpNeq  Operator = "neq"
+	OpGt   Operator = "gt"
+	OpGte  Operator = "gte"
+	OpLt   Operator = "lt"
+	OpLte  Operator = "lte"
+	OpLike Operator = "like"
+	OpIn   Operator = "in"
+
+	// Array containment operators.
+	OpHasAny Operator = "hasAny"
+	OpHasAll Operator = "hasAll"
+
+	// Aggregate operators.
+	OpMin           Operator = "min"
+	OpMax           Operator = "max"
+	OpSum           Operator = "sum"
+	OpCount         Operator = "count"
+	OpCountDistinct Operator = "countDistinct"
+	OpAvg           Operator = "avg"
+	OpAvgDistinct   Operator = "avgDistinct"
+
+	// Projection operators.
+	OpYear      Operator = "year"
+	OpMonth     Operator = "month"
+	OpDay       Operator = "day"
+	OpDayOfWeek Operator = "dayOfWeek"
+	OpWeek      Operator = "week"
+	OpDate      Operator = "date"
+	OpTime      Operator = "time"
+	OpHour      Operator = "hour"
+	OpMinute    Operator = "minute"
+	OpSecond    Operator = "second"
+)
+
+var aggregateOperators = map[Operator]bool{
+	OpMin: true, OpMax: true, OpSum: true, OpCount: true, OpCountDistinct: true, OpAvg: true, OpAvgDistinct: true,
+}
+
+var projectionOperators = map[Operator]bool{
+	OpYear: true, OpMonth: true, OpDay: true, OpDayOfWeek: true, OpWeek: true,
+	OpDate: true, OpTime: true, OpHour: true, OpMinute: true, OpSecond: true,
+}
+
+// FieldExpr is a tree of expressions, each of which has an associated name.
+type FieldExpr struct {
+	// A human-readable representation of the expression.
+	Name string
+	// User-specified alias for the expression. Can be used in a `Select` and
+	// then in a subsequent `OrderBy`.
+	Alias string
+	// The operation at the root of the expression.
+	Op Operator
+	// The operands of `Op`. Empty for identifiers and literals.
+	Operands []*FieldExpr
+	// The value of a literal: a string, bool, int64, float64, or a []any of
+	// those.
+	Value any
+}
+
+// Selectable is implemented by every field and expression that may be
+// selected, filtered on, or ordered by.
+type Selectable interface {
+	Expr() *FieldExpr
+}
+
+// Expr returns the expression itself.
+func (e *FieldExpr) Expr() *FieldExpr {
+	return e
+}
+
+func (e *FieldExpr) String() string {
+	return e.Name
+}
+
+// IsAggregate reports whether the expression is an aggregation.
+func (e *FieldExpr) IsAggregate() bool {
+	return aggregateOperators[e.Op]
+}
+
+func (e *FieldExpr) withAlias(alias string) *FieldExpr {
+	aliased := *e
+	aliased.Alias = alias
+	return &aliased
+}
+
+func newIdent(name string) *FieldExpr {
+	return &FieldExpr{Name: name, Op: OpIdent}
+}
+
+func newLiteral(value any) *FieldExpr {
+	name := fmt.Sprintf("lit(%v)", value)
+	if values, ok := value.([]any); ok {
+		names := make([]string, len(values))
+		for i, v := range values {
+			names[i] = fmt.Sprintf("%v", v)
+		}
+		name = fmt.Sprintf("lit([%s])", strings.Join(names, ","))
+	}
+	return &FieldExpr{Name: name, Op: OpLiteral, Value: value}
+}
+
+func newUnary(op Operator, operand *FieldExpr) *FieldExpr {
+	return &FieldExpr{
+		Name:     fmt.Sprintf("(%s(%s))", op, operand.Name),
+		Op:       op,
+		Operands: []*FieldExpr{operand},
+	}
+}
+
+func newBinary(lhs *FieldExpr, op Operator, rhs *FieldExpr) *FieldExpr {
+	return &FieldExpr{
+		Name:     fmt.Sprintf("(%s %s %s)", lhs.Name, op, rhs.Name),
+		Op:       op,
+		Operands: []*FieldExpr{lhs, rhs},
+	}
+}
+
+// BooleanFieldExpr is a boolean condition. Can be combined with other
+// conditions using the `And`, `Or` methods.
+type BooleanFieldExpr struct {
+	expr *FieldExpr
+}
+
+// Expr returns the underlying expression.
+func (b *BooleanFieldExpr) Expr() *FieldExpr {
+	return b.expr
+}
+
+// And returns the conjunction of this condition and `that`.
+func (b *BooleanFieldExpr) And(that *BooleanFieldExpr) *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newBinary(b.expr, OpAnd, that.expr)}
+}
+
+// Or returns the disjunction of this condition and `that`.
+func (b *BooleanFieldExpr) Or(that *BooleanFieldExpr) *BooleanFieldExpr {
+	return &BooleanFieldExpr{expr: newBinary(b.expr, OpOr, that.expr)}
+}
+
+// AggregateFieldExpr is an aggregation applied to a field, e.g. `price.Avg()`,
+// whose values have type T.
+type AggregateFieldExpr[T any] struct {
+	expr *FieldExpr
+}
+
+// Expr returns the underlying expression.
+func (a *AggregateFieldExpr[T]) Expr() *FieldExpr {
+	return a.expr
+}
+
+// As aliases this aggregation. E.g.,
+//
+//	query := MyTable.Select(name, price.Avg().As("meanPrice")).
+//		OrderBy(dpm.Desc(dpm.Ref("meanPrice")))
+func (a *AggregateFieldExpr[T]) As(alias string) *AggregateFieldExpr[T] {
+	return &AggregateFieldExpr[T]{expr: a.expr.withAlias(alias)}
+}
+
+// Ref refers to a selected expression by its alias, e.g. in an `OrderBy`.
+func Ref(alias string) *FieldExpr {
+	return newIdent(alias)
+}
@@ -0,0 +1,6 @@
+package dpm
+
+// Generates the `dpm-agent` gRPC client into this package. Requires `protoc`,
+// `protoc-gen-go`, and `protoc-gen-go-grpc` to be on the PATH.
+
+//go:generate protoc -I ../proto --go_out=. --go_opt=paths=source_relative --go_opt=Mdpm_agent.proto=dpm.sh/dpm;dpm --go-grpc_out=. --go-grpc_opt=paths=source_relative --go-grpc_opt=Mdpm_agent.proto=dpm.sh/dpm;dpm ../proto/dpm_agent.proto
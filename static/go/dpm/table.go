@@ -0,0 +1,169 @@
+package dpm
+
+import "context"
+
+// Direction is the direction in which to sort by an expression.
+type Direction int
+
+const (
+	Ascending Direction = iota
+	Descending
+)
+
+// Ordering is an expression, paired with the direction in which to sort by it.
+type Ordering struct {
+	Expr      Selectable
+	Direction Direction
+}
+
+// Asc sorts by `expr` in ascending order.
+func Asc(expr Selectable) Ordering {
+	return Ordering{Expr: expr, Direction: Ascending}
+}
+
+// Desc sorts by `expr` in descending order.
+func Desc(expr Selectable) Ordering {
+	return Ordering{Expr: expr, Direction: Descending}
+}
+
+// Table is the entry point to query building. The flow:
+//  1. Starting with a generated table, `Select` columns.
+//  2. Optionally, `Filter`, `OrderBy`, `Limit`.
+//  3. Compile and/or execute the formulated query against an execution backend.
+//
+// Tables are immutable: each builder method returns a modified copy.
+//
+// N.B.: Avoid a direct instantiation of `Table`! Select from one of the
+// generated tables to obtain a Table.
+type Table struct {
+	PackageID      string
+	DatasetName    string
+	DatasetVersion string
+	Name           string
+	fields         []*FieldExpr
+
+	filterExpr *BooleanFieldExpr
+	selection  []*FieldExpr
+	ordering   []Ordering
+	limitTo    uint64
+}
+
+// NewTable returns a table with the given fields.
+func NewTable(packageID, datasetName, datasetVersion, name string, fields ...Selectable) *Table {
+	exprs := make([]*FieldExpr, len(fields))
+	for i, f := range fields {
+		exprs[i] = f.Expr()
+	}
+	return &Table{
+		PackageID:      packageID,
+		DatasetName:    datasetName,
+		DatasetVersion: datasetVersion,
+		Name:           name,
+		fields:         exprs,
+		limitTo:        1000,
+	}
+}
+
+func (t *Table) clone() *Table {
+	c := *t
+	return &c
+}
+
+// Get returns the field with the given name, or the selected expression with
+// the given alias, or nil if there is none.
+func (t *Table) Get(name string) *FieldExpr {
+	for _, f := range t.fields {
+		if f.Name == name {
+			return f
+		}
+	}
+	for _, f := range t.selection {
+		if f.Alias == name {
+			return f
+		}
+	}
+	return nil
+}
+
+// Filter sets the filter expression for the table.
+func (t *Table) Filter(expr *BooleanFieldExpr) *Table {
+	c := t.clone()
+	c.filterExpr = expr
+	return c
+}
+
+// Select sets the fields to select from the table. One may specify a mix of
+// fields, derived fields, and aggregate field expressions. E.g.,
+//
+//	query := MyTable.Select(
+//		name,
+//		saleDate.Month().As("saleMonth"),
+//		price.Avg().As("meanPrice"),
+//	).Limit(10)
+func (t *Table) Select(selection ...Selectable) *Table {
+	c := t.clone()
+	c.selection = make([]*FieldExpr, len(selection))
+	for i, s := range selection {
+		c.selection[i] = s.Expr()
+	}
+	return c
+}
+
+// OrderBy sets the table's ordering expressions with their sort direction.
+func (t *Table) OrderBy(ordering ...Ordering) *Table {
+	c := t.clone()
+	c.ordering = ordering
+	return c
+}
+
+// Limit sets the row limit on the table.
+func (t *Table) Limit(n uint64) *Table {
+	c := t.clone()
+	c.limitTo = n
+	return c
+}
+
+// ToQuery returns the `dpm-agent` query message for this table expression.
+func (t *Table) ToQuery() *Query {
+	return makeDpmAgentQuery(t)
+}
+
+// Compile compiles the table expression into a query string on its execution
+// backend. E.g., returns a Snowsql string for a table expression with a
+// Snowflake execution backend.
+func (t *Table) Compile(ctx context.Context) (string, error) {
+	client, err := NewAgentClientFromEnv()
+	if err != nil {
+		return "", err
+	}
+	defer client.Close()
+	return client.Compile(ctx, t)
+}
+
+// Execute executes the table expression on its execution backend and returns
+// the results, one map per row, keyed by column name or alias.
+func (t *Table) Execute(ctx context.Context) ([]map[string]any, error) {
+	client, err := NewAgentClientFromEnv()
+	if err != nil {
+		return nil, err
+	}
+	defer client.Close()
+	return client.Execute(ctx, t)
+}
+
+// ExecuteAs executes the table expression on its execution backend and returns
+// the results as rows of type `Row`, e.g. a generated table's row struct:
+//
+//	rows, err := dpm.ExecuteAs[tables.MyTableRow](ctx, query)
+func ExecuteAs[Row any](ctx context.Context, t *Table) ([]Row, error) {
+	client, err := NewAgentClientFromEnv()
+	if err != nil {
+		return nil, err
+	}
+	defer client.Close()
+	var rows []Row
+	if err := client.ExecuteInto(ctx, t, &rows); err != nil {
+		return nil, err
+	}
+	return rows, nil
+}
@@ -0,0 +1,4 @@
+package dpm
+
+// CodeVersion is the version of the generated code.
+const CodeVersion = "0.1.0"
@@ -0,0 +1,10 @@
+// Module used to develop the static code in isolation. Generated data
+// packages get their own go.mod, written by the Go generator.
+module dpm.sh/dev
+
+go 1.21
+
+require (
+	google.golang.org/grpc v1.58.3
+	google.golang.org/protobuf v1.31.0
+)
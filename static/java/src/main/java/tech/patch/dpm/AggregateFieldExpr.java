@@ -0,0 +1,44 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * A field expression to represent an aggregation applied on a field expression.
+ * E.g., a sum of a field can be expressed as:
+ * <pre>{@code
+ * var price = new Field<Double>("price");
+ * var totalPrice = new AggregateFieldExpr<Double>(price, Operator.SUM);
+ * }</pre>
+ */
+public class AggregateFieldExpr<T> extends FieldExpr {
+  private final FieldExpr field;
+  private final Operator op;
+
+  public AggregateFieldExpr(FieldExpr field, Operator op) {
+    super("(" + op + "(" + field.name() + "))");
+    this.field = field;
+    this.op = op;
+  }
+
+  @Override
+  public Operator operator() {
+    return op;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of(field);
+  }
+
+  /**
+   * Alias this expression. E.g.,
+   * <pre>{@code
+   * var query = MyTable.select(name, price.sum().as("totalPrice")).limit(10);
+   * }</pre>
+   */
+  public AggregateFieldExpr<T> as(String alias) {
+    var copy = new AggregateFieldExpr<T>(field, op);
+    copy.alias = alias;
+    return copy;
+  }
+}
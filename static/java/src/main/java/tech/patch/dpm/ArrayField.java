@@ -0,0 +1,59 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * An array field. Its values are lists of scalars, which may or may not be
+ * homogeneous, so it only supports the array containment operators and null
+ * checks.
+ */
+public class ArrayField extends FieldExpr {
+  public ArrayField(String name) {
+    super(name);
+  }
+
+  @Override
+  public Operator operator() {
+    return Operator.IDENT;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of(this);
+  }
+
+  /** Alias this field. */
+  public ArrayField as(String alias) {
+    var copy = new ArrayField(name);
+    copy.alias = alias;
+    return copy;
+  }
+
+  /**
+   * Returns a boolean expression that checks if the field has any of the
+   * values present in `values`. Values may be strings, booleans, integers, or
+   * floating point numbers.
+   */
+  public BinaryBooleanFieldExpr hasAny(List<?> values) {
+    return new BinaryBooleanFieldExpr(this, Operator.HAS_ANY, new LiteralField(values));
+  }
+
+  /**
+   * Returns a boolean expression that checks if the field has all the values
+   * present in `values`. Values may be strings, booleans, integers, or
+   * floating point numbers.
+   */
+  public BinaryBooleanFieldExpr hasAll(List<?> values) {
+    return new BinaryBooleanFieldExpr(this, Operator.HAS_ALL, new LiteralField(values));
+  }
+
+  /** Returns a boolean expression that checks if the field is null. */
+  public UnaryBooleanFieldExpr isNull() {
+    return new UnaryBooleanFieldExpr(this, Operator.IS_NULL);
+  }
+
+  /** Returns a boolean expression that checks if the field is not null. */
+  public UnaryBooleanFieldExpr isNotNull() {
+    return new UnaryBooleanFieldExpr(this, Operator.IS_NOT_NULL);
+  }
+}
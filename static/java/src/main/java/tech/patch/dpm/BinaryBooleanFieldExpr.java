@@ -0,0 +1,29 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * A binary boolean expression, e.g. a comparison of a field with a literal.
+ */
+public class BinaryBooleanFieldExpr extends BooleanFieldExpr {
+  private final FieldExpr field;
+  private final Operator op;
+  private final FieldExpr other;
+
+  public BinaryBooleanFieldExpr(FieldExpr field, Operator op, FieldExpr other) {
+    super("(" + field.name() + " " + op + " " + other.name() + ")");
+    this.field = field;
+    this.op = op;
+    this.other = other;
+  }
+
+  @Override
+  public Operator operator() {
+    return op;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of(field, other);
+  }
+}
@@ -0,0 +1,21 @@
+package tech.patch.dpm;
+
+/**
+ * A boolean expression. Can be combined with other boolean expressions using
+ * the `and`, `or` methods.
+ */
+public abstract class BooleanFieldExpr extends FieldExpr {
+  protected BooleanFieldExpr(String name) {
+    super(name);
+  }
+
+  /** Returns the conjunction of this expression and `that`. */
+  public BinaryBooleanFieldExpr and(BooleanFieldExpr that) {
+    return new BinaryBooleanFieldExpr(this, Operator.AND, that);
+  }
+
+  /** Returns the disjunction of this expression and `that`. */
+  public BinaryBooleanFieldExpr or(BooleanFieldExpr that) {
+    return new BinaryBooleanFieldExpr(this, Operator.OR, that);
+  }
+}
@@ -0,0 +1,53 @@
+package tech.patch.dpm;
+
+import java.time.LocalDate;
+import java.time.format.DateTimeFormatter;
+
+/**
+ * A field whose values are dates.
+ */
+public class DateField extends Field<LocalDate> {
+  public DateField(String name) {
+    super(name);
+  }
+
+  @Override
+  protected Object toLiteral(LocalDate value) {
+    return value.format(DateTimeFormatter.ISO_LOCAL_DATE);
+  }
+
+  /** Projects the date to its year. */
+  public DerivedField<Long, LocalDate> year() {
+    return new DerivedField<>(this, Operator.YEAR);
+  }
+
+  /** Projects the date to its month. */
+  public DerivedField<Long, LocalDate> month() {
+    return new DerivedField<>(this, Operator.MONTH);
+  }
+
+  /** Projects the date to its day. */
+  public DerivedField<Long, LocalDate> day() {
+    return new DerivedField<>(this, Operator.DAY);
+  }
+
+  /** Projects the date to its day of week. */
+  public DerivedField<Long, LocalDate> dayOfWeek() {
+    return new DerivedField<>(this, Operator.DAY_OF_WEEK);
+  }
+
+  /** Projects the date to its week of year. */
+  public DerivedField<Long, LocalDate> week() {
+    return new DerivedField<>(this, Operator.WEEK);
+  }
+
+  /** Returns a boolean expression that checks if this field is before `d`. */
+  public BinaryBooleanFieldExpr before(LocalDate d) {
+    return lt(d);
+  }
+
+  /** Returns a boolean expression that checks if this field is after `d`. */
+  public BinaryBooleanFieldExpr after(LocalDate d) {
+    return gt(d);
+  }
+}
@@ -0,0 +1,85 @@
+package tech.patch.dpm;
+
+import java.time.Instant;
+import java.time.LocalDate;
+import java.time.LocalTime;
+import java.time.ZoneOffset;
+import java.time.format.DateTimeFormatter;
+
+/**
+ * A field whose values are date-times.
+ */
+public class DateTimeField extends Field<Instant> {
+  private static final DateTimeFormatter FORMATTER =
+      DateTimeFormatter.ofPattern("yyyy-MM-dd'T'HH:mm:ss.SSS'Z'").withZone(ZoneOffset.UTC);
+
+  public DateTimeField(String name) {
+    super(name);
+  }
+
+  @Override
+  protected Object toLiteral(Instant value) {
+    // ISO 8601 in UTC, with millisecond precision.
+    return FORMATTER.format(value);
+  }
+
+  /** Projects the date-time to its year. */
+  public DerivedField<Long, Instant> year() {
+    return new DerivedField<>(this, Operator.YEAR);
+  }
+
+  /** Projects the date-time to its month. */
+  public DerivedField<Long, Instant> month() {
+    return new DerivedField<>(this, Operator.MONTH);
+  }
+
+  /** Projects the date-time to its day. */
+  public DerivedField<Long, Instant> day() {
+    return new DerivedField<>(this, Operator.DAY);
+  }
+
+  /** Projects the date-time to its day of week. */
+  public DerivedField<Long, Instant> dayOfWeek() {
+    return new DerivedField<>(this, Operator.DAY_OF_WEEK);
+  }
+
+  /** Projects the date-time to its week of year. */
+  public DerivedField<Long, Instant> week() {
+    return new DerivedField<>(this, Operator.WEEK);
+  }
+
+  /** Projects the date-time to its hour. */
+  public DerivedField<Long, Instant> hour() {
+    return new DerivedField<>(this, Operator.HOUR);
+  }
+
+  /** Projects the date-time to its minute. */
+  public DerivedField<Long, Instant> minute() {
+    return new DerivedField<>(this, Operator.MINUTE);
+  }
+
+  /** Projects the date-time to its second. */
+  public DerivedField<Long, Instant> second() {
+    return new DerivedField<>(this, Operator.SECOND);
+  }
+
+  /** Projects the date-time to its date. */
+  public DerivedField<LocalDate, Instant> date() {
+    return new DerivedField<>(this, Operator.DATE);
+  }
+
+  /** Projects the date-time to its time. */
+  public DerivedField<LocalTime, Instant> time() {
+    return new DerivedField<>(this, Operator.TIME);
+  }
+
+  /** Returns a boolean expression that checks if this field is before `dt`. */
+  public BinaryBooleanFieldExpr before(Instant dt) {
+    return lt(dt);
+  }
+
+  /** Returns a boolean expression that checks if this field is after `dt`. */
+  public BinaryBooleanFieldExpr after(Instant dt) {
+    return gt(dt);
+  }
+}
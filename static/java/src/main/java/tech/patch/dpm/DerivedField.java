@@ -0,0 +1,42 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * A derived field obtained by applying a projection operator.
+ * E.g.
+ * <pre>{@code
+ * var startDateTime = new DateTimeField("started_at");
+ * var startYear = new DerivedField<Long, Instant>(startDateTime, Operator.YEAR);
+ * }</pre>
+ *
+ * For getters that return derived fields, see {@link DateField#year()},
+ * {@link DateField#month()}, {@link DateField#day()}.
+ */
+public class DerivedField<T, U> extends Field<T> {
+  private final Field<U> field;
+  private final Operator op;
+
+  public DerivedField(Field<U> field, Operator op) {
+    super("(" + op + "(" + field.name() + "))");
+    this.field = field;
+    this.op = op;
+  }
+
+  @Override
+  public Operator operator() {
+    return op;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of(field);
+  }
+
+  @Override
+  public DerivedField<T, U> as(String alias) {
+    var copy = new DerivedField<T, U>(field, op);
+    copy.alias = alias;
+    return copy;
+  }
+}
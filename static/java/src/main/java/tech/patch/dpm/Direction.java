@@ -0,0 +1,9 @@
+package tech.patch.dpm;
+
+/**
+ * The direction in which to sort by an expression.
+ */
+public enum Direction {
+  ASC,
+  DESC
+}
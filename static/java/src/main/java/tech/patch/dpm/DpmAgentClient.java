@@ -0,0 +1,67 @@
+package tech.patch.dpm;
+
+import io.grpc.ManagedChannel;
+import io.grpc.ManagedChannelBuilder;
+import io.grpc.Metadata;
+import io.grpc.StatusRuntimeException;
+import io.grpc.stub.MetadataUtils;
+import java.net.URI;
+import java.util.concurrent.TimeUnit;
+import tech.patch.dpm.agent.DpmAgentGrpc;
+import tech.patch.dpm.agent.Query;
+import tech.patch.dpm.agent.QueryResult;
+
+/**
+ * Uses a gRPC client to compile and execute queries by using the `dpm-agent`
+ * which routes the queries to the specific source specified in the query's
+ * dataset.
+ */
+public class DpmAgentClient implements AutoCloseable {
+  private static final Metadata.Key<String> AUTH_TOKEN_KEY =
+      Metadata.Key.of("dpm-auth-token", Metadata.ASCII_STRING_MARSHALLER);
+
+  private final ManagedChannel channel;
+  private final DpmAgentGrpc.DpmAgentBlockingStub client;
+
+  /**
+   * Returns a client for the `dpm-agent` at `dpmAgentAddress`, e.g.
+   * "http://localhost:50051" or "https://agent.dpm.sh". TLS is used if and only
+   * if the scheme is `https`.
+   */
+  public DpmAgentClient(String dpmAgentAddress, String dpmAuthToken) {
+    var uri = URI.create(dpmAgentAddress);
+    var tls = "https".equals(uri.getScheme()) || uri.getPort() == 443;
+    var port = uri.getPort() != -1 ? uri.getPort() : (tls ? 443 : 80);
+    var builder = ManagedChannelBuilder.forAddress(uri.getHost(), port);
+    if (tls) {
+      builder.useTransportSecurity();
+    } else {
+      builder.usePlaintext();
+    }
+    channel = builder.build();
+
+    var headers = new Metadata();
+    headers.put(AUTH_TOKEN_KEY, dpmAuthToken);
+    client = DpmAgentGrpc.newBlockingStub(channel)
+        .withInterceptors(MetadataUtils.newAttachHeadersInterceptor(headers));
+  }
+
+  /** Executes the query, returning the compiled query string and its results. */
+  public QueryResult executeQuery(Query query) {
+    try {
+      return client.executeQuery(query);
+    } catch (StatusRuntimeException e) {
+      throw new DpmException("Error executing query: " + e.getStatus(), e);
+    }
+  }
+
+  /** Compiles the query, returning the compiled query string. */
+  public String compileQuery(Query query) {
+    return executeQuery(query.toBuilder().setDryRun(true).build()).getQueryString();
+  }
+
+  @Override
+  public void close() throws InterruptedException {
+    channel.shutdown().awaitTermination(5, TimeUnit.SECONDS);
+  }
+}
@@ -0,0 +1,26 @@
+package tech.patch.dpm;
+
+import java.util.concurrent.ConcurrentHashMap;
+
+/**
+ * Creates DpmAgentClient instances, one per service address.
+ */
+public final class DpmAgentClientFactory {
+  private static final ConcurrentHashMap<String, DpmAgentClient> AGENT_CLIENTS =
+      new ConcurrentHashMap<>();
+
+  private DpmAgentClientFactory() {}
+
+  public static DpmAgentClient makeClient(String dpmAgentAddress, String dpmAuthToken) {
+    return AGENT_CLIENTS.computeIfAbsent(
+        dpmAgentAddress, address -> new DpmAgentClient(address, dpmAuthToken));
+  }
+
+  /**
+   * Returns a client for the `dpm-agent` named by DPM_AGENT_URL, authenticated
+   * with the token discovered by {@link Env#getDpmAuthToken()}.
+   */
+  public static DpmAgentClient makeClient() {
+    return makeClient(Env.getDpmAgentUrl(), Env.getDpmAuthToken());
+  }
+}
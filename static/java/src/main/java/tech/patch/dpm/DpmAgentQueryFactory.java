@@ -0,0 +1,191 @@
+package tech.patch.dpm;
+
+import java.util.ArrayList;
+import java.util.HashSet;
+import java.util.List;
+import java.util.Map;
+import tech.patch.dpm.agent.ClientVersion;
+import tech.patch.dpm.agent.Query;
+
+/**
+ * Builds a dpm-agent Query from a table expression.
+ */
+public final class DpmAgentQueryFactory {
+  private static final Map<Operator, Query.AggregateExpression.AggregateOperator>
+      AGGREGATE_OPERATORS = Map.of(
+          Operator.MIN, Query.AggregateExpression.AggregateOperator.MIN,
+          Operator.MAX, Query.AggregateExpression.AggregateOperator.MAX,
+          Operator.SUM, Query.AggregateExpression.AggregateOperator.SUM,
+          Operator.COUNT, Query.AggregateExpression.AggregateOperator.COUNT,
+          Operator.COUNT_DISTINCT, Query.AggregateExpression.AggregateOperator.COUNT_DISTINCT,
+          Operator.AVG, Query.AggregateExpression.AggregateOperator.MEAN,
+          // dpm-agent uses Ibis, which does not support distinct mean.
+          Operator.AVG_DISTINCT, Query.AggregateExpression.AggregateOperator.MEAN);
+
+  private static final Map<Operator, Query.DerivedExpression.ProjectionOperator>
+      PROJECTION_OPERATORS = Map.of(
+          Operator.YEAR, Query.DerivedExpression.ProjectionOperator.YEAR,
+          Operator.MONTH, Query.DerivedExpression.ProjectionOperator.MONTH,
+          Operator.DAY, Query.DerivedExpression.ProjectionOperator.DAY,
+          Operator.DAY_OF_WEEK, Query.DerivedExpression.ProjectionOperator.DAY_OF_WEEK,
+          Operator.WEEK, Query.DerivedExpression.ProjectionOperator.WEEK,
+          Operator.DATE, Query.DerivedExpression.ProjectionOperator.DATE,
+          Operator.TIME, Query.DerivedExpression.ProjectionOperator.TIME,
+          Operator.HOUR, Query.DerivedExpression.ProjectionOperator.HOUR,
+          Operator.MINUTE, Query.DerivedExpression.ProjectionOperator.MINUTE,
+          Operator.SECOND, Query.DerivedExpression.ProjectionOperator.SECOND);
+
+  private static final Map<Operator, Query.BooleanExpression.BooleanOperator> BOOLEAN_OPERATORS =
+      Map.ofEntries(
+          Map.entry(Operator.AND, Query.BooleanExpression.BooleanOperator.AND),
+          Map.entry(Operator.OR, Query.BooleanExpression.BooleanOperator.OR),
+          Map.entry(Operator.EQ, Query.BooleanExpression.BooleanOperator.EQ),
+          Map.entry(Operator.NEQ, Query.BooleanExpression.BooleanOperator.NEQ),
+          Map.entry(Operator.GT, Query.BooleanExpression.BooleanOperator.GT),
+          Map.entry(Operator.GTE, Query.BooleanExpression.BooleanOperator.GTE),
+          Map.entry(Operator.LT, Query.BooleanExpression.BooleanOperator.LT),
+          Map.entry(Operator.LTE, Query.BooleanExpression.BooleanOperator.LTE),
+          Map.entry(Operator.LIKE, Query.BooleanExpression.BooleanOperator.LIKE),
+          Map.entry(Operator.IN, Query.BooleanExpression.BooleanOperator.IN),
+          Map.entry(Operator.HAS_ANY, Query.BooleanExpression.BooleanOperator.HAS_ANY),
+          Map.entry(Operator.HAS_ALL, Query.BooleanExpression.BooleanOperator.HAS_ALL),
+          Map.entry(Operator.IS_NULL, Query.BooleanExpression.BooleanOperator.IS_NULL),
+          Map.entry(Operator.IS_NOT_NULL, Query.BooleanExpression.BooleanOperator.IS_NOT_NULL));
+
+  private DpmAgentQueryFactory() {}
+
+  static Query.Literal makeLiteral(Object value) {
+    var literal = Query.Literal.newBuilder();
+    if (value instanceof String s) {
+      literal.setString(s);
+    } else if (value instanceof Boolean b) {
+      literal.setBoolean(b);
+    } else if (value instanceof Integer || value instanceof Long) {
+      literal.setI64(((Number) value).longValue());
+    } else if (value instanceof Float || value instanceof Double) {
+      literal.setF64(((Number) value).doubleValue());
+    } else if (value instanceof List<?> values) {
+      var list = Query.Literal.List.newBuilder();
+      for (var v : values) {
+        list.addValues(makeLiteral(v));
+      }
+      literal.setList(list);
+    } else {
+      throw new DpmException("Unexpected literal value: " + value);
+    }
+    return literal.build();
+  }
+
+  static Query.FieldReference makeFieldReference(FieldExpr expr) {
+    return Query.FieldReference.newBuilder().setFieldName(expr.name()).build();
+  }
+
+  static Query.DerivedExpression makeDerivedExpression(FieldExpr expr) {
+    return Query.DerivedExpression.newBuilder()
+        .setOp(PROJECTION_OPERATORS.get(expr.operator()))
+        .setArgument(makeExpression(expr.operands().get(0)))
+        .build();
+  }
+
+  static Query.BooleanExpression makeBooleanExpression(FieldExpr expr) {
+    var condition =
+        Query.BooleanExpression.newBuilder().setOp(BOOLEAN_OPERATORS.get(expr.operator()));
+    for (var operand : expr.operands()) {
+      condition.addArguments(makeExpression(operand));
+    }
+    return condition.build();
+  }
+
+  static Query.Expression makeExpression(FieldExpr expr) {
+    var expression = Query.Expression.newBuilder();
+    switch (expr.operator().kind) {
+      case IDENT -> expression.setField(makeFieldReference(expr));
+      case LITERAL -> expression.setLiteral(makeLiteral(((LiteralField) expr).value()));
+      case PROJECTION -> expression.setDerived(makeDerivedExpression(expr));
+      case AGGREGATE -> expression.setAggregate(Query.AggregateExpression.newBuilder()
+          .setOp(AGGREGATE_OPERATORS.get(expr.operator()))
+          .setArgument(makeExpression(expr.operands().get(0))));
+      case UNARY, BOOLEAN -> expression.setCondition(makeBooleanExpression(expr));
+    }
+    return expression.build();
+  }
+
+  static Query.GroupByExpression makeGroupByExpression(FieldExpr expr) {
+    var groupBy = Query.GroupByExpression.newBuilder();
+    switch (expr.operator().kind) {
+      case IDENT -> groupBy.setField(makeFieldReference(expr));
+      case PROJECTION -> groupBy.setDerived(makeDerivedExpression(expr));
+      default -> throw new DpmException("Unexpected field expression in groupBy: " + expr);
+    }
+    return groupBy.build();
+  }
+
+  static Query.SelectExpression makeSelectExpression(FieldExpr expr) {
+    var select = Query.SelectExpression.newBuilder().setArgument(makeExpression(expr));
+    if (expr.alias() != null) {
+      select.setAlias(expr.alias());
+    }
+    return select.build();
+  }
+
+  static Query.OrderByExpression makeOrderByExpression(Ordering ordering) {
+    return Query.OrderByExpression.newBuilder()
+        .setArgument(makeExpression(ordering.field()))
+        .setDirection(ordering.direction() == Direction.ASC
+            ? Query.OrderByExpression.Direction.ASC
+            : Query.OrderByExpression.Direction.DESC)
+        .build();
+  }
+
+  /** Makes a query message from the table expression to send to dpm-agent. */
+  public static Query makeQuery(Table table) {
+    var query = Query.newBuilder()
+        .setId(Query.Id.newBuilder().setPackageId(table.packageId))
+        .setSelectFrom(table.name)
+        .setClientVersion(ClientVersion.newBuilder()
+            .setClient(ClientVersion.Client.JAVA)
+            .setDatasetVersion(table.datasetVersion)
+            .setCodeVersion(Version.CODE_VERSION));
+
+    for (var expr : table.selection) {
+      query.addSelect(makeSelectExpression(expr));
+    }
+
+    if (table.filterExpr != null) {
+      query.setFilter(makeBooleanExpression(table.filterExpr));
+    }
+
+    // Process any groupings defined in selection or orderBy.
+    var selectionNames = new HashSet<String>();
+    var expandedSelection = new ArrayList<>(table.selection);
+    for (var expr : table.selection) {
+      selectionNames.add(expr.name());
+    }
+    for (var ordering : table.ordering) {
+      if (!selectionNames.contains(ordering.field().name())) {
+        expandedSelection.add(ordering.field());
+      }
+    }
+    if (expandedSelection.stream().anyMatch(DpmAgentQueryFactory::isAggregate)) {
+      for (var expr : expandedSelection) {
+        if (!isAggregate(expr)) {
+          query.addGroupBy(makeGroupByExpression(expr));
+        }
+      }
+    }
+
+    for (var ordering : table.ordering) {
+      query.addOrderBy(makeOrderByExpression(ordering));
+    }
+
+    if (table.limitTo > 0) {
+      query.setLimit(table.limitTo);
+    }
+
+    return query.build();
+  }
+
+  private static boolean isAggregate(FieldExpr expr) {
+    return expr.operator().kind == Operator.Kind.AGGREGATE;
+  }
+}
@@ -0,0 +1,15 @@
+package tech.patch.dpm;
+
+/**
+ * Thrown when a query cannot be compiled or executed, or its results cannot be
+ * deserialized.
+ */
+public class DpmException extends RuntimeException {
+  public DpmException(String message) {
+    super(message);
+  }
+
+  public DpmException(String message, Throwable cause) {
+    super(message, cause);
+  }
+}
@@ -0,0 +1,62 @@
+package tech.patch.dpm;
+
+import com.fasterxml.jackson.databind.ObjectMapper;
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+
+/**
+ * Discovers the `dpm-agent` address and `dpm` authentication token from the
+ * environment.
+ */
+public final class Env {
+  private Env() {}
+
+  /** Returns the address of the `dpm-agent` to send queries to. */
+  public static String getDpmAgentUrl() {
+    var url = System.getenv("DPM_AGENT_URL");
+    return url != null ? url : "https://agent.dpm.sh";
+  }
+
+  /** Returns the path of the session.json file stored by `dpm login`. */
+  private static Path sessionPath() {
+    var home = System.getProperty("user.home");
+    var os = System.getProperty("os.name").toLowerCase();
+    if (os.contains("mac")) {
+      return Path.of(home, "Library", "Application Support", "tech.patch.dpm", "session.json");
+    } else if (os.contains("win")) {
+      return Path.of(System.getenv("APPDATA"), "patch", "dpm", "config", "session.json");
+    }
+    var configHome = System.getenv("XDG_CONFIG_HOME");
+    var configDir = configHome != null ? Path.of(configHome) : Path.of(home, ".config");
+    return configDir.resolve("dpm").resolve("session.json");
+  }
+
+  /**
+   * Discovers the `dpm` authentication token by inspecting:
+   * <ol>
+   *   <li>Environment variable DPM_AUTH_TOKEN</li>
+   *   <li>The session.json file stored by `dpm login`.</li>
+   * </ol>
+   */
+  public static String getDpmAuthToken() {
+    var token = System.getenv("DPM_AUTH_TOKEN");
+    if (token != null) {
+      return token;
+    }
+
+    var path = sessionPath();
+    try {
+      var accessToken = new ObjectMapper().readTree(Files.readString(path)).path("access_token");
+      if (!accessToken.isTextual()) {
+        throw new DpmException("Error reading access token from " + path);
+      }
+      return accessToken.asText();
+    } catch (NoSuchFileException e) {
+      throw new DpmException("Failed to find DPM authentication token. Please run `dpm login`");
+    } catch (IOException e) {
+      throw new DpmException("Error reading access token from " + path, e);
+    }
+  }
+}
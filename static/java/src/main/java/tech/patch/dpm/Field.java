@@ -0,0 +1,178 @@
+package tech.patch.dpm;
+
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * A column of a table, whose values have type T.
+ */
+public class Field<T> extends FieldExpr {
+  public Field(String name) {
+    super(name);
+  }
+
+  @Override
+  public Operator operator() {
+    return Operator.IDENT;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of(this);
+  }
+
+  /**
+   * Converts a value of this field's type to a literal that can be sent to
+   * dpm-agent. Fields whose values are not natively representable, e.g. dates,
+   * override this.
+   */
+  protected Object toLiteral(T value) {
+    return value;
+  }
+
+  /**
+   * Alias this field. E.g.,
+   * <pre>{@code
+   * var query = MyTable.select(fieldWithLongName.as("shortName"), price).limit(10);
+   * }</pre>
+   */
+  public Field<T> as(String alias) {
+    var copy = new Field<T>(name);
+    copy.alias = alias;
+    return copy;
+  }
+
+  private BinaryBooleanFieldExpr asBooleanExpr(Operator op, T that) {
+    return new BinaryBooleanFieldExpr(this, op, new LiteralField(toLiteral(that)));
+  }
+
+  private BinaryBooleanFieldExpr asBooleanExpr(Operator op, Field<T> that) {
+    return new BinaryBooleanFieldExpr(this, op, that);
+  }
+
+  /** Returns a 'max' aggregation applied to this field. */
+  public AggregateFieldExpr<T> max() {
+    return new AggregateFieldExpr<>(this, Operator.MAX);
+  }
+
+  /** Returns a 'min' aggregation applied to this field. */
+  public AggregateFieldExpr<T> min() {
+    return new AggregateFieldExpr<>(this, Operator.MIN);
+  }
+
+  /** Returns a 'sum' aggregation applied to this field. */
+  public AggregateFieldExpr<T> sum() {
+    return new AggregateFieldExpr<>(this, Operator.SUM);
+  }
+
+  /** Returns a 'count' aggregation applied to this field. */
+  public AggregateFieldExpr<Long> count() {
+    return new AggregateFieldExpr<>(this, Operator.COUNT);
+  }
+
+  /** Returns a distinct 'count' aggregation applied to this field. */
+  public AggregateFieldExpr<Long> countDistinct() {
+    return new AggregateFieldExpr<>(this, Operator.COUNT_DISTINCT);
+  }
+
+  /** Returns an 'average' aggregation applied to this field. */
+  public AggregateFieldExpr<Double> avg() {
+    return new AggregateFieldExpr<>(this, Operator.AVG);
+  }
+
+  /** Returns a distinct 'average' aggregation applied to this field. */
+  public AggregateFieldExpr<Double> avgDistinct() {
+    return new AggregateFieldExpr<>(this, Operator.AVG_DISTINCT);
+  }
+
+  /** Returns a boolean expression with an equality check. */
+  public BinaryBooleanFieldExpr eq(T that) {
+    return asBooleanExpr(Operator.EQ, that);
+  }
+
+  /** Returns a boolean expression with an equality check. */
+  public BinaryBooleanFieldExpr eq(Field<T> that) {
+    return asBooleanExpr(Operator.EQ, that);
+  }
+
+  /** Returns a boolean expression with a not equal check. */
+  public BinaryBooleanFieldExpr neq(T that) {
+    return asBooleanExpr(Operator.NEQ, that);
+  }
+
+  /** Returns a boolean expression with a not equal check. */
+  public BinaryBooleanFieldExpr neq(Field<T> that) {
+    return asBooleanExpr(Operator.NEQ, that);
+  }
+
+  /** Returns a boolean expression with greater than (>) check. */
+  public BinaryBooleanFieldExpr gt(T that) {
+    return asBooleanExpr(Operator.GT, that);
+  }
+
+  /** Returns a boolean expression with greater than (>) check. */
+  public BinaryBooleanFieldExpr gt(Field<T> that) {
+    return asBooleanExpr(Operator.GT, that);
+  }
+
+  /** Returns a boolean expression with greater than or equal (>=) check. */
+  public BinaryBooleanFieldExpr gte(T that) {
+    return asBooleanExpr(Operator.GTE, that);
+  }
+
+  /** Returns a boolean expression with greater than or equal (>=) check. */
+  public BinaryBooleanFieldExpr gte(Field<T> that) {
+    return asBooleanExpr(Operator.GTE, that);
+  }
+
+  /** Returns a boolean expression with lesser than (<) check. */
+  public BinaryBooleanFieldExpr lt(T that) {
+    return asBooleanExpr(Operator.LT, that);
+  }
+
+  /** Returns a boolean expression with lesser than (<) check. */
+  public BinaryBooleanFieldExpr lt(Field<T> that) {
+    return asBooleanExpr(Operator.LT, that);
+  }
+
+  /** Returns a boolean expression with lesser than or equal (<=) check. */
+  public BinaryBooleanFieldExpr lte(T that) {
+    return asBooleanExpr(Operator.LTE, that);
+  }
+
+  /** Returns a boolean expression with lesser than or equal (<=) check. */
+  public BinaryBooleanFieldExpr lte(Field<T> that) {
+    return asBooleanExpr(Operator.LTE, that);
+  }
+
+  /**
+   * Returns a boolean expression with an array membership check. The field's
+   * value must exactly match at least one entry in `that` for the check to be
+   * true.
+   */
+  public BinaryBooleanFieldExpr in(List<T> that) {
+    var values = new ArrayList<Object>(that.size());
+    for (var value : that) {
+      values.add(toLiteral(value));
+    }
+    return new BinaryBooleanFieldExpr(this, Operator.IN, new LiteralField(values));
+  }
+
+  /**
+   * Returns a boolean expression that checks if the field's value is in
+   * between a range (inclusive of bounds).
+   */
+  public BinaryBooleanFieldExpr between(T minVal, T maxVal) {
+    return gte(minVal).and(lte(maxVal));
+  }
+
+  /** Returns a boolean expression that checks if the field is null. */
+  public UnaryBooleanFieldExpr isNull() {
+    return new UnaryBooleanFieldExpr(this, Operator.IS_NULL);
+  }
+
+  /** Returns a boolean expression that checks if the field is not null. */
+  public UnaryBooleanFieldExpr isNotNull() {
+    return new UnaryBooleanFieldExpr(this, Operator.IS_NOT_NULL);
+  }
+}
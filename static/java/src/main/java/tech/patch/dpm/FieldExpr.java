@@ -0,0 +1,39 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * A tree of expressions, each of which has an associated name.
+ */
+public abstract class FieldExpr {
+  /** A human-readable representation of the expression. */
+  protected final String name;
+
+  /**
+   * User-specified alias for expression. Can be used in a `select` to alias a
+   * selected field.
+   */
+  protected String alias;
+
+  protected FieldExpr(String name) {
+    this.name = name;
+  }
+
+  public String name() {
+    return name;
+  }
+
+  /** Returns the alias of the expression, or null if it has none. */
+  public String alias() {
+    return alias;
+  }
+
+  @Override
+  public String toString() {
+    return name;
+  }
+
+  public abstract Operator operator();
+
+  public abstract List<FieldExpr> operands();
+}
@@ -0,0 +1,30 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * A constant that appears in a query. Its value is a `String`, `Boolean`,
+ * `Long`, `Double`, or a `List` of those.
+ */
+public class LiteralField extends FieldExpr {
+  private final Object value;
+
+  public LiteralField(Object value) {
+    super("lit(" + value + ")");
+    this.value = value;
+  }
+
+  public Object value() {
+    return value;
+  }
+
+  @Override
+  public Operator operator() {
+    return Operator.LITERAL;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of();
+  }
+}
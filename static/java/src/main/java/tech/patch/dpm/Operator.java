@@ -0,0 +1,66 @@
+package tech.patch.dpm;
+
+/**
+ * The operation at the root of a {@link FieldExpr}.
+ */
+public enum Operator {
+  IDENT(Kind.IDENT, "ident"),
+  LITERAL(Kind.LITERAL, "lit"),
+
+  IS_NULL(Kind.UNARY, "isNull"),
+  IS_NOT_NULL(Kind.UNARY, "isNotNull"),
+
+  AND(Kind.BOOLEAN, "and"),
+  OR(Kind.BOOLEAN, "or"),
+  EQ(Kind.BOOLEAN, "eq"),
+  NEQ(Kind.BOOLEAN, "neq"),
+  GT(Kind.BOOLEAN, "gt"),
+  GTE(Kind.BOOLEAN, "gte"),
+  LT(Kind.BOOLEAN, "lt"),
+  LTE(Kind.BOOLEAN, "lte"),
+  LIKE(Kind.BOOLEAN, "like"),
+  IN(Kind.BOOLEAN, "in"),
+  HAS_ANY(Kind.BOOLEAN, "hasAny"),
+  HAS_ALL(Kind.BOOLEAN, "hasAll"),
+
+  MIN(Kind.AGGREGATE, "min"),
+  MAX(Kind.AGGREGATE, "max"),
+  SUM(Kind.AGGREGATE, "sum"),
+  COUNT(Kind.AGGREGATE, "count"),
+  COUNT_DISTINCT(Kind.AGGREGATE, "countDistinct"),
+  AVG(Kind.AGGREGATE, "avg"),
+  AVG_DISTINCT(Kind.AGGREGATE, "avgDistinct"),
+
+  YEAR(Kind.PROJECTION, "year"),
+  MONTH(Kind.PROJECTION, "month"),
+  DAY(Kind.PROJECTION, "day"),
+  DAY_OF_WEEK(Kind.PROJECTION, "dayOfWeek"),
+  WEEK(Kind.PROJECTION, "week"),
+  DATE(Kind.PROJECTION, "date"),
+  TIME(Kind.PROJECTION, "time"),
+  HOUR(Kind.PROJECTION, "hour"),
+  MINUTE(Kind.PROJECTION, "minute"),
+  SECOND(Kind.PROJECTION, "second");
+
+  public enum Kind {
+    IDENT,
+    LITERAL,
+    UNARY,
+    BOOLEAN,
+    AGGREGATE,
+    PROJECTION
+  }
+
+  public final Kind kind;
+  private final String symbol;
+
+  Operator(Kind kind, String symbol) {
+    this.kind = kind;
+    this.symbol = symbol;
+  }
+
+  @Override
+  public String toString() {
+    return symbol;
+  }
+}
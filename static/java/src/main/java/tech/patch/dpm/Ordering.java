@@ -0,0 +1,16 @@
+package tech.patch.dpm;
+
+/**
+ * An expression, paired with the direction in which to sort by it.
+ */
+public record Ordering(FieldExpr field, Direction direction) {
+  /** Sorts by `field` in ascending order. */
+  public static Ordering asc(FieldExpr field) {
+    return new Ordering(field, Direction.ASC);
+  }
+
+  /** Sorts by `field` in descending order. */
+  public static Ordering desc(FieldExpr field) {
+    return new Ordering(field, Direction.DESC);
+  }
+}
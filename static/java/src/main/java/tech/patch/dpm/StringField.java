@@ -0,0 +1,20 @@
+package tech.patch.dpm;
+
+/**
+ * A field whose values are strings.
+ */
+public class StringField extends Field<String> {
+  public StringField(String name) {
+    super(name);
+  }
+
+  /**
+   * Returns a boolean expression for a pattern match, e.g.
+   * <pre>{@code
+   * var query = MyTable.select(name).filter(name.like("%joe%"));
+   * }</pre>
+   */
+  public BinaryBooleanFieldExpr like(String pattern) {
+    return new BinaryBooleanFieldExpr(this, Operator.LIKE, new LiteralField(pattern));
+  }
+}
@@ -0,0 +1,191 @@
+package tech.patch.dpm;
+
+import com.fasterxml.jackson.core.JsonProcessingException;
+import com.fasterxml.jackson.databind.ObjectMapper;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * The entry point to query building. The flow:
+ * <ol>
+ *   <li>Starting with a generated table, `select` columns.</li>
+ *   <li>Optionally, `filter`, `orderBy`, `limit`.</li>
+ *   <li>Compile and/or execute the formulated query against an execution
+ *   backend.</li>
+ * </ol>
+ * Tables are immutable: each builder method returns a modified copy.
+ *
+ * N.B.: Avoid a direct instantiation of `Table`! Select from one of the
+ * generated table classes to obtain a Table.
+ */
+public final class Table {
+  private static final ObjectMapper MAPPER = new ObjectMapper().findAndRegisterModules();
+
+  public final String packageId;
+  public final String datasetName;
+  public final String datasetVersion;
+  public final String name;
+  private final List<FieldExpr> fields;
+
+  public final BooleanFieldExpr filterExpr;
+  public final List<FieldExpr> selection;
+  public final List<Ordering> ordering;
+  public final long limitTo;
+
+  public Table(
+      String packageId,
+      String datasetName,
+      String datasetVersion,
+      String name,
+      List<FieldExpr> fields) {
+    this(packageId, datasetName, datasetVersion, name, fields, null, List.of(), List.of(), 1000);
+  }
+
+  private Table(
+      String packageId,
+      String datasetName,
+      String datasetVersion,
+      String name,
+      List<FieldExpr> fields,
+      BooleanFieldExpr filterExpr,
+      List<FieldExpr> selection,
+      List<Ordering> ordering,
+      long limitTo) {
+    this.packageId = packageId;
+    this.datasetName = datasetName;
+    this.datasetVersion = datasetVersion;
+    this.name = name;
+    this.fields = List.copyOf(fields);
+    this.filterExpr = filterExpr;
+    this.selection = List.copyOf(selection);
+    this.ordering = List.copyOf(ordering);
+    this.limitTo = limitTo;
+  }
+
+  /**
+   * Returns the field with the given name, or the selected expression with the
+   * given alias, or null if there is none.
+   */
+  public FieldExpr get(String name) {
+    for (var field : fields) {
+      if (field.name().equals(name)) {
+        return field;
+      }
+    }
+    for (var expr : selection) {
+      if (name.equals(expr.alias())) {
+        return expr;
+      }
+    }
+    return null;
+  }
+
+  /**
+   * Sets the filter expression for the table. E.g.,
+   * <pre>{@code
+   * var query = MyTable.select(name, category, saleDate.month().as("saleMonth"))
+   *     .filter(category.in(List.of("shirts", "tops")).and(saleDate.month().lt(5L)))
+   *     .limit(10);
+   * }</pre>
+   */
+  public Table filter(BooleanFieldExpr expr) {
+    return new Table(
+        packageId, datasetName, datasetVersion, name, fields, expr, selection, ordering, limitTo);
+  }
+
+  /**
+   * Sets the fields to select from the table. One may specify a mix of fields,
+   * derived fields, and aggregate field expressions. E.g.,
+   * <pre>{@code
+   * var query = MyTable.select(
+   *     name,
+   *     category,
+   *     saleDate.month().as("saleMonth"),
+   *     price.avg().as("meanPrice")
+   * ).limit(10);
+   * }</pre>
+   */
+  public Table select(FieldExpr... selection) {
+    return new Table(
+        packageId,
+        datasetName,
+        datasetVersion,
+        name,
+        fields,
+        filterExpr,
+        List.of(selection),
+        ordering,
+        limitTo);
+  }
+
+  /**
+   * Sets the table's ordering expressions with their sort direction. E.g.,
+   * <pre>{@code
+   * var query = MyTable.select(name, price.avg().as("meanPrice"))
+   *     .orderBy(Ordering.desc(MyTable.table().get("meanPrice")))
+   *     .limit(10);
+   * }</pre>
+   */
+  public Table orderBy(Ordering... ordering) {
+    return new Table(
+        packageId,
+        datasetName,
+        datasetVersion,
+        name,
+        fields,
+        filterExpr,
+        selection,
+        List.of(ordering),
+        limitTo);
+  }
+
+  /** Sets the row limit on the table. */
+  public Table limit(long n) {
+    return new Table(
+        packageId, datasetName, datasetVersion, name, fields, filterExpr, selection, ordering, n);
+  }
+
+  /**
+   * Compiles the table expression into a query string on its execution
+   * backend. E.g., returns a Snowsql string for a table expression with a
+   * Snowflake execution backend.
+   */
+  public String compile() {
+    var query = DpmAgentQueryFactory.makeQuery(this);
+    return DpmAgentClientFactory.makeClient().compileQuery(query);
+  }
+
+  /**
+   * Executes the table expression on its execution backend and returns the
+   * results, one map per row, keyed by column name or alias.
+   */
+  public List<Map<String, Object>> execute() {
+    var jsonData = executeJson();
+    try {
+      return MAPPER.readValue(jsonData, MAPPER.getTypeFactory()
+          .constructCollectionType(List.class, MAPPER.getTypeFactory()
+              .constructMapType(Map.class, String.class, Object.class)));
+    } catch (JsonProcessingException e) {
+      throw new DpmException("Error when JSON deserializing query results", e);
+    }
+  }
+
+  /**
+   * Executes the table expression on its execution backend and returns the
+   * results, deserialized into instances of `rowType`.
+   */
+  public <R> List<R> execute(Class<R> rowType) {
+    var jsonData = executeJson();
+    try {
+      return MAPPER.readValue(
+          jsonData, MAPPER.getTypeFactory().constructCollectionType(List.class, rowType));
+    } catch (JsonProcessingException e) {
+      throw new DpmException("Error when JSON deserializing query results", e);
+    }
+  }
+
+  private String executeJson() {
+    var query = DpmAgentQueryFactory.makeQuery(this);
+    return DpmAgentClientFactory.makeClient().executeQuery(query).getJsonData();
+  }
+}
@@ -0,0 +1,45 @@
+package tech.patch.dpm;
+
+import java.time.LocalTime;
+import java.time.format.DateTimeFormatter;
+
+/**
+ * A field whose values are times of day.
+ */
+public class TimeField extends Field<LocalTime> {
+  private static final DateTimeFormatter FORMATTER = DateTimeFormatter.ofPattern("HH:mm:ss.SSS");
+
+  public TimeField(String name) {
+    super(name);
+  }
+
+  @Override
+  protected Object toLiteral(LocalTime value) {
+    return value.format(FORMATTER);
+  }
+
+  /** Projects the time to its hour. */
+  public DerivedField<Long, LocalTime> hour() {
+    return new DerivedField<>(this, Operator.HOUR);
+  }
+
+  /** Projects the time to its minute. */
+  public DerivedField<Long, LocalTime> minute() {
+    return new DerivedField<>(this, Operator.MINUTE);
+  }
+
+  /** Projects the time to its second. */
+  public DerivedField<Long, LocalTime> second() {
+    return new DerivedField<>(this, Operator.SECOND);
+  }
+
+  /** Returns a boolean expression that checks if this field is before `t`. */
+  public BinaryBooleanFieldExpr before(LocalTime t) {
+    return lt(t);
+  }
+
+  /** Returns a boolean expression that checks if this field is after `t`. */
+  public BinaryBooleanFieldExpr after(LocalTime t) {
+    return gt(t);
+  }
+}
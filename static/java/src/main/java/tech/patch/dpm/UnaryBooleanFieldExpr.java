@@ -0,0 +1,32 @@
+package tech.patch.dpm;
+
+import java.util.List;
+
+/**
+ * A unary boolean expression.
+ * E.g., a null check on a field can be expressed using a UnaryBooleanFieldExpr:
+ * <pre>{@code
+ * var nameField = new StringField("name");
+ * var isNameNotNull = new UnaryBooleanFieldExpr(nameField, Operator.IS_NOT_NULL);
+ * }</pre>
+ */
+public class UnaryBooleanFieldExpr extends BooleanFieldExpr {
+  private final FieldExpr field;
+  private final Operator op;
+
+  public UnaryBooleanFieldExpr(FieldExpr field, Operator op) {
+    super("(" + op + "(" + field.name() + "))");
+    this.field = field;
+    this.op = op;
+  }
+
+  @Override
+  public Operator operator() {
+    return op;
+  }
+
+  @Override
+  public List<FieldExpr> operands() {
+    return List.of(field);
+  }
+}
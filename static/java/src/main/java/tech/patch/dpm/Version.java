@@ -0,0 +1,7 @@
+package tech.patch.dpm;
+
+public final class Version {
+  public static final String CODE_VERSION = "0.1.0";
+
+  private Version() {}
+}
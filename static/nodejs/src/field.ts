@@ -834,7 +834,12 @@ export class DateTimeField extends DateField {
   }
 }
 
-export class ArrayField<T extends Scalar> extends Field<T> {
+/**
+ * An array field. Its values are lists of scalars, which may or may not be
+ * homogeneous. Defines the array containment operators, and disallows those
+ * that only apply to scalars.
+ */
+export class ArrayField<T extends Scalar = Scalar> extends Field<T> {
   constructor(name: string){
     super(name);
   }
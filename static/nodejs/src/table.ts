@@ -10,10 +10,12 @@ export type Selector = string | FieldExpr;
  *   1. Starting with an instance of `Table`, `select` columns.
  *   2. Optionally, `filter`, `orderBy`, `limit`.
  *   3. Compile and/or execute the formulated query against an execution backend.
+ * The `Row` type parameter describes the rows returned by `execute`. Generated
+ * table classes set it to their row interface, e.g. `Table<MyTableRow>`.
  * N.B.: Avoid a direct instantiation of `Table`! select from one of the
  * generated table classes to obtain a Table.
  */
-export class Table {
+export class Table<Row extends object = object> {
   readonly packageId: string;
   readonly datasetName: string;
   readonly datasetVersion: string;
@@ -86,8 +88,8 @@ export class Table {
     selection?: FieldExpr[];
     ordering?: Ordering[];
     limitTo?: number;
-  }): Table {
-    return new Table({ ...this, ...args });
+  }): Table<Row> {
+    return new Table<Row>({ ...this, ...args });
   }
 
   private selectedFieldExpr(selector: Selector): FieldExpr {
@@ -117,7 +119,7 @@ export class Table {
    * @param expr Boolean expression to filter by.
    * @returns Copy of table with filter set.
    */
-  filter(expr: BooleanFieldExpr | UnaryBooleanFieldExpr): Table {
+  filter(expr: BooleanFieldExpr | UnaryBooleanFieldExpr): Table<Row> {
     return this.copy({ filterExpr: expr });
   }
 
@@ -137,7 +139,7 @@ export class Table {
    * @param selection Fields to select. Accepts both field expressions, or field name strings.
    * @returns Copy of table with field selection set.
    */
-  select(...selection: Selector[]): Table {
+  select(...selection: Selector[]): Table<Row> {
     let selectExprs: FieldExpr[] = selection.map((s) => {
       return this.selectedFieldExpr(s);
     });
@@ -161,7 +163,7 @@ export class Table {
    * @param ordering (selector, direction) pairs.
    * @returns Copy of table with ordering set.
    */
-  orderBy(...ordering: [Selector, 'ASC' | 'DESC'][]): Table {
+  orderBy(...ordering: [Selector, 'ASC' | 'DESC'][]): Table<Row> {
     let orderingExpr: Ordering[] = ordering.map(([sel, dir]) => {
       try {
         return [this.selectedFieldExpr(sel), dir];
@@ -185,7 +187,7 @@ export class Table {
    * @param n limit value.
    * @returns Copy of table with limit set to 'n'.
    */
-  limit(n: number): Table {
+  limit(n: number): Table<Row> {
     return this.copy({ limitTo: n });
   }
 
@@ -208,13 +210,14 @@ export class Table {
 
   /**
    * Executes the table expression on its execution backend and returns a
-   * promise that resolves to the results.
+   * promise that resolves to the results. Rows are typed as `Row` unless
+   * another row type is given, e.g. for a selection of aggregates.
    * @returns Result of executing the table expression on its execution backend.
    */
-  execute<Row extends object>(): Promise<Row[]> {
+  execute<R extends object = Row>(): Promise<R[]> {
     const backend = this.getOrMakeBackend();
     if (backend) {
-      return backend.execute(this);
+      return backend.execute<R>(this);
     }
 
     return Promise.reject(
@@ -1,4 +1,4 @@
-from typing import List, Union, Tuple, Dict, Literal
+from typing import Any, Generic, List, Mapping, Union, Tuple, Literal, TypeVar
 
 from .backends.factory import make_backend
 from .backends.interface import Backend
@@ -7,8 +7,12 @@ from .field_expr import BooleanFieldExpr, FieldExpr, UnaryBooleanFieldExpr
 Direction = Union[Literal["ASC"], Literal["DESC"]]
 Ordering = Tuple[FieldExpr, Direction]
 
+# The type of the rows returned by executing a table expression. Generated
+# tables use their row `TypedDict`, e.g. `Table[MyTableRow]`.
+Row = TypeVar("Row", bound=Mapping[str, Any])
 
-class Table:
+
+class Table(Generic[Row]):
     def __init__(
         self,
         package_id: str,
@@ -46,7 +50,7 @@ class Table:
         selection: List[FieldExpr] = None,
         ordering: List[Ordering] = None,
         limit_to: int = None,
-    ) -> "Table":
+    ) -> "Table[Row]":
         return Table(
             backend=self.backend,
             package_id=self.package_id,
@@ -88,7 +92,7 @@ class Table:
             self.backend = make_backend()
         return self.backend
 
-    def filter(self, expr: Union[BooleanFieldExpr, UnaryBooleanFieldExpr]) -> "Table":
+    def filter(self, expr: Union[BooleanFieldExpr, UnaryBooleanFieldExpr]) -> "Table[Row]":
         """
         Sets the filter expression for the table.
 
@@ -106,7 +110,7 @@ class Table:
         """
         return self.copy(filter_expr=expr)
 
-    def select(self, *selection: Union[str, FieldExpr]) -> "Table":
+    def select(self, *selection: Union[str, FieldExpr]) -> "Table[Row]":
         """
         Sets the fields to select from the table. Accepts a mix of field expressions and field name strings.
 
@@ -129,7 +133,7 @@ class Table:
 
     def order_by(
         self, *ordering: List[Tuple[Union[str, FieldExpr], Ordering]]
-    ) -> "Table":
+    ) -> "Table[Row]":
         """
         Set the table's ordering columns with their sort direction. The column selectors can be field expressions or strings that refer to table field aliases of selected fields.
 
@@ -150,7 +154,7 @@ class Table:
         ordering_expr = [(self.order_by_expr(sel), dir) for sel, dir in ordering]
         return self.copy(ordering=ordering_expr)
 
-    def limit(self, n: int) -> "Table":
+    def limit(self, n: int) -> "Table[Row]":
         """
         Sets the row limit on the table.
 
@@ -179,9 +183,10 @@ class Table:
         else:
             raise ValueError("Failed to find a suitable backend to compile this query")
 
-    async def execute(self) -> List[Dict]:
+    async def execute(self) -> List[Row]:
         """
-        Executes the table expression on its execution backend and resolves to the results.
+        Executes the table expression on its execution backend and resolves to the results,
+        one row per result entry, keyed by field name or alias.
 
         Returns:
             The result of executing the table expression on its execution backend.
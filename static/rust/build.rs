@@ -0,0 +1,9 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use a vendored `protoc`, so that building the package does not require
+    // one to be installed.
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure()
+        .build_server(false)
+        .compile(&["proto/dpm_agent.proto"], &["proto"])?;
+    Ok(())
+}
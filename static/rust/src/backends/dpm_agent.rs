@@ -0,0 +1,279 @@
+//! A `dpm-agent` client that compiles and executes queries.
+
+use serde::de::DeserializeOwned;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use super::env::{get_dpm_agent_url, get_dpm_auth_token};
+use super::proto::{
+    client_version,
+    dpm_agent_client::DpmAgentClient as DpmAgentGrpcClient,
+    query::{
+        self, aggregate_expression::AggregateOperator as DpmAggregateOperator,
+        boolean_expression::BooleanOperator as DpmBooleanOperator,
+        derived_expression::ProjectionOperator as DpmProjectionOperator, expression::ExType,
+        literal::LiteralType, order_by_expression::Direction as DpmDirection,
+    },
+    ClientVersion, Query,
+};
+use crate::error::Error;
+use crate::field_expr::{
+    AggregateOperator, BooleanFieldExpr, BooleanOperator, Expr, FieldExpr, ProjectionOperator,
+    Scalar, UnaryOperator,
+};
+use crate::table::{Direction, Ordering, Table};
+use crate::version::CODE_VERSION;
+
+fn make_dpm_literal(literal: &Scalar) -> query::Literal {
+    let literal_type = match literal {
+        Scalar::String(s) => LiteralType::String(s.to_owned()),
+        Scalar::Boolean(b) => LiteralType::Boolean(*b),
+        Scalar::Int(i) => LiteralType::I64(*i),
+        Scalar::Float(x) => LiteralType::F64(*x),
+        Scalar::List(values) => LiteralType::List(query::literal::List {
+            values: values.iter().map(make_dpm_literal).collect(),
+        }),
+    };
+    query::Literal {
+        literal_type: Some(literal_type),
+    }
+}
+
+fn make_dpm_field_reference(name: &str) -> query::FieldReference {
+    query::FieldReference {
+        field_name: name.to_owned(),
+        table_name: None,
+    }
+}
+
+fn make_dpm_aggregate_expression(
+    op: AggregateOperator,
+    field: &FieldExpr,
+) -> query::AggregateExpression {
+    let op = match op {
+        AggregateOperator::Min => DpmAggregateOperator::Min,
+        AggregateOperator::Max => DpmAggregateOperator::Max,
+        AggregateOperator::Sum => DpmAggregateOperator::Sum,
+        AggregateOperator::Count => DpmAggregateOperator::Count,
+        AggregateOperator::CountDistinct => DpmAggregateOperator::CountDistinct,
+        AggregateOperator::Avg => DpmAggregateOperator::Mean,
+        // dpm-agent uses Ibis, which does not support distinct mean.
+        AggregateOperator::AvgDistinct => DpmAggregateOperator::Mean,
+    };
+    query::AggregateExpression {
+        op: op.into(),
+        argument: Some(Box::new(make_dpm_expression(field))),
+    }
+}
+
+fn make_dpm_derived_expression(
+    op: ProjectionOperator,
+    field: &FieldExpr,
+) -> query::DerivedExpression {
+    let op = match op {
+        ProjectionOperator::Year => DpmProjectionOperator::Year,
+        ProjectionOperator::Month => DpmProjectionOperator::Month,
+        ProjectionOperator::Day => DpmProjectionOperator::Day,
+        ProjectionOperator::DayOfWeek => DpmProjectionOperator::DayOfWeek,
+        ProjectionOperator::Week => DpmProjectionOperator::Week,
+        ProjectionOperator::Date => DpmProjectionOperator::Date,
+        ProjectionOperator::Time => DpmProjectionOperator::Time,
+        ProjectionOperator::Hour => DpmProjectionOperator::Hour,
+        ProjectionOperator::Minute => DpmProjectionOperator::Minute,
+        ProjectionOperator::Second => DpmProjectionOperator::Second,
+    };
+    query::DerivedExpression {
+        op: op.into(),
+        argument: Some(Box::new(make_dpm_expression(field))),
+    }
+}
+
+fn make_dpm_expression(field: &FieldExpr) -> query::Expression {
+    let ex_type = match field.expr() {
+        Expr::Field(name) => ExType::Field(make_dpm_field_reference(name)),
+        Expr::Literal(value) => ExType::Literal(make_dpm_literal(value)),
+        Expr::Derived(op, field) => {
+            ExType::Derived(Box::new(make_dpm_derived_expression(*op, field)))
+        }
+        Expr::Aggregate(op, field) => {
+            ExType::Aggregate(Box::new(make_dpm_aggregate_expression(*op, field)))
+        }
+        Expr::Boolean(condition) => ExType::Condition(make_dpm_boolean_expression(condition)),
+    };
+    query::Expression {
+        ex_type: Some(ex_type),
+    }
+}
+
+fn make_dpm_group_by_expression(field: &FieldExpr) -> query::GroupByExpression {
+    use query::group_by_expression::ExType;
+
+    let ex_type = match field.expr() {
+        Expr::Field(name) => ExType::Field(make_dpm_field_reference(name)),
+        Expr::Derived(op, field) => ExType::Derived(make_dpm_derived_expression(*op, field)),
+        _ => panic!("Unexpected field expression in group_by: {}", field),
+    };
+    query::GroupByExpression {
+        ex_type: Some(ex_type),
+    }
+}
+
+fn make_dpm_select_expression(field: &FieldExpr) -> query::SelectExpression {
+    query::SelectExpression {
+        argument: Some(make_dpm_expression(field)),
+        alias: field.alias_name().map(String::from),
+    }
+}
+
+fn make_dpm_boolean_expression(filter: &BooleanFieldExpr) -> query::BooleanExpression {
+    let (op, arguments) = match filter {
+        BooleanFieldExpr::Binary { op, lhs, rhs } => {
+            let op = match op {
+                BooleanOperator::And => DpmBooleanOperator::And,
+                BooleanOperator::Or => DpmBooleanOperator::Or,
+                BooleanOperator::Eq => DpmBooleanOperator::Eq,
+                BooleanOperator::Neq => DpmBooleanOperator::Neq,
+                BooleanOperator::Gt => DpmBooleanOperator::Gt,
+                BooleanOperator::Gte => DpmBooleanOperator::Gte,
+                BooleanOperator::Lt => DpmBooleanOperator::Lt,
+                BooleanOperator::Lte => DpmBooleanOperator::Lte,
+                BooleanOperator::Like => DpmBooleanOperator::Like,
+                BooleanOperator::In => DpmBooleanOperator::In,
+                BooleanOperator::HasAny => DpmBooleanOperator::HasAny,
+                BooleanOperator::HasAll => DpmBooleanOperator::HasAll,
+            };
+            (op, vec![make_dpm_expression(lhs), make_dpm_expression(rhs)])
+        }
+        BooleanFieldExpr::Unary { op, operand } => {
+            let op = match op {
+                UnaryOperator::IsNull => DpmBooleanOperator::IsNull,
+                UnaryOperator::IsNotNull => DpmBooleanOperator::IsNotNull,
+            };
+            (op, vec![make_dpm_expression(operand)])
+        }
+    };
+    query::BooleanExpression {
+        op: op.into(),
+        arguments,
+    }
+}
+
+fn make_dpm_order_by_expression((field, direction): &Ordering) -> query::OrderByExpression {
+    let direction = match direction {
+        Direction::Asc => DpmDirection::Asc,
+        Direction::Desc => DpmDirection::Desc,
+    };
+    query::OrderByExpression {
+        argument: Some(make_dpm_expression(field)),
+        direction: Some(direction.into()),
+    }
+}
+
+/// Makes a query message from the table expression to send to dpm-agent.
+pub fn make_dpm_agent_query(table: &Table) -> Query {
+    let mut dpm_agent_query = Query {
+        id: Some(query::Id {
+            id_type: Some(query::id::IdType::PackageId(table.package_id.to_owned())),
+        }),
+        client_version: Some(ClientVersion {
+            client: client_version::Client::Rust.into(),
+            dataset_version: table.dataset_version.to_owned(),
+            code_version: CODE_VERSION.to_owned(),
+        }),
+        select_from: table.name.to_owned(),
+        ..Default::default()
+    };
+
+    let selection = table.selection.as_deref().unwrap_or_default();
+    let ordering = table.ordering.as_deref().unwrap_or_default();
+    dpm_agent_query.select = selection.iter().map(make_dpm_select_expression).collect();
+
+    if let Some(filter) = &table.filter_expr {
+        dpm_agent_query.filter = Some(make_dpm_boolean_expression(filter));
+    }
+
+    // Process any groupings defined in selection or order_by.
+    let selection_names: Vec<String> = selection.iter().map(|f| f.name()).collect();
+    let expanded_selection: Vec<&FieldExpr> = selection
+        .iter()
+        .chain(
+            ordering
+                .iter()
+                .map(|(f, _)| f)
+                .filter(|f| !selection_names.contains(&f.name())),
+        )
+        .collect();
+    if expanded_selection.iter().any(|f| f.is_aggregate()) {
+        dpm_agent_query.group_by = expanded_selection
+            .into_iter()
+            .filter(|f| !f.is_aggregate())
+            .map(make_dpm_group_by_expression)
+            .collect();
+    }
+
+    dpm_agent_query.order_by = ordering.iter().map(make_dpm_order_by_expression).collect();
+
+    if table.limit_to > 0 {
+        dpm_agent_query.limit = Some(table.limit_to);
+    }
+
+    dpm_agent_query
+}
+
+/// DpmAgentClient uses a gRPC client to compile and execute queries by using the
+/// `dpm-agent` which routes the queries to the specific source specified in the
+/// query's dataset.
+#[derive(Clone)]
+pub struct DpmAgentClient {
+    client: DpmAgentGrpcClient<Channel>,
+    dpm_auth_token: String,
+}
+
+impl DpmAgentClient {
+    /// Returns a client for the `dpm-agent` at `dpm_agent_service_address`, e.g.
+    /// "http://localhost:50051" or "https://agent.dpm.sh". The connection is
+    /// established on first use.
+    pub fn new(dpm_agent_service_address: &str, dpm_auth_token: &str) -> Result<Self, Error> {
+        let mut endpoint = Endpoint::from_shared(dpm_agent_service_address.to_owned())?;
+        if endpoint.uri().scheme_str() == Some("https") || endpoint.uri().port_u16() == Some(443) {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+        }
+
+        Ok(Self {
+            client: DpmAgentGrpcClient::new(endpoint.connect_lazy()),
+            dpm_auth_token: dpm_auth_token.to_owned(),
+        })
+    }
+
+    async fn execute_query(&self, query: Query) -> Result<super::proto::QueryResult, Error> {
+        let mut request = tonic::Request::new(query);
+        let token = MetadataValue::try_from(self.dpm_auth_token.as_str())
+            .map_err(|_| Error::MissingAuthToken)?;
+        request.metadata_mut().insert("dpm-auth-token", token);
+
+        let response = self.client.clone().execute_query(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Compiles the table expression using dpm-agent, and returns the compiled
+    /// query string.
+    pub async fn compile(&self, table: &Table) -> Result<String, Error> {
+        let mut query = make_dpm_agent_query(table);
+        query.dry_run = Some(true);
+        Ok(self.execute_query(query).await?.query_string)
+    }
+
+    /// Executes the table expression using dpm-agent, and returns the
+    /// deserialized results.
+    pub async fn execute<Row: DeserializeOwned>(&self, table: &Table) -> Result<Vec<Row>, Error> {
+        let result = self.execute_query(make_dpm_agent_query(table)).await?;
+        Ok(serde_json::from_str(&result.json_data)?)
+    }
+}
+
+/// Makes a client for the `dpm-agent` named by DPM_AGENT_URL, authenticated
+/// with the token discovered by `get_dpm_auth_token`.
+pub fn make_client() -> Result<DpmAgentClient, Error> {
+    let dpm_auth_token = get_dpm_auth_token().ok_or(Error::MissingAuthToken)?;
+    DpmAgentClient::new(&get_dpm_agent_url(), &dpm_auth_token)
+}
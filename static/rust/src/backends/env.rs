@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use serde::Deserialize;
+
+/// Returns the address of the `dpm-agent` to send queries to.
+pub fn get_dpm_agent_url() -> String {
+    std::env::var("DPM_AGENT_URL").unwrap_or_else(|_| "https://agent.dpm.sh".into())
+}
+
+/// Discovers the `dpm` authentication token by inspecting:
+/// 1. Environment variable DPM_AUTH_TOKEN
+/// 2. The session.json file stored by `dpm login`.
+pub fn get_dpm_auth_token() -> Option<String> {
+    if let Ok(token) = std::env::var("DPM_AUTH_TOKEN") {
+        return Some(token);
+    }
+
+    #[derive(Deserialize)]
+    struct Session {
+        access_token: String,
+    }
+
+    let session_path: PathBuf = ProjectDirs::from("tech", "patch", "dpm")?
+        .config_dir()
+        .join("session.json");
+    let session = match std::fs::read_to_string(&session_path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!(
+                "error reading access token from {}: {}",
+                session_path.display(),
+                e
+            );
+            return None;
+        }
+    };
+    serde_json::from_str::<Session>(&session)
+        .ok()
+        .map(|s| s.access_token)
+}
@@ -0,0 +1,9 @@
+//! Execution backends.
+
+pub mod dpm_agent;
+pub mod env;
+
+/// Messages and client generated from `dpm_agent.proto`.
+pub mod proto {
+    tonic::include_proto!("dpm_agent");
+}
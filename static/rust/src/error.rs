@@ -0,0 +1,56 @@
+use std::fmt::Display;
+
+/// Errors that may occur while compiling or executing a query.
+#[derive(Debug)]
+pub enum Error {
+    /// No DPM authentication token could be found.
+    MissingAuthToken,
+    /// The `dpm-agent` address is invalid, or a connection to it failed.
+    Transport(tonic::transport::Error),
+    /// `dpm-agent` returned an error.
+    Status(Box<tonic::Status>),
+    /// The query results could not be deserialized.
+    Json(serde_json::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingAuthToken => {
+                f.write_str("Failed to find DPM authentication token. Please run `dpm login`")
+            }
+            Error::Transport(e) => write!(f, "Error connecting to dpm-agent: {e}"),
+            Error::Status(s) => write!(f, "Error executing query: {s}"),
+            Error::Json(e) => write!(f, "Error parsing JSON: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::MissingAuthToken => None,
+            Error::Transport(e) => Some(e),
+            Error::Status(s) => Some(s.as_ref()),
+            Error::Json(e) => Some(e),
+        }
+    }
+}
+
+impl From<tonic::transport::Error> for Error {
+    fn from(value: tonic::transport::Error) -> Self {
+        Error::Transport(value)
+    }
+}
+
+impl From<tonic::Status> for Error {
+    fn from(value: tonic::Status) -> Self {
+        Error::Status(Box::new(value))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Json(value)
+    }
+}
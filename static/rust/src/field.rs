@@ -0,0 +1,396 @@
+//! Typed fields of a `Table`.
+
+use std::marker::PhantomData;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+use crate::field_expr::{
+    AggregateFieldExpr, AggregateOperator, BooleanFieldExpr, BooleanOperator, Expr, FieldExpr,
+    Operand, ProjectionOperator, Scalar, UnaryOperator, Value,
+};
+
+/// A field in a `Table`, whose values have type `T`. Identifies the underlying
+/// DB table column by its name, or, for a derived field, the projection of
+/// another field.
+#[derive(Clone, Debug)]
+pub struct Field<T> {
+    expr: FieldExpr,
+    _type: PhantomData<T>,
+}
+
+/// A string field. Defines additional operators that are specific to strings.
+pub type StringField = Field<String>;
+/// A date field.
+pub type DateField = Field<NaiveDate>;
+/// A date-time field, with values on the UTC time scale.
+pub type DateTimeField = Field<DateTime<Utc>>;
+/// A time field.
+pub type TimeField = Field<NaiveTime>;
+/// A field derived by applying a projection operator to another field.
+pub type DerivedField<T> = Field<T>;
+
+/// An array field. Its values are lists of scalars, which may or may not be
+/// homogeneous, so it only supports the array containment and null checks.
+#[derive(Clone, Debug)]
+pub struct ArrayField {
+    expr: FieldExpr,
+}
+
+impl<T> Field<T> {
+    pub fn new(name: &str) -> Self {
+        Self::from_expr(FieldExpr::new(Expr::Field(name.to_owned())))
+    }
+
+    fn from_expr(expr: FieldExpr) -> Self {
+        Self {
+            expr,
+            _type: PhantomData,
+        }
+    }
+
+    fn derive<U>(&self, op: ProjectionOperator) -> Field<U> {
+        Field::from_expr(FieldExpr::new(Expr::Derived(
+            op,
+            Box::new(self.expr.clone()),
+        )))
+    }
+
+    fn aggregate<U>(&self, op: AggregateOperator) -> AggregateFieldExpr<U> {
+        AggregateFieldExpr::new(op, self.expr.clone())
+    }
+
+    fn compare(&self, op: BooleanOperator, that: impl Operand<T>) -> BooleanFieldExpr {
+        BooleanFieldExpr::binary(self.expr.clone(), op, that.into_field_expr())
+    }
+
+    /// A human-readable representation of the field.
+    pub fn name(&self) -> String {
+        self.expr.name()
+    }
+
+    /// Alias this field. E.g.,
+    /// ```ignore
+    /// let query = MyTable::select([field_with_long_name.alias("shortName").into()]);
+    /// ```
+    pub fn alias(&self, alias: &str) -> Self {
+        Self::from_expr(self.expr.alias(alias))
+    }
+
+    /// Returns a `max` aggregation applied on this field.
+    pub fn max(&self) -> AggregateFieldExpr<T> {
+        self.aggregate(AggregateOperator::Max)
+    }
+
+    /// Returns a `min` aggregation applied on this field.
+    pub fn min(&self) -> AggregateFieldExpr<T> {
+        self.aggregate(AggregateOperator::Min)
+    }
+
+    /// Returns a `sum` aggregation applied on this field.
+    pub fn sum(&self) -> AggregateFieldExpr<f64> {
+        self.aggregate(AggregateOperator::Sum)
+    }
+
+    /// Returns a `count` aggregation applied on this field.
+    pub fn count(&self) -> AggregateFieldExpr<i64> {
+        self.aggregate(AggregateOperator::Count)
+    }
+
+    /// Returns a distinct `count` aggregation applied on this field.
+    pub fn count_distinct(&self) -> AggregateFieldExpr<i64> {
+        self.aggregate(AggregateOperator::CountDistinct)
+    }
+
+    /// Returns an `average` aggregation applied on this field.
+    pub fn avg(&self) -> AggregateFieldExpr<f64> {
+        self.aggregate(AggregateOperator::Avg)
+    }
+
+    /// Returns a distinct `average` aggregation applied on this field.
+    pub fn avg_distinct(&self) -> AggregateFieldExpr<f64> {
+        self.aggregate(AggregateOperator::AvgDistinct)
+    }
+
+    /// Returns a boolean expression with an equality check.
+    pub fn eq(&self, that: impl Operand<T>) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Eq, that)
+    }
+
+    /// Returns a boolean expression with a not equal check.
+    pub fn neq(&self, that: impl Operand<T>) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Neq, that)
+    }
+
+    /// Returns a boolean expression with greater than (>) check.
+    pub fn gt(&self, that: impl Operand<T>) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Gt, that)
+    }
+
+    /// Returns a boolean expression with greater than or equal (>=) check.
+    pub fn gte(&self, that: impl Operand<T>) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Gte, that)
+    }
+
+    /// Returns a boolean expression with lesser than (<) check.
+    pub fn lt(&self, that: impl Operand<T>) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Lt, that)
+    }
+
+    /// Returns a boolean expression with lesser than or equal (<=) check.
+    pub fn lte(&self, that: impl Operand<T>) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Lte, that)
+    }
+
+    /// Returns a boolean expression with an array membership check. The field's
+    /// value must exactly match at least one entry in `values` for the check to
+    /// be true.
+    pub fn is_in<V: Value<T>>(&self, values: impl IntoIterator<Item = V>) -> BooleanFieldExpr {
+        let values = values.into_iter().map(|v| v.into_scalar()).collect();
+        BooleanFieldExpr::binary(
+            self.expr.clone(),
+            BooleanOperator::In,
+            FieldExpr::new(Expr::Literal(Scalar::List(values))),
+        )
+    }
+
+    /// Returns a boolean expression that checks if the field's value is in
+    /// between a range (inclusive of bounds).
+    pub fn between(&self, min_val: impl Operand<T>, max_val: impl Operand<T>) -> BooleanFieldExpr {
+        self.gte(min_val) & self.lte(max_val)
+    }
+
+    /// Returns a boolean expression that checks if the field is null.
+    pub fn is_null(&self) -> BooleanFieldExpr {
+        BooleanFieldExpr::unary(self.expr.clone(), UnaryOperator::IsNull)
+    }
+
+    /// Returns a boolean expression that checks if the field is not null.
+    pub fn is_not_null(&self) -> BooleanFieldExpr {
+        BooleanFieldExpr::unary(self.expr.clone(), UnaryOperator::IsNotNull)
+    }
+}
+
+impl<T> Operand<T> for &Field<T> {
+    fn into_field_expr(self) -> FieldExpr {
+        self.expr.clone()
+    }
+}
+
+impl<T> From<Field<T>> for FieldExpr {
+    fn from(value: Field<T>) -> Self {
+        value.expr
+    }
+}
+
+impl<T> From<&Field<T>> for FieldExpr {
+    fn from(value: &Field<T>) -> Self {
+        value.expr.clone()
+    }
+}
+
+impl Field<String> {
+    /// Returns a boolean expression for a string `like` check.
+    /// See: https://en.wikibooks.org/wiki/Structured_Query_Language/Like_Predicate#LIKE
+    /// E.g.,
+    /// ```ignore
+    /// let query = MyTable::select([name.into(), price.into()])
+    ///     .filter(name.like("%shirt%"))
+    ///     .limit(10);
+    /// ```
+    pub fn like(&self, pattern: &str) -> BooleanFieldExpr {
+        self.compare(BooleanOperator::Like, pattern)
+    }
+}
+
+impl Field<NaiveDate> {
+    /// Projects the date to its year.
+    pub fn year(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Year)
+    }
+
+    /// Projects the date to its month.
+    pub fn month(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Month)
+    }
+
+    /// Projects the date to its day.
+    pub fn day(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Day)
+    }
+
+    /// Projects the date to its day of week.
+    pub fn day_of_week(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::DayOfWeek)
+    }
+
+    /// Projects the date to its week of the year.
+    pub fn week(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Week)
+    }
+
+    /// Returns a boolean expression that checks if this date is before `d`.
+    pub fn before(&self, d: NaiveDate) -> BooleanFieldExpr {
+        self.lt(d)
+    }
+
+    /// Returns a boolean expression that checks if this date is after `d`.
+    pub fn after(&self, d: NaiveDate) -> BooleanFieldExpr {
+        self.gt(d)
+    }
+}
+
+impl Field<DateTime<Utc>> {
+    /// Projects the date-time to its year.
+    pub fn year(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Year)
+    }
+
+    /// Projects the date-time to its month.
+    pub fn month(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Month)
+    }
+
+    /// Projects the date-time to its day.
+    pub fn day(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Day)
+    }
+
+    /// Projects the date-time to its day of week.
+    pub fn day_of_week(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::DayOfWeek)
+    }
+
+    /// Projects the date-time to its week of the year.
+    pub fn week(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Week)
+    }
+
+    /// Projects the date-time to its hour.
+    pub fn hour(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Hour)
+    }
+
+    /// Projects the date-time to its minute.
+    pub fn minute(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Minute)
+    }
+
+    /// Projects the date-time to its second.
+    pub fn second(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Second)
+    }
+
+    /// Projects the date-time to its date.
+    pub fn date(&self) -> DerivedField<NaiveDate> {
+        self.derive(ProjectionOperator::Date)
+    }
+
+    /// Projects the date-time to its time.
+    pub fn time(&self) -> DerivedField<NaiveTime> {
+        self.derive(ProjectionOperator::Time)
+    }
+
+    /// Returns a boolean expression that checks if this date-time is before `d`.
+    pub fn before(&self, d: DateTime<Utc>) -> BooleanFieldExpr {
+        self.lt(d)
+    }
+
+    /// Returns a boolean expression that checks if this date-time is after `d`.
+    pub fn after(&self, d: DateTime<Utc>) -> BooleanFieldExpr {
+        self.gt(d)
+    }
+}
+
+impl Field<NaiveTime> {
+    /// Projects the time to its hour.
+    pub fn hour(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Hour)
+    }
+
+    /// Projects the time to its minute.
+    pub fn minute(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Minute)
+    }
+
+    /// Projects the time to its second.
+    pub fn second(&self) -> DerivedField<i64> {
+        self.derive(ProjectionOperator::Second)
+    }
+
+    /// Returns a boolean expression that checks if this time is before `t`.
+    pub fn before(&self, t: NaiveTime) -> BooleanFieldExpr {
+        self.lt(t)
+    }
+
+    /// Returns a boolean expression that checks if this time is after `t`.
+    pub fn after(&self, t: NaiveTime) -> BooleanFieldExpr {
+        self.gt(t)
+    }
+}
+
+impl ArrayField {
+    pub fn new(name: &str) -> Self {
+        Self {
+            expr: FieldExpr::new(Expr::Field(name.to_owned())),
+        }
+    }
+
+    fn contains(
+        &self,
+        op: BooleanOperator,
+        values: impl IntoIterator<Item = impl Into<Scalar>>,
+    ) -> BooleanFieldExpr {
+        let values = values.into_iter().map(Into::into).collect();
+        BooleanFieldExpr::binary(
+            self.expr.clone(),
+            op,
+            FieldExpr::new(Expr::Literal(Scalar::List(values))),
+        )
+    }
+
+    /// A human-readable representation of the field.
+    pub fn name(&self) -> String {
+        self.expr.name()
+    }
+
+    /// Alias this field.
+    pub fn alias(&self, alias: &str) -> Self {
+        Self {
+            expr: self.expr.alias(alias),
+        }
+    }
+
+    /// Returns a boolean expression that checks if the field has any of the
+    /// values present in `values`.
+    pub fn has_any(&self, values: impl IntoIterator<Item = impl Into<Scalar>>) -> BooleanFieldExpr {
+        self.contains(BooleanOperator::HasAny, values)
+    }
+
+    /// Returns a boolean expression that checks if the field has all the values
+    /// present in `values`.
+    pub fn has_all(&self, values: impl IntoIterator<Item = impl Into<Scalar>>) -> BooleanFieldExpr {
+        self.contains(BooleanOperator::HasAll, values)
+    }
+
+    /// Returns a boolean expression that checks if the field is null.
+    pub fn is_null(&self) -> BooleanFieldExpr {
+        BooleanFieldExpr::unary(self.expr.clone(), UnaryOperator::IsNull)
+    }
+
+    /// Returns a boolean expression that checks if the field is not null.
+    pub fn is_not_null(&self) -> BooleanFieldExpr {
+        BooleanFieldExpr::unary(self.expr.clone(), UnaryOperator::IsNotNull)
+    }
+}
+
+impl From<ArrayField> for FieldExpr {
+    fn from(value: ArrayField) -> Self {
+        value.expr
+    }
+}
+
+impl From<&ArrayField> for FieldExpr {
+    fn from(value: &ArrayField) -> Self {
+        value.expr.clone()
+    }
+}
@@ -0,0 +1,323 @@
+//! Field expressions: the trees of fields, literals and operators that make up
+//! the selections, filters, and orderings of a query.
+
+use std::fmt::Display;
+use std::ops::{BitAnd, BitOr};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
+
+/// A literal value that appears in a query.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scalar {
+    String(String),
+    Boolean(bool),
+    Int(i64),
+    Float(f64),
+    List(Vec<Scalar>),
+}
+
+impl Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scalar::String(s) => f.write_str(s),
+            Scalar::Boolean(b) => write!(f, "{b}"),
+            Scalar::Int(i) => write!(f, "{i}"),
+            Scalar::Float(x) => write!(f, "{x}"),
+            Scalar::List(values) => {
+                let values = values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",");
+                write!(f, "[{values}]")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOperator {
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOperator {
+    And,
+    Or,
+    Eq,
+    Neq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Like,
+    In,
+    HasAny,
+    HasAll,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AggregateOperator {
+    Min,
+    Max,
+    Sum,
+    Count,
+    CountDistinct,
+    Avg,
+    AvgDistinct,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectionOperator {
+    Year,
+    Month,
+    Day,
+    DayOfWeek,
+    Week,
+    Date,
+    Time,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// The operator and operands at the root of a `FieldExpr`.
+#[derive(Clone, Debug)]
+pub enum Expr {
+    /// A reference to a table column.
+    Field(String),
+    /// A constant.
+    Literal(Scalar),
+    /// A projection of another expression, e.g. the year of a date.
+    Derived(ProjectionOperator, Box<FieldExpr>),
+    /// An aggregation of another expression, e.g. the sum of a number.
+    Aggregate(AggregateOperator, Box<FieldExpr>),
+    /// A boolean condition.
+    Boolean(BooleanFieldExpr),
+}
+
+/// A tree of expressions, each of which has an associated name.
+#[derive(Clone, Debug)]
+pub struct FieldExpr {
+    expr: Expr,
+    alias: Option<String>,
+}
+
+impl FieldExpr {
+    pub(crate) fn new(expr: Expr) -> Self {
+        Self { expr, alias: None }
+    }
+
+    /// The operator and operands of this expression.
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// A human-readable representation of the expression.
+    pub fn name(&self) -> String {
+        match &self.expr {
+            Expr::Field(name) => name.to_owned(),
+            Expr::Literal(value) => format!("lit({value})"),
+            Expr::Derived(op, field) => format!("({:?}({}))", op, field.name()),
+            Expr::Aggregate(op, field) => format!("({:?}({}))", op, field.name()),
+            Expr::Boolean(condition) => condition.name(),
+        }
+    }
+
+    /// User-specified alias for the expression, if any.
+    pub fn alias_name(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    /// Returns a copy of this expression with the given alias.
+    pub fn alias(&self, alias: &str) -> Self {
+        Self {
+            expr: self.expr.clone(),
+            alias: Some(alias.to_owned()),
+        }
+    }
+
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self.expr, Expr::Aggregate(..))
+    }
+}
+
+impl Display for FieldExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// A boolean expression. Can be combined with other boolean expressions using
+/// `and`, `or` methods, or the `&`, `|` operators.
+#[derive(Clone, Debug)]
+pub enum BooleanFieldExpr {
+    Binary {
+        op: BooleanOperator,
+        lhs: Box<FieldExpr>,
+        rhs: Box<FieldExpr>,
+    },
+    Unary {
+        op: UnaryOperator,
+        operand: Box<FieldExpr>,
+    },
+}
+
+impl BooleanFieldExpr {
+    pub(crate) fn binary(lhs: FieldExpr, op: BooleanOperator, rhs: FieldExpr) -> Self {
+        BooleanFieldExpr::Binary {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        }
+    }
+
+    pub(crate) fn unary(operand: FieldExpr, op: UnaryOperator) -> Self {
+        BooleanFieldExpr::Unary {
+            op,
+            operand: Box::new(operand),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            BooleanFieldExpr::Binary { op, lhs, rhs } => {
+                format!("({} {:?} {})", lhs.name(), op, rhs.name())
+            }
+            BooleanFieldExpr::Unary { op, operand } => format!("({:?}({}))", op, operand.name()),
+        }
+    }
+
+    /// Returns the conjunction of this expression and `that`.
+    pub fn and(self, that: BooleanFieldExpr) -> BooleanFieldExpr {
+        BooleanFieldExpr::binary(self.into(), BooleanOperator::And, that.into())
+    }
+
+    /// Returns the disjunction of this expression and `that`.
+    pub fn or(self, that: BooleanFieldExpr) -> BooleanFieldExpr {
+        BooleanFieldExpr::binary(self.into(), BooleanOperator::Or, that.into())
+    }
+}
+
+impl BitAnd for BooleanFieldExpr {
+    type Output = BooleanFieldExpr;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.and(rhs)
+    }
+}
+
+impl BitOr for BooleanFieldExpr {
+    type Output = BooleanFieldExpr;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.or(rhs)
+    }
+}
+
+impl From<BooleanFieldExpr> for FieldExpr {
+    fn from(value: BooleanFieldExpr) -> Self {
+        FieldExpr::new(Expr::Boolean(value))
+    }
+}
+
+/// An aggregation applied to a field, e.g. `price.avg()`.
+#[derive(Clone, Debug)]
+pub struct AggregateFieldExpr<T> {
+    expr: FieldExpr,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> AggregateFieldExpr<T> {
+    pub(crate) fn new(op: AggregateOperator, field: FieldExpr) -> Self {
+        Self {
+            expr: FieldExpr::new(Expr::Aggregate(op, Box::new(field))),
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Alias this aggregation. E.g.,
+    /// ```ignore
+    /// let query = MyTable::select([price.avg().alias("meanPrice").into()]);
+    /// ```
+    pub fn alias(&self, alias: &str) -> Self {
+        Self {
+            expr: self.expr.alias(alias),
+            _type: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> From<AggregateFieldExpr<T>> for FieldExpr {
+    fn from(value: AggregateFieldExpr<T>) -> Self {
+        value.expr
+    }
+}
+
+macro_rules! impl_from_for_scalar {
+    ($value_type:ty, $convert:expr) => {
+        impl From<$value_type> for Scalar {
+            fn from(value: $value_type) -> Self {
+                let convert: fn($value_type) -> Scalar = $convert;
+                convert(value)
+            }
+        }
+    };
+}
+
+// Conversions for the elements of array fields, whose type is not known.
+impl_from_for_scalar!(String, Scalar::String);
+impl_from_for_scalar!(&str, |s| Scalar::String(s.to_owned()));
+impl_from_for_scalar!(bool, Scalar::Boolean);
+impl_from_for_scalar!(i64, Scalar::Int);
+impl_from_for_scalar!(i32, |i| Scalar::Int(i.into()));
+impl_from_for_scalar!(f64, Scalar::Float);
+
+/// A value that may be compared against a field of type `T`.
+pub trait Value<T> {
+    fn into_scalar(self) -> Scalar;
+}
+
+/// A value or field that may appear on the right-hand side of a comparison
+/// with a field of type `T`.
+pub trait Operand<T> {
+    fn into_field_expr(self) -> FieldExpr;
+}
+
+macro_rules! impl_value {
+    ($field_type:ty, $value_type:ty, $convert:expr) => {
+        impl Value<$field_type> for $value_type {
+            fn into_scalar(self) -> Scalar {
+                let convert: fn($value_type) -> Scalar = $convert;
+                convert(self)
+            }
+        }
+
+        impl Operand<$field_type> for $value_type {
+            fn into_field_expr(self) -> FieldExpr {
+                FieldExpr::new(Expr::Literal(<Self as Value<$field_type>>::into_scalar(
+                    self,
+                )))
+            }
+        }
+    };
+}
+
+impl_value!(String, String, Scalar::String);
+impl_value!(String, &str, |s| Scalar::String(s.to_owned()));
+impl_value!(bool, bool, Scalar::Boolean);
+impl_value!(f64, f64, Scalar::Float);
+impl_value!(f64, i64, Scalar::Int);
+impl_value!(f64, i32, |i| Scalar::Int(i.into()));
+impl_value!(i64, i64, Scalar::Int);
+impl_value!(i64, i32, |i| Scalar::Int(i.into()));
+impl_value!(NaiveDate, NaiveDate, |d| Scalar::String(
+    d.format("%Y-%m-%d").to_string()
+));
+impl_value!(NaiveTime, NaiveTime, |t| Scalar::String(
+    t.format("%H:%M:%S%.3f").to_string()
+));
+impl_value!(DateTime<Utc>, DateTime<Utc>, |dt| Scalar::String(
+    dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+));
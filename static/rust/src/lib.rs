@@ -0,0 +1,9 @@
+//! Static code shared by all Rust data packages. Generated packages replace
+//! this file with one that also declares their `tables` module.
+
+pub mod backends;
+pub mod error;
+pub mod field;
+pub mod field_expr;
+pub mod table;
+pub mod version;
@@ -0,0 +1,130 @@
+//! The entry point to query building.
+
+use serde::de::DeserializeOwned;
+
+use crate::backends::dpm_agent::{make_client, make_dpm_agent_query};
+use crate::backends::proto::Query;
+use crate::error::Error;
+use crate::field_expr::{BooleanFieldExpr, FieldExpr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// A field expression, paired with the direction in which to sort by it.
+pub type Ordering = (FieldExpr, Direction);
+
+/// The entry point to query building. The flow:
+///   1. Starting with an instance of `Table`, `select` columns.
+///   2. Optionally, `filter`, `order_by`, `limit`.
+///   3. Compile and/or execute the formulated query against an execution backend.
+///
+/// N.B.: Avoid a direct instantiation of `Table`! select from one of the
+/// generated table structs to obtain a Table.
+#[derive(Clone, Debug)]
+pub struct Table {
+    pub package_id: String,
+    pub dataset_name: String,
+    pub dataset_version: String,
+    pub name: String,
+    fields: Vec<FieldExpr>,
+
+    pub filter_expr: Option<BooleanFieldExpr>,
+    pub selection: Option<Vec<FieldExpr>>,
+    pub ordering: Option<Vec<Ordering>>,
+    pub limit_to: u64,
+}
+
+impl Table {
+    pub fn new(
+        package_id: &str,
+        dataset_name: &str,
+        dataset_version: &str,
+        name: &str,
+        fields: Vec<FieldExpr>,
+    ) -> Self {
+        Self {
+            package_id: package_id.to_owned(),
+            dataset_name: dataset_name.to_owned(),
+            dataset_version: dataset_version.to_owned(),
+            name: name.to_owned(),
+            fields,
+            filter_expr: None,
+            selection: None,
+            ordering: None,
+            limit_to: 1_000,
+        }
+    }
+
+    /// Returns the field with the given name, or the selected expression with
+    /// the given alias.
+    pub fn get(&self, name: &str) -> Option<&FieldExpr> {
+        self.fields.iter().find(|f| f.name() == name).or_else(|| {
+            self.selection
+                .as_ref()
+                .and_then(|s| s.iter().find(|f| f.alias_name() == Some(name)))
+        })
+    }
+
+    /// Sets the filter expression for the table.
+    pub fn filter(self, expr: BooleanFieldExpr) -> Self {
+        Self {
+            filter_expr: Some(expr),
+            ..self
+        }
+    }
+
+    /// Sets the fields to select from the table. One may specify a mix of
+    /// fields, derived fields, and aggregate field expressions.
+    /// E.g.,
+    /// ```ignore
+    /// let query = MyTable::select([
+    ///     name.into(),
+    ///     sale_date.month().alias("saleMonth").into(),
+    ///     price.avg().alias("meanPrice").into(),
+    /// ])
+    /// .limit(10);
+    /// ```
+    pub fn select(self, selection: impl IntoIterator<Item = FieldExpr>) -> Self {
+        Self {
+            selection: Some(selection.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Sets the table's ordering columns with their sort direction.
+    pub fn order_by(self, ordering: impl IntoIterator<Item = Ordering>) -> Self {
+        Self {
+            ordering: Some(ordering.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Sets the row limit on the table.
+    pub fn limit(self, n: u64) -> Self {
+        Self {
+            limit_to: n,
+            ..self
+        }
+    }
+
+    /// Returns the `dpm-agent` query message for this table expression.
+    pub fn to_query(&self) -> Query {
+        make_dpm_agent_query(self)
+    }
+
+    /// Compiles the table expression into a query string on its execution backend.
+    /// E.g., returns a Snowsql string for a table expression with a Snowflake
+    /// execution backend.
+    pub async fn compile(&self) -> Result<String, Error> {
+        make_client()?.compile(self).await
+    }
+
+    /// Executes the table expression on its execution backend and returns the
+    /// results, deserialized into rows of type `Row`.
+    pub async fn execute<Row: DeserializeOwned>(&self) -> Result<Vec<Row>, Error> {
+        make_client()?.execute(self).await
+    }
+}
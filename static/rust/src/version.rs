@@ -0,0 +1,2 @@
+/// The version of the generated code.
+pub const CODE_VERSION: &str = "0.1.0";
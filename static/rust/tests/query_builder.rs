@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+use dpm::backends::proto::query::{
+    boolean_expression::BooleanOperator, expression::ExType, group_by_expression,
+    literal::LiteralType,
+};
+use dpm::field::{ArrayField, DateField, Field, StringField};
+use dpm::table::{Direction, Table};
+
+fn table() -> (Table, StringField, Field<f64>, DateField) {
+    let name = StringField::new("name");
+    let price = Field::<f64>::new("price");
+    let sale_date = DateField::new("sale_date");
+    let table = Table::new(
+        "d1d2d3",
+        "test",
+        "0.1.0",
+        "sales",
+        vec![(&name).into(), (&price).into(), (&sale_date).into()],
+    );
+    (table, name, price, sale_date)
+}
+
+#[test]
+fn select_filter_limit() {
+    let (table, name, price, sale_date) = table();
+    let query = table
+        .select([name.clone().into(), price.alias("cost").into()])
+        .filter(
+            name.like("%shirt%") & sale_date.before(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+        )
+        .limit(10)
+        .to_query();
+
+    assert_eq!(query.select_from, "sales");
+    assert_eq!(query.select.len(), 2);
+    assert_eq!(query.select[1].alias.as_deref(), Some("cost"));
+    assert_eq!(query.limit, Some(10));
+    assert!(query.group_by.is_empty());
+
+    let filter = query.filter.unwrap();
+    assert_eq!(filter.op(), BooleanOperator::And);
+    let Some(ExType::Condition(date_check)) = &filter.arguments[1].ex_type else {
+        panic!("expected a condition");
+    };
+    let Some(ExType::Literal(literal)) = &date_check.arguments[1].ex_type else {
+        panic!("expected a literal");
+    };
+    assert_eq!(
+        literal.literal_type,
+        Some(LiteralType::String("2023-01-01".into()))
+    );
+}
+
+#[test]
+fn aggregates_group_by_remaining_selection() {
+    let (table, name, price, sale_date) = table();
+    let query = table
+        .select([name.into(), price.avg().alias("meanPrice").into()])
+        .order_by([(sale_date.month().into(), Direction::Asc)])
+        .to_query();
+
+    assert_eq!(query.group_by.len(), 2);
+    assert!(matches!(
+        query.group_by[0].ex_type,
+        Some(group_by_expression::ExType::Field(_))
+    ));
+    assert!(matches!(
+        query.group_by[1].ex_type,
+        Some(group_by_expression::ExType::Derived(_))
+    ));
+}
+
+#[test]
+fn array_containment() {
+    let (table, name, ..) = table();
+    let tags = ArrayField::new("tags");
+    let query = table
+        .select([name.into()])
+        .filter(tags.has_any(["foo", "bar"]))
+        .to_query();
+
+    let filter = query.filter.unwrap();
+    assert_eq!(filter.op(), BooleanOperator::HasAny);
+    let Some(ExType::Literal(literal)) = &filter.arguments[1].ex_type else {
+        panic!("expected a literal");
+    };
+    let Some(LiteralType::List(values)) = &literal.literal_type else {
+        panic!("expected a list");
+    };
+    assert_eq!(values.values.len(), 2);
+}
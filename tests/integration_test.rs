@@ -7,11 +7,12 @@ mod integration_test {
 use std::env;
 use std::fs::{self};
 use std::path::PathBuf;
+use std::process::Command;
 
 use integration_test::nodejs::Nodejs;
 use integration_test::python::Python;
 use integration_test::target_tester::{
-    create_snowflake_source, init_snowflake, publish_snowflake_package, TargetTester,
+    create_snowflake_source, exec_cmd, init_snowflake, publish_snowflake_package, TargetTester,
 };
 
 fn startup() -> std::io::Result<()> {
@@ -30,6 +31,60 @@ fn test_python() {
     test_target(Python {});
 }
 
+/// Exercises `dpm init` and `dpm build-package` on a local DuckDB database,
+/// without DPM Cloud. Skipped when the DuckDB CLI isn't installed.
+#[test]
+fn test_duckdb() {
+    if Command::new("duckdb").arg("-version").output().is_err() {
+        eprintln!("skipping test_duckdb: duckdb not found");
+        return;
+    }
+    let curr_dir = env::current_dir().expect("Failed to get current directory");
+    startup().expect("failed to generate directories");
+    let generated_dir = curr_dir.join("tests/resources/generated");
+
+    let _ = fs::remove_file(generated_dir.join("test.duckdb"));
+    exec_cmd(
+        &generated_dir,
+        "duckdb",
+        &[
+            "test.duckdb",
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, placed DATE, total DECIMAL(10, 2), note VARCHAR);",
+        ],
+    );
+    exec_cmd(
+        &generated_dir,
+        env!("CARGO_BIN_EXE_dpm"),
+        &[
+            "init",
+            "-o",
+            "datapackage_duckdb.json",
+            "--name",
+            "test-duckdb",
+            "--source",
+            "duckdb:test.duckdb",
+            "--primary-key",
+            "main.orders=id",
+        ],
+    );
+    exec_cmd(
+        &generated_dir,
+        env!("CARGO_BIN_EXE_dpm"),
+        &[
+            "--output-format",
+            "json",
+            "build-package",
+            "-s",
+            "datapackage_duckdb.json",
+            "-o",
+            "dist_duckdb",
+            "-y",
+            "nodejs",
+        ],
+    );
+    assert!(generated_dir.join("dist_duckdb/nodejs").exists());
+}
+
 /// Integration test for a given target.
 ///
 /// Exercises the following: